@@ -1,6 +1,8 @@
 use std::sync::OnceLock;
 
+use crate::artifact::Lifecycle;
 use crate::store::{Priority, Status};
+use crate::validate::Severity;
 
 fn enabled() -> bool {
     static ENABLED: OnceLock<bool> = OnceLock::new();
@@ -52,6 +54,21 @@ pub fn bold(text: &str) -> String {
     format!("\x1b[1m{text}\x1b[0m")
 }
 
+/// Additions — created items (green)
+pub fn added(text: &str) -> String {
+    rgb(166, 227, 161, text)
+}
+
+/// Removals — deleted lines (red)
+pub fn removed(text: &str) -> String {
+    rgb(243, 139, 168, text)
+}
+
+/// Modifications — updated items (yellow)
+pub fn changed(text: &str) -> String {
+    rgb(249, 226, 175, text)
+}
+
 /// Styled arrow for transitions
 pub fn arrow() -> String {
     rgb(69, 71, 90, "→")
@@ -67,6 +84,17 @@ pub fn for_status(status: &Status, text: &str) -> String {
     }
 }
 
+/// Plan/spec lifecycle-colored text (matches ui/theme.rs lifecycle_style)
+pub fn for_lifecycle(status: Lifecycle, text: &str) -> String {
+    match status {
+        Lifecycle::Draft => dim(text),
+        Lifecycle::Approved => rgb(180, 190, 254, text), // lavender
+        Lifecycle::InProgress => rgb(137, 180, 250, text), // accent
+        Lifecycle::Done => rgb(166, 227, 161, text),     // green
+        Lifecycle::Superseded => rgb(69, 71, 90, text),  // overlay
+    }
+}
+
 /// Priority-colored text
 pub fn for_priority(priority: &Priority, text: &str) -> String {
     match priority {
@@ -90,6 +118,20 @@ pub fn for_type(type_str: &str, text: &str) -> String {
     }
 }
 
+/// Warnings (yellow)
+pub fn warning(text: &str) -> String {
+    rgb(249, 226, 175, text)
+}
+
+/// Severity label ("error"/"warning"), padded to `width` and colored
+pub fn for_severity(severity: Severity, width: usize) -> String {
+    let text = format!("{:<width$}", severity.as_str());
+    match severity {
+        Severity::Error => blocked(&text),
+        Severity::Warning => warning(&text),
+    }
+}
+
 /// Blocked indicator
 pub fn blocked(text: &str) -> String {
     rgb(243, 139, 168, text) // red
@@ -4,17 +4,19 @@ use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::style::Style;
 use ratatui::text::{Line, Span};
 
-use std::collections::HashSet;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 use crate::editor;
 use crate::plan;
 use crate::spec;
 use crate::store::{self, Status, Store, Task, TaskList};
 use crate::ui::{
-    confirm, create, detail, help, list, plan_detail, plans, spec_detail, specs, status, theme,
-    vibe, vibe_detail,
+    board, confirm, create, detail, graph, help, list, move_picker, plan_detail, plans, search,
+    spec_detail, specs, status, theme, vibe, vibe_detail,
 };
+use crate::undo::{self, UndoStack};
+use crate::views;
 
 fn truncate_at_char_boundary(s: &str, max_bytes: usize) -> &str {
     if s.len() <= max_bytes {
@@ -33,6 +35,7 @@ enum Tab {
     Plans,
     Specs,
     Vibe,
+    Board,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -40,6 +43,7 @@ enum Screen {
     List,
     Detail,
     Status,
+    Move,
     Confirm,
     Create,
     Plans,
@@ -48,7 +52,9 @@ enum Screen {
     SpecDetail,
     Vibe,
     VibeDetail,
+    Board,
     Help,
+    Search,
 }
 
 pub struct App {
@@ -60,8 +66,10 @@ pub struct App {
     prev_screen: Screen,
     active_tab: Tab,
     list: list::ListState,
+    board: board::BoardState,
     detail: Option<detail::DetailState>,
     status_picker: Option<status::StatusPickerState>,
+    move_picker: Option<move_picker::MovePickerState>,
     confirm: Option<confirm::ConfirmState>,
     create_form: Option<create::CreateState>,
     plans_state: Option<plans::PlansState>,
@@ -70,8 +78,11 @@ pub struct App {
     spec_detail: Option<spec_detail::SpecDetailState>,
     vibe_state: Option<vibe::VibeState>,
     vibe_detail: Option<vibe_detail::VibeDetailState>,
+    search: Option<search::SearchState>,
     help_scroll: u16,
     status_msg: String,
+    undo_stack: UndoStack,
+    views_path: PathBuf,
     pub should_quit: bool,
     pub editor_request: Option<EditorRequest>,
 }
@@ -88,6 +99,7 @@ impl App {
         task_lists: Vec<TaskList>,
         tasks: Vec<Task>,
         active_list: String,
+        views_path: PathBuf,
     ) -> Self {
         let list_idx = task_lists
             .iter()
@@ -101,9 +113,11 @@ impl App {
             .into_iter()
             .cloned()
             .collect();
-        let plan_counts = store::plan_link_counts(&tasks);
+        let mut plans_state = plans::PlansState::new(all_plans, HashMap::new());
+        plans_state.set_tasks(&tasks);
         let spec_counts = store::spec_link_counts(&tasks);
         let vibe_state = vibe::VibeState::new(vibe_trackers, &tasks);
+        let board = board::BoardState::new(&tasks);
         Self {
             store,
             active_list,
@@ -112,19 +126,27 @@ impl App {
             screen: Screen::List,
             prev_screen: Screen::List,
             active_tab: Tab::Tasks,
-            list: list::ListState::new(tasks),
+            list: list::ListState {
+                views: views::load(&views_path),
+                ..list::ListState::new(tasks)
+            },
+            board,
             detail: None,
             status_picker: None,
+            move_picker: None,
             confirm: None,
             create_form: None,
-            plans_state: Some(plans::PlansState::new(all_plans, plan_counts)),
+            plans_state: Some(plans_state),
             plan_detail: None,
             specs_state: Some(specs::SpecsState::new(spec::list_specs(), spec_counts)),
             spec_detail: None,
             vibe_state: Some(vibe_state),
             vibe_detail: None,
+            search: None,
             help_scroll: 0,
             status_msg: String::new(),
+            undo_stack: UndoStack::default(),
+            views_path,
             should_quit: false,
             editor_request: None,
         }
@@ -140,10 +162,29 @@ impl App {
             self.status_msg.clear();
         }
 
+        // Global search (works everywhere but forms, help and modals; a
+        // modal's own `prev_screen` would be lost)
+        if key.code == KeyCode::Char('f')
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+            && !matches!(
+                self.screen,
+                Screen::Search
+                    | Screen::Create
+                    | Screen::Help
+                    | Screen::Status
+                    | Screen::Move
+                    | Screen::Confirm
+            )
+        {
+            self.open_search();
+            return;
+        }
+
         // Help toggle (works from most screens)
         if key.code == KeyCode::Char('?')
             && self.screen != Screen::Help
             && !self.list.searching
+            && self.list.view_input.is_none()
             && !self.plans_state.as_ref().is_some_and(|p| p.searching)
             && !self.specs_state.as_ref().is_some_and(|s| s.searching)
             && !self.vibe_state.as_ref().is_some_and(|v| v.searching)
@@ -174,6 +215,7 @@ impl App {
             Screen::List => self.handle_list_key(key),
             Screen::Detail => self.handle_detail_key(key),
             Screen::Status => self.handle_status_key(key),
+            Screen::Move => self.handle_move_key(key),
             Screen::Confirm => self.handle_confirm_key(key),
             Screen::Create => self.handle_create_key(key),
             Screen::Plans => self.handle_plans_key(key),
@@ -182,6 +224,8 @@ impl App {
             Screen::SpecDetail => self.handle_spec_detail_key(key),
             Screen::Vibe => self.handle_vibe_key(key),
             Screen::VibeDetail => self.handle_vibe_detail_key(key),
+            Screen::Board => self.handle_board_key(key),
+            Screen::Search => self.handle_search_key(key),
             Screen::Help => {} // handled above
         }
     }
@@ -210,6 +254,11 @@ impl App {
             }
         }
 
+        if self.list.view_input.is_some() {
+            self.handle_view_input_key(key);
+            return;
+        }
+
         // Search mode
         if self.list.searching {
             match key.code {
@@ -237,6 +286,14 @@ impl App {
             return;
         }
 
+        if matches!(
+            key.code,
+            KeyCode::Char('s' | 'p' | 'a' | 'd' | 'n' | 'e' | 'D' | 'm')
+        ) && self.reject_archived_edit()
+        {
+            return;
+        }
+
         match key.code {
             KeyCode::Char('q') | KeyCode::Char('c')
                 if key.modifiers.contains(KeyModifiers::CONTROL) =>
@@ -263,18 +320,24 @@ impl App {
             KeyCode::Char('T') => {
                 self.list.tree_view = !self.list.tree_view;
             }
+            KeyCode::Char('g') => self.list.show_graph = !self.list.show_graph,
             KeyCode::Char('A') => {
                 self.list.show_closed = !self.list.show_closed;
                 self.list.rebuild();
             }
-            KeyCode::Char('F') => {
-                self.list.status_filter = crate::store::StatusFilter::Active;
-                self.list.sort_order = crate::store::SortOrder::Id;
-                self.list.show_closed = false;
-                self.list.tree_view = false;
-                self.list.query.clear();
-                self.list.search_input.clear();
-                self.list.rebuild();
+            KeyCode::Char('S') => {
+                self.list.source = self.list.source.next();
+                self.reload_tasks();
+            }
+            KeyCode::Char('F') => self.reset_filters(),
+            KeyCode::Char('v') => self.cycle_view(),
+            KeyCode::Char('V') => {
+                let name = self
+                    .list
+                    .active_view()
+                    .map(|i| self.list.views[i].name.clone())
+                    .unwrap_or_default();
+                self.list.view_input = Some(name);
             }
             KeyCode::Char('s') => self.open_status_picker(),
             KeyCode::Char('p') => self.quick_status(Status::Pending),
@@ -286,6 +349,10 @@ impl App {
             }
             KeyCode::Char('e') => self.open_editor(),
             KeyCode::Char('D') => self.open_confirm(),
+            KeyCode::Char('X') => self.archive_selected(),
+            KeyCode::Char('m') => self.open_move_picker(),
+            KeyCode::Char('u') => self.undo(),
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => self.redo(),
             KeyCode::Char('R') => {
                 self.reload_tasks();
                 self.status_msg = "Reloaded".to_string();
@@ -296,6 +363,7 @@ impl App {
             KeyCode::Char('2') => self.switch_tab(Tab::Plans),
             KeyCode::Char('3') => self.switch_tab(Tab::Specs),
             KeyCode::Char('4') => self.switch_tab(Tab::Vibe),
+            KeyCode::Char('5') => self.switch_tab(Tab::Board),
             KeyCode::Char('z') if self.list.tree_view => {
                 self.list.pending_z = true;
             }
@@ -332,9 +400,12 @@ impl App {
     }
 
     fn handle_detail_key(&mut self, key: KeyEvent) {
+        if matches!(key.code, KeyCode::Char('s' | 'e' | 'D' | 'm')) && self.reject_archived_edit() {
+            return;
+        }
         match key.code {
             KeyCode::Char('q') => self.should_quit = true,
-            KeyCode::Esc => self.screen = Screen::List,
+            KeyCode::Esc => self.screen = self.tasks_screen(),
             KeyCode::Char('j') | KeyCode::Down => {
                 if let Some(d) = &mut self.detail {
                     d.scroll_down();
@@ -358,6 +429,10 @@ impl App {
             KeyCode::Char('s') => self.open_status_picker(),
             KeyCode::Char('e') => self.open_editor(),
             KeyCode::Char('D') => self.open_confirm(),
+            KeyCode::Char('X') => self.archive_selected(),
+            KeyCode::Char('m') => self.open_move_picker(),
+            KeyCode::Char('u') => self.undo(),
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => self.redo(),
             KeyCode::Char('p') => {
                 let project = self.detail.as_ref().map(|d| d.task.project.clone());
                 if let Some(ps) = &mut self.plans_state {
@@ -396,20 +471,63 @@ impl App {
         }
     }
 
+    fn handle_move_key(&mut self, key: KeyEvent) {
+        let Some(mp) = &mut self.move_picker else {
+            return;
+        };
+        match key.code {
+            KeyCode::Char('q') => self.should_quit = true,
+            KeyCode::Esc => self.screen = self.prev_screen,
+            KeyCode::Char('j') | KeyCode::Down => mp.next(),
+            KeyCode::Char('k') | KeyCode::Up => mp.prev(),
+            KeyCode::Char('c') => mp.copy = !mp.copy,
+            KeyCode::Enter => {
+                let Some(to) = mp.selected().map(str::to_string) else {
+                    return;
+                };
+                let (task_id, copy) = (mp.task_id.clone(), mp.copy);
+                match self.store.move_task(&self.active_list, &task_id, &to, copy) {
+                    Ok(pairs) => {
+                        let verb = if copy { "Copied" } else { "Moved" };
+                        let new_id = pairs.first().map(|(_, n)| n.as_str()).unwrap_or("");
+                        self.status_msg = format!(
+                            "{verb} #{task_id} → {to} #{new_id} ({} task(s))",
+                            pairs.len()
+                        );
+                        self.screen = self.tasks_screen();
+                        self.reload_tasks();
+                    }
+                    Err(e) => {
+                        self.status_msg = format!("Error: {e}");
+                        self.screen = self.prev_screen;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn handle_confirm_key(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Char('y') | KeyCode::Char('Y') => {
                 if let Some(c) = &self.confirm {
                     let task_id = c.task_id.clone();
-                    match self.store.delete_task(&self.active_list, &task_id) {
+                    let snapshot = self.store.load_task(&self.active_list, &task_id);
+                    match self.store.trash_task(&self.active_list, &task_id) {
                         Ok(()) => {
-                            self.status_msg = format!("Deleted #{task_id}");
+                            if let Some(task) = snapshot {
+                                self.undo_stack.push(undo::Action::Delete {
+                                    list_id: self.active_list.clone(),
+                                    task,
+                                });
+                            }
+                            self.status_msg = format!("Deleted #{task_id} (u to undo)");
                             self.reload_tasks();
                         }
                         Err(e) => self.status_msg = format!("Error: {e}"),
                     }
                 }
-                self.screen = Screen::List;
+                self.screen = self.tasks_screen();
             }
             KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
                 self.screen = self.prev_screen;
@@ -424,7 +542,7 @@ impl App {
         };
 
         match key.code {
-            KeyCode::Esc => self.screen = Screen::List,
+            KeyCode::Esc => self.screen = self.tasks_screen(),
             KeyCode::Char('q') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 self.should_quit = true;
             }
@@ -432,7 +550,7 @@ impl App {
                 let subject = form.subject().to_string();
                 if subject.is_empty() {
                     self.status_msg = "Subject is required".to_string();
-                    self.screen = Screen::List;
+                    self.screen = self.tasks_screen();
                     return;
                 }
                 let description = form.description().to_string();
@@ -467,12 +585,18 @@ impl App {
 
                 match self.store.create_task(&self.active_list, &task) {
                     Ok(created) => {
+                        if let Some(task) = self.store.load_task(&self.active_list, &created.id) {
+                            self.undo_stack.push(undo::Action::Create {
+                                list_id: self.active_list.clone(),
+                                task,
+                            });
+                        }
                         self.status_msg = format!("Created #{}: {}", created.id, created.subject);
                         self.reload_tasks();
                     }
                     Err(e) => self.status_msg = format!("Error: {e}"),
                 }
-                self.screen = Screen::List;
+                self.screen = self.tasks_screen();
             }
             KeyCode::Tab | KeyCode::Down => form.next_field(),
             KeyCode::BackTab | KeyCode::Up => form.prev_field(),
@@ -520,11 +644,13 @@ impl App {
             KeyCode::Char('2') => self.switch_tab(Tab::Plans),
             KeyCode::Char('3') => self.switch_tab(Tab::Specs),
             KeyCode::Char('4') => self.switch_tab(Tab::Vibe),
+            KeyCode::Char('5') => self.switch_tab(Tab::Board),
             KeyCode::Char('j') | KeyCode::Down => ps.next(),
             KeyCode::Char('k') | KeyCode::Up => ps.prev(),
             KeyCode::Char('g') => ps.home(),
             KeyCode::Char('G') => ps.end(),
             KeyCode::Char('A') => ps.cycle_source(),
+            KeyCode::Char('s') => ps.cycle_status(),
             KeyCode::Char('e') if ps.source == plans::PlanSource::Active => {
                 if let Some(p) = ps.selected_plan().cloned() {
                     let path = p.path.to_string_lossy().to_string();
@@ -575,6 +701,41 @@ impl App {
                     pd.page_up(10);
                 }
             }
+            KeyCode::Char('r') => {
+                if let Some(pd) = &mut self.plan_detail {
+                    pd.view.toggle_raw();
+                }
+            }
+            KeyCode::Char('o') => {
+                if let Some(pd) = &mut self.plan_detail {
+                    pd.view.toggle_outline();
+                }
+            }
+            KeyCode::Char('n') => {
+                if let Some(pd) = &mut self.plan_detail {
+                    pd.next_heading();
+                }
+            }
+            KeyCode::Char('N') => {
+                if let Some(pd) = &mut self.plan_detail {
+                    pd.prev_heading();
+                }
+            }
+            KeyCode::Char('d') => {
+                if let Some(pd) = &mut self.plan_detail {
+                    pd.toggle_diff();
+                }
+            }
+            KeyCode::Char('[') => {
+                if let Some(pd) = &mut self.plan_detail {
+                    pd.step_rev(-1);
+                }
+            }
+            KeyCode::Char(']') => {
+                if let Some(pd) = &mut self.plan_detail {
+                    pd.step_rev(1);
+                }
+            }
             _ => {}
         }
     }
@@ -617,11 +778,13 @@ impl App {
             KeyCode::Char('2') => self.switch_tab(Tab::Plans),
             KeyCode::Char('3') => self.switch_tab(Tab::Specs),
             KeyCode::Char('4') => self.switch_tab(Tab::Vibe),
+            KeyCode::Char('5') => self.switch_tab(Tab::Board),
             KeyCode::Char('j') | KeyCode::Down => ss.next(),
             KeyCode::Char('k') | KeyCode::Up => ss.prev(),
             KeyCode::Char('g') => ss.home(),
             KeyCode::Char('G') => ss.end(),
             KeyCode::Char('A') => ss.cycle_source(),
+            KeyCode::Char('s') => ss.cycle_status(),
             KeyCode::Char('e') if ss.source == specs::SpecSource::Active => {
                 if let Some(s) = ss.selected_spec().cloned() {
                     let path = s.path.to_string_lossy().to_string();
@@ -672,6 +835,26 @@ impl App {
                     sd.page_up(10);
                 }
             }
+            KeyCode::Char('r') => {
+                if let Some(sd) = &mut self.spec_detail {
+                    sd.view.toggle_raw();
+                }
+            }
+            KeyCode::Char('o') => {
+                if let Some(sd) = &mut self.spec_detail {
+                    sd.view.toggle_outline();
+                }
+            }
+            KeyCode::Char('n') => {
+                if let Some(sd) = &mut self.spec_detail {
+                    sd.next_heading();
+                }
+            }
+            KeyCode::Char('N') => {
+                if let Some(sd) = &mut self.spec_detail {
+                    sd.prev_heading();
+                }
+            }
             _ => {}
         }
     }
@@ -709,11 +892,12 @@ impl App {
 
         match key.code {
             KeyCode::Char('q') => self.should_quit = true,
-            KeyCode::Tab => self.switch_tab(Tab::Tasks),
+            KeyCode::Tab => self.switch_tab(Tab::Board),
             KeyCode::Char('1') => self.switch_tab(Tab::Tasks),
             KeyCode::Char('2') => self.switch_tab(Tab::Plans),
             KeyCode::Char('3') => self.switch_tab(Tab::Specs),
             KeyCode::Char('4') => self.switch_tab(Tab::Vibe),
+            KeyCode::Char('5') => self.switch_tab(Tab::Board),
             KeyCode::Char('j') | KeyCode::Down => vs.next(),
             KeyCode::Char('k') | KeyCode::Up => vs.prev(),
             KeyCode::Char('g') => vs.home(),
@@ -744,6 +928,70 @@ impl App {
         }
     }
 
+    fn handle_board_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Char('c')
+                if key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                self.should_quit = true;
+            }
+            KeyCode::Char('q') => self.should_quit = true,
+            KeyCode::Tab => self.switch_tab(Tab::Tasks),
+            KeyCode::Char('1') => self.switch_tab(Tab::Tasks),
+            KeyCode::Char('2') => self.switch_tab(Tab::Plans),
+            KeyCode::Char('3') => self.switch_tab(Tab::Specs),
+            KeyCode::Char('4') => self.switch_tab(Tab::Vibe),
+            KeyCode::Char('5') => self.switch_tab(Tab::Board),
+            KeyCode::Char('j') | KeyCode::Down => self.board.next(),
+            KeyCode::Char('k') | KeyCode::Up => self.board.prev(),
+            KeyCode::Char('g') => self.board.home(),
+            KeyCode::Char('G') => self.board.end(),
+            KeyCode::Left if key.modifiers.contains(KeyModifiers::SHIFT) => self.move_card(-1),
+            KeyCode::Right if key.modifiers.contains(KeyModifiers::SHIFT) => self.move_card(1),
+            KeyCode::Char('H') => self.move_card(-1),
+            KeyCode::Char('L') => self.move_card(1),
+            KeyCode::Char('h') | KeyCode::Left => self.board.left(),
+            KeyCode::Char('l') | KeyCode::Right => self.board.right(),
+            KeyCode::Enter => {
+                if let Some(id) = self.board.selected_id()
+                    && let Some(task) = self.store.load_task(&self.active_list, &id)
+                {
+                    self.detail = Some(self.detail_state(task));
+                    self.screen = Screen::Detail;
+                }
+            }
+            KeyCode::Char('s') => self.open_status_picker(),
+            KeyCode::Char('p') => self.quick_status(Status::Pending),
+            KeyCode::Char('a') => self.quick_status(Status::InProgress),
+            KeyCode::Char('d') => self.quick_status(Status::Completed),
+            KeyCode::Char('n') => {
+                self.create_form = Some(create::CreateState::new());
+                self.screen = Screen::Create;
+            }
+            KeyCode::Char('e') => self.open_editor(),
+            KeyCode::Char('D') => self.open_confirm(),
+            KeyCode::Char('X') => self.archive_selected(),
+            KeyCode::Char('m') => self.open_move_picker(),
+            KeyCode::Char('u') => self.undo(),
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => self.redo(),
+            KeyCode::Char('R') => {
+                self.reload_tasks();
+                self.status_msg = "Reloaded".to_string();
+            }
+            _ => {}
+        }
+    }
+
+    /// Move the selected card to the neighbouring column, changing its status.
+    fn move_card(&mut self, delta: isize) {
+        let (Some(id), Some(status)) =
+            (self.board.selected_id(), self.board.neighbor_status(delta))
+        else {
+            return;
+        };
+        self.change_status(&id, status);
+    }
+
     fn handle_vibe_detail_key(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Char('q') => self.should_quit = true,
@@ -772,6 +1020,136 @@ impl App {
         }
     }
 
+    fn handle_search_key(&mut self, key: KeyEvent) {
+        let Some(s) = &mut self.search else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.screen = self.prev_screen,
+            KeyCode::Enter => {
+                if let Some(hit) = s.selected().cloned() {
+                    self.jump_to(&hit);
+                }
+            }
+            KeyCode::Down => s.next(),
+            KeyCode::Up => s.prev(),
+            KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => s.next(),
+            KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => s.prev(),
+            KeyCode::Backspace => s.pop(),
+            KeyCode::Char(c) => s.push(c),
+            _ => {}
+        }
+    }
+
+    /// Index the current list's tasks and every plan and spec, then show
+    /// the search overlay. The previous query is kept for quick re-runs.
+    fn open_search(&mut self) {
+        let mut docs = crate::search::task_docs(
+            &self.list.tasks,
+            &self.active_list,
+            crate::search::Source::Active,
+        );
+        docs.extend(crate::search::task_docs(
+            &self.store.list_archived_tasks(&self.active_list),
+            &self.active_list,
+            crate::search::Source::Archived,
+        ));
+        docs.extend(crate::search::artifact_docs());
+
+        let mut state = search::SearchState::new(docs);
+        if let Some(prev) = self.search.take() {
+            state.input = prev.input;
+            state.update();
+        }
+        self.search = Some(state);
+        self.prev_screen = self.screen;
+        self.screen = Screen::Search;
+    }
+
+    /// Switch to the tab holding `hit`, select it and open its detail view.
+    fn jump_to(&mut self, hit: &crate::search::Hit) {
+        use crate::search::{Kind, Source};
+        let d = &hit.doc;
+        match d.kind {
+            Kind::Task => {
+                self.switch_tab(Tab::Tasks);
+                let source = if d.source == Source::Archived {
+                    list::TaskSource::Archived
+                } else {
+                    list::TaskSource::Active
+                };
+                if self.list.source != source {
+                    self.list.source = source;
+                    self.reload_tasks();
+                }
+                if let Some(idx) = self.list.filtered.iter().position(|t| t.id == d.key) {
+                    self.list.table_state.select(Some(idx));
+                }
+                match self.load_visible_task(&d.key) {
+                    Some(task) => {
+                        self.detail = Some(self.detail_state(task));
+                        self.screen = Screen::Detail;
+                    }
+                    None => self.status_msg = format!("Task #{} no longer exists", d.key),
+                }
+            }
+            Kind::Plan => {
+                self.switch_tab(Tab::Plans);
+                let Some(ps) = &mut self.plans_state else {
+                    return;
+                };
+                ps.source = match d.source {
+                    Source::Active => plans::PlanSource::Active,
+                    Source::Archived => plans::PlanSource::Archived,
+                    Source::GitNotes => plans::PlanSource::GitNotes,
+                };
+                ps.query.clear();
+                ps.search_input.clear();
+                ps.status_filter = None;
+                ps.reload_plans();
+                if !ps.filtered.iter().any(|p| p.name == d.key) {
+                    ps.project_filter = None;
+                    ps.reload_plans();
+                }
+                match ps.filtered.iter().position(|p| p.name == d.key) {
+                    Some(idx) => {
+                        ps.table_state.select(Some(idx));
+                        let p = ps.filtered[idx].clone();
+                        self.plan_detail =
+                            Some(plan_detail::PlanDetailState::new(p, &self.list.tasks));
+                        self.screen = Screen::PlanDetail;
+                    }
+                    None => self.status_msg = format!("Plan {} no longer exists", d.key),
+                }
+            }
+            Kind::Spec => {
+                self.switch_tab(Tab::Specs);
+                let Some(ss) = &mut self.specs_state else {
+                    return;
+                };
+                ss.source = match d.source {
+                    Source::Active => specs::SpecSource::Active,
+                    Source::Archived => specs::SpecSource::Archived,
+                    Source::GitNotes => specs::SpecSource::GitNotes,
+                };
+                ss.query.clear();
+                ss.search_input.clear();
+                ss.status_filter = None;
+                ss.reload_specs();
+                match ss.filtered.iter().position(|s| s.name == d.key) {
+                    Some(idx) => {
+                        ss.table_state.select(Some(idx));
+                        let s = ss.filtered[idx].clone();
+                        self.spec_detail =
+                            Some(spec_detail::SpecDetailState::new(s, &self.list.tasks));
+                        self.screen = Screen::SpecDetail;
+                    }
+                    None => self.status_msg = format!("Spec {} no longer exists", d.key),
+                }
+            }
+        }
+    }
+
     fn switch_tab(&mut self, tab: Tab) {
         self.active_tab = tab;
         self.screen = match tab {
@@ -779,27 +1157,69 @@ impl App {
             Tab::Plans => Screen::Plans,
             Tab::Specs => Screen::Specs,
             Tab::Vibe => Screen::Vibe,
+            Tab::Board => Screen::Board,
         };
     }
 
+    /// Where task-level screens (detail, pickers, forms) return to.
+    fn tasks_screen(&self) -> Screen {
+        if self.active_tab == Tab::Board {
+            Screen::Board
+        } else {
+            Screen::List
+        }
+    }
+
+    /// True when the Tasks tab is showing the archive rather than the live list.
+    fn viewing_archive(&self) -> bool {
+        self.active_tab == Tab::Tasks && self.list.source == list::TaskSource::Archived
+    }
+
     // Actions
 
     fn open_detail(&mut self) {
         if let Some(id) = self.list.selected_id()
-            && let Some(task) = self.store.load_task(&self.active_list, &id)
+            && let Some(task) = self.load_visible_task(&id)
         {
-            let children: Vec<Task> = self
-                .list
-                .tasks
-                .iter()
-                .filter(|t| t.parent_id == id)
-                .cloned()
-                .collect();
-            self.detail = Some(detail::DetailState::new(task, children));
+            self.detail = Some(self.detail_state(task));
             self.screen = Screen::Detail;
         }
     }
 
+    /// Load a task from whichever source the Tasks tab is showing.
+    fn load_visible_task(&self, task_id: &str) -> Option<Task> {
+        if self.viewing_archive() {
+            self.store.load_archived_task(&self.active_list, task_id)
+        } else {
+            self.store.load_task(&self.active_list, task_id)
+        }
+    }
+
+    /// Archived tasks are read-only until restored; returns true (and explains
+    /// why) when the key should be ignored.
+    fn reject_archived_edit(&mut self) -> bool {
+        if self.viewing_archive() {
+            self.status_msg = "Archived task is read-only — X to restore".to_string();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn detail_state(&self, task: Task) -> detail::DetailState {
+        let children: Vec<Task> = self
+            .list
+            .pool()
+            .iter()
+            .filter(|t| t.parent_id == task.id)
+            .cloned()
+            .collect();
+        let history = self.store.task_history(&self.active_list, &task.id);
+        let mut state = detail::DetailState::new(task, children);
+        state.history = history;
+        state
+    }
+
     fn open_status_picker(&mut self) {
         let (task_id, current_status) = match self.screen {
             Screen::List => {
@@ -809,6 +1229,13 @@ impl App {
                     return;
                 }
             }
+            Screen::Board => {
+                if let Some(t) = self.board.selected_task() {
+                    (t.id.clone(), t.status.clone())
+                } else {
+                    return;
+                }
+            }
             Screen::Detail => {
                 if let Some(d) = &self.detail {
                     (d.task.id.clone(), d.task.status.clone())
@@ -826,6 +1253,7 @@ impl App {
     fn quick_status(&mut self, new_status: Status) {
         let task_id = match self.screen {
             Screen::List => self.list.selected_id(),
+            Screen::Board => self.board.selected_id(),
             Screen::Detail => self.detail.as_ref().map(|d| d.task.id.clone()),
             _ => None,
         };
@@ -836,9 +1264,14 @@ impl App {
 
     fn change_status(&mut self, task_id: &str, new_status: Status) {
         if let Some(mut task) = self.store.load_task(&self.active_list, task_id) {
+            let before = task.clone();
             task.status = new_status.clone();
             match self.store.save_task(&self.active_list, &task) {
                 Ok(()) => {
+                    self.push_update(
+                        before,
+                        format!("status → {} on #{task_id}", new_status.as_str()),
+                    );
                     self.status_msg = format!("Status → {}", new_status.as_str());
                     self.reload_tasks();
                     if self.screen == Screen::Detail || self.prev_screen == Screen::Detail {
@@ -850,9 +1283,182 @@ impl App {
         }
     }
 
+    /// Record a just-saved update, snapshotting the post-save state from disk.
+    fn push_update(&mut self, before: Task, label: String) {
+        if let Some(after) = self.store.load_task(&self.active_list, &before.id) {
+            self.undo_stack.push(undo::Action::Update {
+                list_id: self.active_list.clone(),
+                before: Box::new(before),
+                after: Box::new(after),
+                label,
+            });
+        }
+    }
+
+    fn reset_filters(&mut self) {
+        self.list.status_filter = crate::store::StatusFilter::Active;
+        self.list.sort_order = crate::store::SortOrder::Id;
+        self.list.show_closed = false;
+        self.list.tree_view = false;
+        self.list.query.clear();
+        self.list.search_input.clear();
+        self.list.rebuild();
+    }
+
+    /// Step to the saved view after the one currently in effect; past the
+    /// last view, fall back to the default filters.
+    fn cycle_view(&mut self) {
+        if self.list.views.is_empty() {
+            self.status_msg = "No saved views — V to save one".to_string();
+            return;
+        }
+        let next = self.list.active_view().map_or(0, |i| i + 1);
+        match self.list.views.get(next).cloned() {
+            Some(view) => {
+                self.list.apply_view(&view);
+                self.status_msg = format!("View: {}", view.name);
+            }
+            None => {
+                self.reset_filters();
+                self.status_msg = "View: default".to_string();
+            }
+        }
+    }
+
+    fn handle_view_input_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.list.view_input.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.list.view_input = None,
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Enter => {
+                let name = input.trim().to_string();
+                self.list.view_input = None;
+                if name.is_empty() {
+                    return;
+                }
+                let view = self.list.to_view(&name);
+                match views::save(&self.views_path, &view) {
+                    Ok(()) => {
+                        self.list.views = views::load(&self.views_path);
+                        self.status_msg = format!("Saved view {name}");
+                    }
+                    Err(e) => self.status_msg = format!("Error: {e}"),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn archive_selected(&mut self) {
+        let task_id = match self.screen {
+            Screen::List => self.list.selected_id(),
+            Screen::Board => self.board.selected_id(),
+            Screen::Detail => self.detail.as_ref().map(|d| d.task.id.clone()),
+            _ => None,
+        };
+        let Some(id) = task_id else { return };
+        if self.viewing_archive() {
+            self.restore_archived(&id);
+            return;
+        }
+        let Some(task) = self.store.load_task(&self.active_list, &id) else {
+            return;
+        };
+        match self.store.archive_task(&self.active_list, &id) {
+            Ok(()) => {
+                self.undo_stack.push(undo::Action::Archive {
+                    list_id: self.active_list.clone(),
+                    task,
+                });
+                self.status_msg = format!("Archived #{id} (u to undo)");
+                self.screen = self.tasks_screen();
+                self.reload_tasks();
+            }
+            Err(e) => self.status_msg = format!("Error: {e}"),
+        }
+    }
+
+    fn restore_archived(&mut self, id: &str) {
+        match self.store.unarchive_task(&self.active_list, id) {
+            Ok(restored) => {
+                if let Some(task) = self.store.load_task(&self.active_list, &restored) {
+                    self.undo_stack.push(undo::Action::Unarchive {
+                        list_id: self.active_list.clone(),
+                        task,
+                    });
+                }
+                self.status_msg = if restored == id {
+                    format!("Restored #{id}")
+                } else {
+                    format!("Restored #{id} as #{restored} (ID in use)")
+                };
+                self.screen = self.tasks_screen();
+                self.reload_tasks();
+            }
+            Err(e) => self.status_msg = format!("Error: {e}"),
+        }
+    }
+
+    fn undo(&mut self) {
+        let result = self.undo_stack.undo(&self.store);
+        self.finish_undo(result, "Nothing to undo");
+    }
+
+    fn redo(&mut self) {
+        let result = self.undo_stack.redo(&self.store);
+        self.finish_undo(result, "Nothing to redo");
+    }
+
+    fn finish_undo(&mut self, result: Option<Result<String, String>>, empty: &str) {
+        self.status_msg = match result {
+            Some(Ok(msg)) | Some(Err(msg)) => msg,
+            None => empty.to_string(),
+        };
+        if self.screen == Screen::Detail
+            && let Some(d) = &self.detail
+            && self
+                .store
+                .load_task(&self.active_list, &d.task.id)
+                .is_none()
+        {
+            self.screen = self.tasks_screen();
+        }
+        self.reload_tasks();
+    }
+
+    fn open_move_picker(&mut self) {
+        let task_id = match self.screen {
+            Screen::List => self.list.selected_id(),
+            Screen::Board => self.board.selected_id(),
+            Screen::Detail => self.detail.as_ref().map(|d| d.task.id.clone()),
+            _ => None,
+        };
+        let Some(id) = task_id else { return };
+        let options: Vec<String> = self
+            .store
+            .list_task_lists()
+            .into_iter()
+            .map(|l| l.id)
+            .filter(|l| *l != self.active_list)
+            .collect();
+        if options.is_empty() {
+            self.status_msg = "No other task lists".to_string();
+            return;
+        }
+        self.prev_screen = self.screen;
+        self.move_picker = Some(move_picker::MovePickerState::new(id, options));
+        self.screen = Screen::Move;
+    }
+
     fn open_confirm(&mut self) {
         let task_id = match self.screen {
             Screen::List => self.list.selected_id(),
+            Screen::Board => self.board.selected_id(),
             Screen::Detail => self.detail.as_ref().map(|d| d.task.id.clone()),
             _ => None,
         };
@@ -866,6 +1472,7 @@ impl App {
     fn open_editor(&mut self) {
         let task_id = match self.screen {
             Screen::List => self.list.selected_id(),
+            Screen::Board => self.board.selected_id(),
             Screen::Detail => self.detail.as_ref().map(|d| d.task.id.clone()),
             _ => None,
         };
@@ -904,6 +1511,14 @@ impl App {
             Ok(data) => match editor::unmarshal_task(&data, &original) {
                 Ok(updated) => match self.store.save_task(list_id, &updated) {
                     Ok(()) => {
+                        if let Some(after) = self.store.load_task(list_id, task_id) {
+                            self.undo_stack.push(undo::Action::Update {
+                                list_id: list_id.to_string(),
+                                before: Box::new(original),
+                                after: Box::new(after),
+                                label: format!("edit of #{task_id}"),
+                            });
+                        }
                         self.status_msg = "Task updated".to_string();
                         self.reload_tasks();
                         if self.prev_screen == Screen::Detail {
@@ -930,9 +1545,18 @@ impl App {
             self.list.tree_view,
             self.list.query.clone(),
         );
+        let source = self.list.source;
         let collapsed = std::mem::take(&mut self.list.collapsed);
         let expanded_ids = std::mem::take(&mut self.list.expanded_ids);
+        let views = std::mem::take(&mut self.list.views);
+        let show_graph = self.list.show_graph;
         self.list = list::ListState::new(tasks);
+        self.list.source = source;
+        self.list.views = views;
+        self.list.show_graph = show_graph;
+        if source == list::TaskSource::Archived {
+            self.list.archived = self.store.list_archived_tasks(&self.active_list);
+        }
         self.list.status_filter = filters.0;
         self.list.sort_order = filters.1;
         self.list.show_closed = filters.2;
@@ -942,6 +1566,7 @@ impl App {
         self.list.collapsed = collapsed;
         self.list.expanded_ids = expanded_ids;
         self.list.rebuild();
+        self.board.set_tasks(&self.list.tasks);
 
         // Restore selection by task ID
         if let Some(id) = selected_id
@@ -981,7 +1606,7 @@ impl App {
 
         // Refresh link counts for plans and specs
         if let Some(ps) = &mut self.plans_state {
-            ps.link_counts = store::plan_link_counts(&self.list.tasks);
+            ps.set_tasks(&self.list.tasks);
         }
         if let Some(ss) = &mut self.specs_state {
             ss.link_counts = store::spec_link_counts(&self.list.tasks);
@@ -990,22 +1615,15 @@ impl App {
 
     pub fn reload_plans(&mut self) {
         if let Some(ps) = &mut self.plans_state {
-            ps.link_counts = store::plan_link_counts(&self.list.tasks);
+            ps.set_tasks(&self.list.tasks);
             ps.reload_plans();
         }
         self.screen = self.prev_screen;
     }
 
     fn refresh_detail(&mut self, task_id: &str) {
-        if let Some(task) = self.store.load_task(&self.active_list, task_id) {
-            let children: Vec<Task> = self
-                .list
-                .tasks
-                .iter()
-                .filter(|t| t.parent_id == task_id)
-                .cloned()
-                .collect();
-            self.detail = Some(detail::DetailState::new(task, children));
+        if let Some(task) = self.load_visible_task(task_id) {
+            self.detail = Some(self.detail_state(task));
         }
     }
 
@@ -1035,11 +1653,20 @@ impl App {
             Screen::List => {
                 self.render_tab_header(f, header_area);
                 list::render_filter_bar(f, filter_bar_area, &self.list);
-                list::render_list(f, body_area, &mut self.list);
+                if self.list.show_graph {
+                    let [list_area, graph_area] =
+                        Layout::horizontal([Constraint::Fill(3), Constraint::Fill(2)])
+                            .areas(body_area);
+                    list::render_list(f, list_area, &mut self.list);
+                    let selected = self.list.selected_id();
+                    graph::render_graph(f, graph_area, self.list.pool(), selected.as_deref());
+                } else {
+                    list::render_list(f, body_area, &mut self.list);
+                }
                 self.render_footer(
                     f,
                     footer_area,
-                    "j/k:move  enter:detail  n:new  s:status  p/a/d:quick-status  e:edit  D:delete  f:filter  T:tree  /:search  R:reload  L:list  tab/2:plans  ?:help  q:quit",
+                    "j/k:move  enter:detail  n:new  s:status  p/a/d:quick-status  e:edit  D:delete  X:archive  m:move  S:archived  u:undo  f:filter  T:tree  g:graph  v/V:views  /:search  R:reload  L:list  tab/2:plans  ?:help  q:quit",
                 );
             }
             Screen::Detail => {
@@ -1062,7 +1689,7 @@ impl App {
                 self.render_footer(
                     f,
                     footer_area,
-                    "j/k:scroll  space/b:page  s:status  e:edit  D:delete  p:plans  esc:back  q:quit",
+                    "j/k:scroll  space/b:page  s:status  e:edit  D:delete  X:archive  m:move  u:undo  p:plans  esc:back  q:quit",
                 );
             }
             Screen::Status => {
@@ -1073,6 +1700,18 @@ impl App {
                 }
                 self.render_footer(f, footer_area, "j/k:navigate  enter:select  esc:cancel");
             }
+            Screen::Move => {
+                self.render_header(f, header_area, "move task");
+                let _ = filter_bar_area;
+                if let Some(mp) = &self.move_picker {
+                    move_picker::render_move_picker(f, body_area, mp);
+                }
+                self.render_footer(
+                    f,
+                    footer_area,
+                    "j/k:navigate  enter:select  c:toggle copy  esc:cancel",
+                );
+            }
             Screen::Confirm => {
                 self.render_header(f, header_area, "confirm");
                 let _ = filter_bar_area;
@@ -1122,10 +1761,14 @@ impl App {
                     .unwrap_or_default();
                 self.render_header(f, header_area, &title);
                 let _ = filter_bar_area;
-                if let Some(pd) = &self.plan_detail {
+                if let Some(pd) = &mut self.plan_detail {
                     plan_detail::render_plan_detail(f, body_area, pd);
                 }
-                self.render_footer(f, footer_area, "j/k:scroll  space/b:page  esc:back  q:quit");
+                self.render_footer(
+                    f,
+                    footer_area,
+                    "j/k:scroll  space/b:page  n/N:heading  o:outline  r:raw  esc:back  q:quit",
+                );
             }
             Screen::Specs => {
                 self.render_tab_header(f, header_area);
@@ -1160,10 +1803,14 @@ impl App {
                     .unwrap_or_default();
                 self.render_header(f, header_area, &title);
                 let _ = filter_bar_area;
-                if let Some(sd) = &self.spec_detail {
+                if let Some(sd) = &mut self.spec_detail {
                     spec_detail::render_spec_detail(f, body_area, sd);
                 }
-                self.render_footer(f, footer_area, "j/k:scroll  space/b:page  esc:back  q:quit");
+                self.render_footer(
+                    f,
+                    footer_area,
+                    "j/k:scroll  space/b:page  n/N:heading  o:outline  r:raw  esc:back  q:quit",
+                );
             }
             Screen::Vibe => {
                 self.render_tab_header(f, header_area);
@@ -1199,6 +1846,36 @@ impl App {
                 }
                 self.render_footer(f, footer_area, "j/k:scroll  space/b:page  esc:back  q:quit");
             }
+            Screen::Board => {
+                self.render_tab_header(f, header_area);
+                let count: usize = self.board.columns.iter().map(|c| c.tasks.len()).sum();
+                f.render_widget(
+                    Line::from(vec![
+                        Span::styled(format!(" {} ", self.active_list), theme::filter_tag_style()),
+                        Span::raw(" "),
+                        Span::styled(format!("{count} tasks"), theme::muted_style()),
+                    ]),
+                    filter_bar_area,
+                );
+                board::render_board(f, body_area, &self.board);
+                self.render_footer(
+                    f,
+                    footer_area,
+                    "h/l:column  j/k:card  H/L:move card  enter:detail  n:new  s:status  e:edit  X:archive  u:undo  tab/1:tasks  ?:help  q:quit",
+                );
+            }
+            Screen::Search => {
+                self.render_header(f, header_area, "search");
+                let _ = filter_bar_area;
+                if let Some(s) = &self.search {
+                    search::render_search(f, body_area, s);
+                }
+                self.render_footer(
+                    f,
+                    footer_area,
+                    "type:query  \"..\":phrase  ↑/↓:move  enter:open  esc:back",
+                );
+            }
             Screen::Help => {
                 self.render_header(f, header_area, "help");
                 let _ = filter_bar_area;
@@ -1208,6 +1885,7 @@ impl App {
                     self.prev_screen == Screen::Plans || self.prev_screen == Screen::PlanDetail;
                 let vibe_ctx =
                     self.prev_screen == Screen::Vibe || self.prev_screen == Screen::VibeDetail;
+                let board_ctx = self.prev_screen == Screen::Board;
                 self.help_scroll = help::render_help(
                     f,
                     body_area,
@@ -1215,6 +1893,7 @@ impl App {
                     plans_ctx,
                     specs_ctx,
                     vibe_ctx,
+                    board_ctx,
                 );
                 self.render_footer(f, footer_area, "j/k:scroll  g/G:top/bottom  ?/esc:close");
             }
@@ -1227,6 +1906,7 @@ impl App {
         let plans_label = "[ 2 Plans ]";
         let specs_label = "[ 3 Specs ]";
         let vibe_label = "[ 4 Vibe ]";
+        let board_label = "[ 5 Board ]";
         let sep = "  ";
 
         let brand_width = brand.len() as u16;
@@ -1236,16 +1916,20 @@ impl App {
             + sep.len()
             + specs_label.len()
             + sep.len()
-            + vibe_label.len()) as u16;
+            + vibe_label.len()
+            + sep.len()
+            + board_label.len()) as u16;
         let gap = area.width.saturating_sub(brand_width + tabs_width);
 
         let dim = theme::header_dim_style();
         let bright = theme::header_style();
-        let (tasks_style, plans_style, specs_style, vibe_style) = match self.active_tab {
-            Tab::Tasks => (bright, dim, dim, dim),
-            Tab::Plans => (dim, bright, dim, dim),
-            Tab::Specs => (dim, dim, bright, dim),
-            Tab::Vibe => (dim, dim, dim, bright),
+        let (tasks_style, plans_style, specs_style, vibe_style, board_style) = match self.active_tab
+        {
+            Tab::Tasks => (bright, dim, dim, dim, dim),
+            Tab::Plans => (dim, bright, dim, dim, dim),
+            Tab::Specs => (dim, dim, bright, dim, dim),
+            Tab::Vibe => (dim, dim, dim, bright, dim),
+            Tab::Board => (dim, dim, dim, dim, bright),
         };
 
         let sep_style = Style::default().bg(theme::ACCENT);
@@ -1259,6 +1943,8 @@ impl App {
             Span::styled(specs_label, specs_style),
             Span::styled(sep, sep_style),
             Span::styled(vibe_label, vibe_style),
+            Span::styled(sep, sep_style),
+            Span::styled(board_label, board_style),
         ]);
         f.render_widget(line, area);
     }
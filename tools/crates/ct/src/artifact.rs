@@ -3,6 +3,8 @@ use std::path::{Path, PathBuf};
 use std::process;
 use std::time::SystemTime;
 
+use crate::frontmatter::{Document, Frontmatter};
+
 fn fatal(msg: &str) -> ! {
     eprintln!("artifact: {msg}");
     process::exit(1);
@@ -45,37 +47,6 @@ pub fn artifact_dir_with_base(project_path: &str, kind: &str, base: &Path) -> Pa
     base.join(".claude").join(kind).join(name)
 }
 
-pub fn yaml_quote(s: &str) -> String {
-    if s.contains(':')
-        || s.contains('{')
-        || s.contains('}')
-        || s.contains('[')
-        || s.contains(']')
-        || s.contains('&')
-        || s.contains('*')
-        || s.contains('?')
-        || s.contains('|')
-        || s.contains('>')
-        || s.contains('!')
-        || s.contains('%')
-        || s.contains('@')
-        || s.contains('`')
-        || s.contains('#')
-        || s.contains(',')
-        || s.contains('"')
-        || s.contains('\'')
-        || s.contains('\n')
-        || s.contains('\\')
-        || s.starts_with(' ')
-        || s.ends_with(' ')
-    {
-        let escaped = s.replace('\\', "\\\\").replace('"', "\\\"");
-        format!("\"{escaped}\"")
-    } else {
-        s.to_string()
-    }
-}
-
 pub fn parse_frontmatter(content: &str) -> (Option<&str>, &str) {
     let delim = "---\n";
     if !content.starts_with(delim) {
@@ -94,26 +65,97 @@ pub fn parse_frontmatter(content: &str) -> (Option<&str>, &str) {
     }
 }
 
-pub fn parse_yaml_map(yaml: &str) -> Vec<(String, String)> {
-    yaml.lines()
-        .filter_map(|line| {
-            let line = line.trim();
-            if line.is_empty() {
-                return None;
-            }
-            let idx = line.find(':')?;
-            let key = line[..idx].trim().to_string();
-            let mut val = line[idx + 1..].trim().to_string();
-            if val.len() >= 2
-                && ((val.starts_with('"') && val.ends_with('"'))
-                    || (val.starts_with('\'') && val.ends_with('\'')))
-            {
-                val = val[1..val.len() - 1].to_string();
-                val = val.replace("\\\"", "\"").replace("\\\\", "\\");
-            }
-            Some((key, val))
-        })
-        .collect()
+/// Lifecycle of a plan or spec, kept in frontmatter `status`. Artifacts
+/// without one are drafts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lifecycle {
+    #[default]
+    Draft,
+    Approved,
+    InProgress,
+    Done,
+    Superseded,
+}
+
+impl Lifecycle {
+    pub const ALL: [Lifecycle; 5] = [
+        Self::Draft,
+        Self::Approved,
+        Self::InProgress,
+        Self::Done,
+        Self::Superseded,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Approved => "approved",
+            Self::InProgress => "in-progress",
+            Self::Done => "done",
+            Self::Superseded => "superseded",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "draft" => Some(Self::Draft),
+            "approved" => Some(Self::Approved),
+            "in-progress" => Some(Self::InProgress),
+            "done" => Some(Self::Done),
+            "superseded" => Some(Self::Superseded),
+            _ => None,
+        }
+    }
+
+    /// Status of a frontmatter value; missing or unknown values are drafts.
+    pub fn from_frontmatter(fm: &Frontmatter) -> Self {
+        Self::parse(&fm.status).unwrap_or_default()
+    }
+
+    /// Approved or being worked on: what `latest` should pick.
+    pub fn is_current(self) -> bool {
+        matches!(self, Self::Approved | Self::InProgress)
+    }
+
+    /// Next step of a tab's status filter: all (`None`), then each state in
+    /// order, then back to all.
+    pub fn next_filter(current: Option<Self>) -> Option<Self> {
+        match current {
+            None => Some(Self::ALL[0]),
+            Some(s) => Self::ALL
+                .iter()
+                .position(|l| *l == s)
+                .and_then(|i| Self::ALL.get(i + 1))
+                .copied(),
+        }
+    }
+}
+
+/// Rewrite `content` with `status` set and `updated` stamped, adding
+/// frontmatter if there is none.
+pub fn set_status(content: &str, status: Lifecycle) -> String {
+    let mut doc = Document::parse(content);
+    let fm = doc.frontmatter.get_or_insert_with(Frontmatter::default);
+    fm.status = status.as_str().to_string();
+    fm.updated = chrono_rfc3339();
+    doc.render()
+}
+
+/// Newest `.md` file directly in `dir`, preferring approved or in-progress
+/// ones over the rest.
+pub fn latest_in(dir: &Path) -> std::io::Result<Option<PathBuf>> {
+    let mut files: Vec<(SystemTime, PathBuf)> = std::fs::read_dir(dir)?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "md"))
+        .filter_map(|p| Some((p.metadata().ok()?.modified().ok()?, p)))
+        .collect();
+    files.sort_by_key(|f| std::cmp::Reverse(f.0));
+    let current = files.iter().find(|(_, p)| {
+        std::fs::read_to_string(p)
+            .is_ok_and(|c| Lifecycle::from_frontmatter(&Document::parse(&c).meta()).is_current())
+    });
+    Ok(current.or(files.first()).map(|(_, p)| p.clone()))
 }
 
 pub fn chrono_rfc3339() -> String {
@@ -142,36 +184,55 @@ pub fn chrono_rfc3339() -> String {
     format!("{year:04}-{m:02}-{d:02}T{hours:02}:{minutes:02}:{seconds:02}Z")
 }
 
+/// `key:` or `key: value`, as frontmatter lines start.
+fn is_yaml_key(line: &str) -> bool {
+    line.split_once(':').is_some_and(|(k, v)| {
+        !k.is_empty()
+            && k.chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+            && (v.is_empty() || v.starts_with(' '))
+    })
+}
+
+/// Line count of the frontmatter block `lines` open with: a `---`, YAML
+/// keys (with indented or `- ` continuations, no blank lines) and a
+/// closing `---`. A horizontal rule in a markdown body is not followed by
+/// that.
+fn frontmatter_len(lines: &[&str]) -> Option<usize> {
+    if lines.first()?.trim() != "---" {
+        return None;
+    }
+    let end = 1 + lines[1..].iter().position(|l| l.trim() == "---")?;
+    let block = &lines[1..end];
+    let yaml = block.first().is_some_and(|l| is_yaml_key(l))
+        && block
+            .iter()
+            .all(|l| is_yaml_key(l) || (l.starts_with([' ', '\t', '-']) && !l.trim().is_empty()));
+    yaml.then_some(end + 1)
+}
+
 /// Split a git note that may contain multiple appended frontmatter documents.
+/// A new document starts at each frontmatter block after the first.
 pub fn split_notes(content: &str) -> Vec<String> {
+    let lines: Vec<&str> = content.lines().collect();
     let mut docs = Vec::new();
     let mut current = String::new();
-    let mut in_frontmatter = false;
     let mut seen_frontmatter = false;
 
-    for line in content.lines() {
-        if line.trim() == "---" {
-            if !seen_frontmatter {
-                in_frontmatter = true;
-                seen_frontmatter = true;
-                current.push_str(line);
-                current.push('\n');
-            } else if in_frontmatter {
-                in_frontmatter = false;
-                current.push_str(line);
-                current.push('\n');
-            } else {
-                if !current.trim().is_empty() {
-                    docs.push(std::mem::take(&mut current));
-                }
-                in_frontmatter = true;
-                current.push_str(line);
-                current.push('\n');
+    let mut i = 0;
+    while i < lines.len() {
+        let block = frontmatter_len(&lines[i..]).unwrap_or(1);
+        if block > 1 {
+            if seen_frontmatter && !current.trim().is_empty() {
+                docs.push(std::mem::take(&mut current));
             }
-        } else {
+            seen_frontmatter = true;
+        }
+        for line in &lines[i..i + block] {
             current.push_str(line);
             current.push('\n');
         }
+        i += block;
     }
     if !current.trim().is_empty() {
         docs.push(current);
@@ -0,0 +1,537 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde_json::Value;
+
+use crate::ansi;
+use crate::store::{self, Store};
+
+/// Leftover `.tmp-*` files younger than this may belong to a write that is
+/// still in flight, so they are left alone.
+const TMP_GRACE: Duration = Duration::from_secs(60);
+
+pub const QUARANTINE_DIR: &str = "quarantine";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    Unparseable,
+    LeftoverTmp,
+    IdMismatch { file_id: String, json_id: String },
+    StaleHighwatermark { hwm: u64, max_id: u64 },
+    ShadowedArchive { task_id: String },
+    OrphanedArchive { task_id: String, parent_id: String },
+}
+
+impl Problem {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Unparseable => "unparseable",
+            Self::LeftoverTmp => "leftover_tmp",
+            Self::IdMismatch { .. } => "id_mismatch",
+            Self::StaleHighwatermark { .. } => "stale_highwatermark",
+            Self::ShadowedArchive { .. } => "shadowed_archive",
+            Self::OrphanedArchive { .. } => "orphaned_archive",
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::Unparseable => "file is not valid JSON".to_string(),
+            Self::LeftoverTmp => "leftover temp file from an interrupted write".to_string(),
+            Self::IdMismatch { file_id, json_id } => {
+                format!("filename says #{file_id} but id field is {json_id:?}")
+            }
+            Self::StaleHighwatermark { hwm, max_id } => {
+                format!(".highwatermark is {hwm} but highest task ID is {max_id}")
+            }
+            Self::ShadowedArchive { task_id } => {
+                format!("archived #{task_id} also exists as an active task")
+            }
+            Self::OrphanedArchive { task_id, parent_id } => {
+                format!("archived #{task_id} has parent #{parent_id}, which exists nowhere")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub list_id: String,
+    pub path: PathBuf,
+    pub problem: Problem,
+}
+
+impl Finding {
+    fn to_json(&self) -> Value {
+        serde_json::json!({
+            "list": self.list_id,
+            "path": self.path.to_string_lossy(),
+            "kind": self.problem.kind(),
+            "message": self.problem.describe(),
+        })
+    }
+}
+
+/// Walk every task list under the store and collect consistency problems.
+pub fn diagnose(store: &Store) -> Vec<Finding> {
+    let now = SystemTime::now();
+    let mut findings = Vec::new();
+    for list in store.list_task_lists() {
+        let dir = store.tasks_base().join(&list.id);
+        diagnose_list(&list.id, &dir, now, &mut findings);
+    }
+    findings
+}
+
+fn diagnose_list(list_id: &str, dir: &Path, now: SystemTime, out: &mut Vec<Finding>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut active_ids = Vec::new();
+    let mut max_id = 0u64;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+
+        if name.starts_with(".tmp-") {
+            if stale_tmp(&entry, now) {
+                out.push(finding(list_id, path, Problem::LeftoverTmp));
+            }
+            continue;
+        }
+
+        let Some(file_id) = name.strip_suffix(".json") else {
+            continue;
+        };
+        let Some(val) = read_json(&path) else {
+            out.push(finding(list_id, path, Problem::Unparseable));
+            continue;
+        };
+        if let Ok(n) = file_id.parse::<u64>() {
+            max_id = max_id.max(n);
+        }
+        active_ids.push(file_id.to_string());
+
+        let json_id = val.get("id").and_then(|v| v.as_str()).unwrap_or_default();
+        if json_id != file_id {
+            out.push(finding(
+                list_id,
+                path,
+                Problem::IdMismatch {
+                    file_id: file_id.to_string(),
+                    json_id: json_id.to_string(),
+                },
+            ));
+        }
+    }
+
+    // (path, id, parent_id) of each archived task
+    let mut archived = Vec::new();
+    let archive_dir = dir.join("archive");
+    if let Ok(entries) = fs::read_dir(&archive_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with(".tmp-") {
+                if stale_tmp(&entry, now) {
+                    out.push(finding(list_id, path, Problem::LeftoverTmp));
+                }
+                continue;
+            }
+            let Some(file_id) = name.strip_suffix(".json") else {
+                continue;
+            };
+            let Some(val) = read_json(&path) else {
+                out.push(finding(list_id, path, Problem::Unparseable));
+                continue;
+            };
+            if let Ok(n) = file_id.parse::<u64>() {
+                max_id = max_id.max(n);
+            }
+            if active_ids.iter().any(|id| id == file_id) {
+                out.push(finding(
+                    list_id,
+                    path.clone(),
+                    Problem::ShadowedArchive {
+                        task_id: file_id.to_string(),
+                    },
+                ));
+            }
+            let parent = val
+                .pointer("/metadata/parent_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            archived.push((path, file_id.to_string(), parent.to_string()));
+        }
+    }
+    for (path, task_id, parent_id) in &archived {
+        let exists = |id: &String| {
+            active_ids.contains(id) || archived.iter().any(|(_, other, _)| other == id)
+        };
+        if !parent_id.is_empty() && !exists(parent_id) {
+            out.push(finding(
+                list_id,
+                path.clone(),
+                Problem::OrphanedArchive {
+                    task_id: task_id.clone(),
+                    parent_id: parent_id.clone(),
+                },
+            ));
+        }
+    }
+
+    let hwm_path = dir.join(".highwatermark");
+    let hwm = fs::read_to_string(&hwm_path)
+        .ok()
+        .and_then(|s| s.trim().parse::<u64>().ok())
+        .unwrap_or(0);
+    if hwm < max_id {
+        out.push(finding(
+            list_id,
+            hwm_path,
+            Problem::StaleHighwatermark { hwm, max_id },
+        ));
+    }
+}
+
+/// A `.tmp-*` file old enough that no write can still be using it.
+fn stale_tmp(entry: &fs::DirEntry, now: SystemTime) -> bool {
+    entry
+        .metadata()
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| now.duration_since(t).ok())
+        .is_some_and(|age| age >= TMP_GRACE)
+}
+
+fn finding(list_id: &str, path: PathBuf, problem: Problem) -> Finding {
+    Finding {
+        list_id: list_id.to_string(),
+        path,
+        problem,
+    }
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    let data = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+/// Repair a single finding. Anything that cannot be repaired in place is moved
+/// into `<list>/quarantine/` so no data is deleted.
+pub fn repair(store: &Store, f: &Finding) -> Result<String, String> {
+    match &f.problem {
+        Problem::Unparseable | Problem::LeftoverTmp | Problem::ShadowedArchive { .. } => {
+            // An agent may have fixed or removed it since diagnosis
+            let _lock = store.lock_list(&f.list_id)?;
+            if !f.path.is_file() {
+                return Err("file no longer exists".into());
+            }
+            match &f.problem {
+                Problem::Unparseable if read_json(&f.path).is_some() => {
+                    return Err("file parses now; left in place".into());
+                }
+                Problem::ShadowedArchive { task_id } => {
+                    let active = store
+                        .tasks_base()
+                        .join(&f.list_id)
+                        .join(format!("{task_id}.json"));
+                    if !active.is_file() {
+                        return Err(format!("#{task_id} is no longer active; left in place"));
+                    }
+                }
+                _ => {}
+            }
+            let dest = quarantine(store, &f.list_id, &f.path)?;
+            Ok(format!("quarantined → {}", dest.display()))
+        }
+        Problem::IdMismatch { file_id, .. } => {
+            let _lock = store.lock_list(&f.list_id)?;
+            let mut val = read_json(&f.path).ok_or("file no longer parses")?;
+            let obj = val.as_object_mut().ok_or("task is not a JSON object")?;
+            obj.insert("id".into(), Value::String(file_id.clone()));
+            let json = serde_json::to_string_pretty(&val).map_err(|e| format!("serialize: {e}"))?;
+            store::atomic_write(&f.path, &json)?;
+            Ok(format!("set id to {file_id:?}"))
+        }
+        Problem::OrphanedArchive { parent_id, .. } => {
+            // Detach it so it restores as a top-level task
+            let _lock = store.lock_list(&f.list_id)?;
+            let mut val = read_json(&f.path).ok_or("file no longer parses")?;
+            if let Some(meta) = val.get_mut("metadata").and_then(Value::as_object_mut) {
+                meta.remove("parent_id");
+            }
+            let json = serde_json::to_string_pretty(&val).map_err(|e| format!("serialize: {e}"))?;
+            store::atomic_write(&f.path, &json)?;
+            Ok(format!("cleared parent #{parent_id}"))
+        }
+        Problem::StaleHighwatermark { max_id, .. } => {
+            // A create may have moved it past `max_id` since diagnosis
+            let _lock = store.lock_list(&f.list_id)?;
+            let hwm = fs::read_to_string(&f.path)
+                .ok()
+                .and_then(|s| s.trim().parse::<u64>().ok())
+                .unwrap_or(0)
+                .max(*max_id);
+            store::atomic_write(&f.path, &hwm.to_string())?;
+            Ok(format!("set .highwatermark to {hwm}"))
+        }
+    }
+}
+
+fn quarantine(store: &Store, list_id: &str, path: &Path) -> Result<PathBuf, String> {
+    let dir = store.tasks_base().join(list_id).join(QUARANTINE_DIR);
+    fs::create_dir_all(&dir).map_err(|e| format!("create quarantine dir: {e}"))?;
+    let name = path
+        .file_name()
+        .ok_or("no file name")?
+        .to_string_lossy()
+        .to_string();
+    // Archive copies share their name with the active file they shadow
+    let prefix = if path.parent().is_some_and(|p| p.ends_with("archive")) {
+        "archive-"
+    } else {
+        ""
+    };
+    let mut dest = dir.join(format!("{prefix}{name}"));
+    let mut n = 1;
+    while dest.exists() {
+        dest = dir.join(format!("{prefix}{name}.{n}"));
+        n += 1;
+    }
+    fs::rename(path, &dest).map_err(|e| format!("move: {e}"))?;
+    Ok(dest)
+}
+
+pub fn run(store: &Store, fix: bool, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let findings = diagnose(store);
+    let mut unresolved = 0usize;
+    let mut results = Vec::new();
+
+    for f in &findings {
+        let outcome = if fix { Some(repair(store, f)) } else { None };
+        if !matches!(outcome, Some(Ok(_))) {
+            unresolved += 1;
+        }
+        results.push((f, outcome));
+    }
+
+    if json {
+        let out: Vec<Value> = results
+            .iter()
+            .map(|(f, outcome)| {
+                let mut v = f.to_json();
+                match outcome {
+                    Some(Ok(action)) => v["fixed"] = Value::String(action.clone()),
+                    Some(Err(e)) => v["fix_error"] = Value::String(e.clone()),
+                    None => {}
+                }
+                v
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&out)?);
+    } else if findings.is_empty() {
+        println!("{}", ansi::dim("Task store is consistent."));
+    } else {
+        for (f, outcome) in &results {
+            println!(
+                "{} {} {}",
+                ansi::id(&format!("{:<12}", f.list_id)),
+                ansi::dim(&f.path.to_string_lossy()),
+                f.problem.describe()
+            );
+            match outcome {
+                Some(Ok(action)) => println!("  {} {action}", ansi::arrow()),
+                Some(Err(e)) => println!("  {} {}", ansi::arrow(), ansi::blocked(e)),
+                None => {}
+            }
+        }
+        if !fix {
+            println!();
+            println!("{}", ansi::dim("Run with --fix to repair."));
+        }
+    }
+
+    if unresolved > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (tempfile::TempDir, Store, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::with_base(dir.path().to_path_buf());
+        let list_dir = dir.path().join("proj");
+        fs::create_dir_all(&list_dir).unwrap();
+        (dir, store, list_dir)
+    }
+
+    fn kinds(findings: &[Finding]) -> Vec<&'static str> {
+        findings.iter().map(|f| f.problem.kind()).collect()
+    }
+
+    #[test]
+    fn consistent_store_has_no_findings() {
+        let (_dir, store, list_dir) = setup();
+        fs::write(list_dir.join("1.json"), r#"{"id":"1"}"#).unwrap();
+        fs::write(list_dir.join(".highwatermark"), "1").unwrap();
+        assert!(diagnose(&store).is_empty());
+    }
+
+    #[test]
+    fn detects_unparseable_and_id_mismatch() {
+        let (_dir, store, list_dir) = setup();
+        fs::write(list_dir.join("1.json"), "{\"id\": \"1\", ").unwrap();
+        fs::write(list_dir.join("2.json"), r#"{"id":"5"}"#).unwrap();
+        fs::write(list_dir.join(".highwatermark"), "5").unwrap();
+        let mut found = kinds(&diagnose(&store));
+        found.sort();
+        assert_eq!(found, vec!["id_mismatch", "unparseable"]);
+    }
+
+    #[test]
+    fn detects_stale_highwatermark_including_archive() {
+        let (_dir, store, list_dir) = setup();
+        fs::write(list_dir.join("1.json"), r#"{"id":"1"}"#).unwrap();
+        fs::create_dir_all(list_dir.join("archive")).unwrap();
+        fs::write(list_dir.join("archive").join("4.json"), r#"{"id":"4"}"#).unwrap();
+        fs::write(list_dir.join(".highwatermark"), "2").unwrap();
+        let findings = diagnose(&store);
+        assert_eq!(
+            findings[0].problem,
+            Problem::StaleHighwatermark { hwm: 2, max_id: 4 }
+        );
+    }
+
+    #[test]
+    fn fresh_tmp_files_are_ignored() {
+        let (_dir, store, list_dir) = setup();
+        fs::write(list_dir.join(".tmp-1-2"), "{}").unwrap();
+        assert!(diagnose(&store).is_empty());
+    }
+
+    #[test]
+    fn fix_quarantines_and_repairs() {
+        let (_dir, store, list_dir) = setup();
+        fs::write(list_dir.join("1.json"), "not json").unwrap();
+        fs::write(list_dir.join("2.json"), r#"{"id":"9","subject":"x"}"#).unwrap();
+        fs::create_dir_all(list_dir.join("archive")).unwrap();
+        fs::write(list_dir.join("archive").join("2.json"), r#"{"id":"2"}"#).unwrap();
+
+        for f in diagnose(&store) {
+            repair(&store, &f).unwrap();
+        }
+
+        assert!(!list_dir.join("1.json").exists());
+        assert!(list_dir.join(QUARANTINE_DIR).join("1.json").exists());
+        assert!(
+            list_dir
+                .join(QUARANTINE_DIR)
+                .join("archive-2.json")
+                .exists()
+        );
+        let task = store.load_task("proj", "2").unwrap();
+        assert_eq!(task.id, "2");
+        assert_eq!(task.subject, "x");
+        assert_eq!(
+            fs::read_to_string(list_dir.join(".highwatermark")).unwrap(),
+            "2"
+        );
+        assert!(diagnose(&store).is_empty());
+    }
+
+    #[test]
+    fn detects_and_detaches_orphaned_archive_entries() {
+        let (_dir, store, list_dir) = setup();
+        let archive = list_dir.join("archive");
+        fs::create_dir_all(&archive).unwrap();
+        fs::write(list_dir.join("1.json"), r#"{"id":"1"}"#).unwrap();
+        for (id, parent) in [("2", "1"), ("3", "2"), ("4", "7")] {
+            let task = format!(r#"{{"id":"{id}","metadata":{{"parent_id":"{parent}"}}}}"#);
+            fs::write(archive.join(format!("{id}.json")), task).unwrap();
+        }
+        fs::write(list_dir.join(".highwatermark"), "4").unwrap();
+
+        let findings = diagnose(&store);
+        assert_eq!(
+            findings.iter().map(|f| &f.problem).collect::<Vec<_>>(),
+            [&Problem::OrphanedArchive {
+                task_id: "4".into(),
+                parent_id: "7".into()
+            }]
+        );
+        assert_eq!(repair(&store, &findings[0]).unwrap(), "cleared parent #7");
+        let restored = read_json(&archive.join("4.json")).unwrap();
+        assert!(restored.pointer("/metadata/parent_id").is_none());
+        assert!(diagnose(&store).is_empty());
+    }
+
+    #[test]
+    fn quarantines_stale_tmp_files_in_the_archive() {
+        let (_dir, store, list_dir) = setup();
+        let archive = list_dir.join("archive");
+        fs::create_dir_all(&archive).unwrap();
+        let tmp = archive.join(".tmp-1-2");
+        fs::write(&tmp, "{}").unwrap();
+        let old = SystemTime::now() - TMP_GRACE * 2;
+        fs::File::options()
+            .write(true)
+            .open(&tmp)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+
+        let findings = diagnose(&store);
+        assert_eq!(kinds(&findings), ["leftover_tmp"]);
+        repair(&store, &findings[0]).unwrap();
+        assert!(!tmp.exists());
+        assert!(
+            list_dir
+                .join(QUARANTINE_DIR)
+                .join("archive-.tmp-1-2")
+                .exists()
+        );
+        assert!(diagnose(&store).is_empty());
+    }
+
+    #[test]
+    fn quarantine_skips_files_fixed_since_diagnosis() {
+        let (_dir, store, list_dir) = setup();
+        fs::write(list_dir.join("1.json"), "not json").unwrap();
+        fs::write(list_dir.join(".highwatermark"), "1").unwrap();
+        let findings = diagnose(&store);
+        assert_eq!(kinds(&findings), ["unparseable"]);
+
+        // An agent rewrites it validly before --fix runs
+        fs::write(list_dir.join("1.json"), r#"{"id":"1"}"#).unwrap();
+        assert!(repair(&store, &findings[0]).is_err());
+        assert!(list_dir.join("1.json").exists());
+        assert!(!list_dir.join(QUARANTINE_DIR).exists());
+    }
+
+    #[test]
+    fn highwatermark_fix_never_lowers_it() {
+        let (_dir, store, list_dir) = setup();
+        fs::write(list_dir.join("3.json"), r#"{"id":"3"}"#).unwrap();
+        let findings = diagnose(&store);
+        assert_eq!(findings.len(), 1);
+
+        // A create bumps it between diagnosis and repair
+        fs::write(list_dir.join(".highwatermark"), "4").unwrap();
+        repair(&store, &findings[0]).unwrap();
+        assert_eq!(
+            fs::read_to_string(list_dir.join(".highwatermark")).unwrap(),
+            "4"
+        );
+        assert!(diagnose(&store).is_empty());
+    }
+}
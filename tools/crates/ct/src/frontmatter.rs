@@ -0,0 +1,1115 @@
+use std::collections::HashSet;
+
+use crate::artifact;
+
+/// A YAML value, limited to what artifact frontmatter uses: strings
+/// (plain, quoted or block), lists and maps. Scalars are not typed further;
+/// `42` and `true` stay strings.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Scalar(String),
+    List(Vec<Value>),
+    Map(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Scalar(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// List items as strings; a comma-separated scalar counts as a list.
+    pub fn strings(&self) -> Vec<String> {
+        match self {
+            Self::List(items) => items
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect(),
+            Self::Scalar(s) => s
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Self::Null => serde_json::Value::Null,
+            Self::Scalar(s) => serde_json::Value::String(s.clone()),
+            Self::List(items) => items.iter().map(Value::to_json).collect(),
+            Self::Map(entries) => serde_json::Value::Object(
+                entries
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.to_json()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Keys with typed fields, besides the title (`topic`, or `title` in files
+/// that use that instead).
+const KNOWN: [&str; 7] = [
+    "project", "spec", "status", "tags", "links", "created", "updated",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Item {
+    Field {
+        key: String,
+        value: Value,
+        raw: String,
+    },
+    /// Comments, blank lines and anything else kept verbatim.
+    Other(String),
+}
+
+/// Frontmatter shared by plans and specs. Parsed documents remember their
+/// source, so writing one back only re-renders the fields that changed and
+/// keeps comments, quoting and ordering of everything else.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Frontmatter {
+    pub title: String,
+    pub project: String,
+    /// Spec a plan was derived from.
+    pub spec: String,
+    pub status: String,
+    pub tags: Vec<String>,
+    pub links: Vec<String>,
+    pub created: String,
+    pub updated: String,
+    /// Every other key, in source order.
+    pub extras: Vec<(String, Value)>,
+    source: Vec<Item>,
+    raw: String,
+    title_key: String,
+    pristine: Option<Box<Frontmatter>>,
+}
+
+impl Frontmatter {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn parse(yaml: &str) -> Self {
+        let mut p = Parser {
+            lines: yaml.split('\n').collect(),
+            pos: 0,
+        };
+        let mut source = Vec::new();
+        while p.pos < p.lines.len() {
+            let line = p.lines[p.pos];
+            match split_key(line).filter(|_| !ignorable(line) && indent(line) == 0) {
+                Some((key, rest)) => {
+                    let start = p.pos;
+                    p.pos += 1;
+                    let value = p.value(rest, 0, true);
+                    source.push(Item::Field {
+                        key,
+                        value,
+                        raw: p.lines[start..p.pos].join("\n"),
+                    });
+                }
+                None => {
+                    source.push(Item::Other(line.to_string()));
+                    p.pos += 1;
+                }
+            }
+        }
+
+        let has_key = |k: &str| {
+            source
+                .iter()
+                .any(|i| matches!(i, Item::Field { key, .. } if key == k))
+        };
+        let title_key = if !has_key("topic") && has_key("title") {
+            "title"
+        } else {
+            "topic"
+        };
+
+        let mut fm = Frontmatter {
+            title_key: title_key.to_string(),
+            ..Default::default()
+        };
+        for item in &source {
+            if let Item::Field { key, value, .. } = item {
+                fm.assign(key, value);
+            }
+        }
+        fm.pristine = Some(Box::new(fm.clone()));
+        fm.source = source;
+        fm.raw = yaml.to_string();
+        fm
+    }
+
+    fn title_key(&self) -> &str {
+        if self.title_key.is_empty() {
+            "topic"
+        } else {
+            &self.title_key
+        }
+    }
+
+    fn assign(&mut self, key: &str, value: &Value) {
+        let scalar = || value.as_str().unwrap_or_default().to_string();
+        match key {
+            k if k == self.title_key() => self.title = scalar(),
+            "project" => self.project = scalar(),
+            "spec" => self.spec = scalar(),
+            "status" => self.status = scalar(),
+            "tags" => self.tags = value.strings(),
+            "links" => self.links = value.strings(),
+            "created" => self.created = scalar(),
+            "updated" => self.updated = scalar(),
+            _ => match self.extras.iter_mut().find(|(k, _)| k == key) {
+                Some(slot) => slot.1 = value.clone(),
+                None => self.extras.push((key.to_string(), value.clone())),
+            },
+        }
+    }
+
+    /// Current value of `key`, `None` when unset or empty.
+    pub fn get(&self, key: &str) -> Option<Value> {
+        let scalar = |s: &str| (!s.is_empty()).then(|| Value::Scalar(s.to_string()));
+        let list = |l: &[String]| {
+            (!l.is_empty()).then(|| Value::List(l.iter().cloned().map(Value::Scalar).collect()))
+        };
+        match key {
+            k if k == self.title_key() => scalar(&self.title),
+            "project" => scalar(&self.project),
+            "spec" => scalar(&self.spec),
+            "status" => scalar(&self.status),
+            "tags" => list(&self.tags),
+            "links" => list(&self.links),
+            "created" => scalar(&self.created),
+            "updated" => scalar(&self.updated),
+            _ => self
+                .extras
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.clone()),
+        }
+    }
+
+    /// Scalar value of `key`, empty when unset or not a scalar.
+    pub fn get_str(&self, key: &str) -> String {
+        self.get(key)
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default()
+    }
+
+    /// Whether `original` still describes the field, so its source text can
+    /// be kept as written.
+    fn unchanged(&self, key: &str, original: &Value) -> bool {
+        let mut probe = Frontmatter {
+            title_key: self.title_key.clone(),
+            ..Default::default()
+        };
+        probe.assign(key, original);
+        probe.get(key) == self.get(key)
+    }
+
+    fn typed_eq(&self, other: &Frontmatter) -> bool {
+        self.title == other.title
+            && self.project == other.project
+            && self.spec == other.spec
+            && self.status == other.status
+            && self.tags == other.tags
+            && self.links == other.links
+            && self.created == other.created
+            && self.updated == other.updated
+            && self.extras == other.extras
+    }
+
+    /// Keys in output order: as they appear in the source, then new typed
+    /// fields, then new extras.
+    fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = Vec::new();
+        for item in &self.source {
+            if let Item::Field { key, .. } = item
+                && !keys.contains(key)
+            {
+                keys.push(key.clone());
+            }
+        }
+        let typed = std::iter::once(self.title_key().to_string())
+            .chain(KNOWN.iter().map(|k| k.to_string()));
+        for key in typed.chain(self.extras.iter().map(|(k, _)| k.clone())) {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        keys
+    }
+
+    pub fn to_yaml(&self) -> String {
+        if let Some(p) = &self.pristine
+            && self.typed_eq(p)
+        {
+            return self.raw.clone();
+        }
+
+        let mut out: Vec<String> = Vec::new();
+        let mut seen: HashSet<&str> = HashSet::new();
+        for item in &self.source {
+            match item {
+                Item::Other(line) => out.push(line.clone()),
+                Item::Field { key, value, raw } => {
+                    if !seen.insert(key) {
+                        continue;
+                    }
+                    if self.unchanged(key, value) {
+                        out.push(raw.clone());
+                    } else if let Some(v) = self.get(key) {
+                        out.push(render_entry(key, &v, 0));
+                    }
+                }
+            }
+        }
+        for key in self.keys() {
+            if !seen.contains(key.as_str())
+                && let Some(v) = self.get(&key)
+            {
+                out.push(render_entry(&key, &v, 0));
+            }
+        }
+        out.join("\n")
+    }
+
+    /// JSON object of the current fields, in output order.
+    pub fn to_json(&self) -> String {
+        let pairs: Vec<String> = self
+            .keys()
+            .into_iter()
+            .filter_map(|k| {
+                let v = self.get(&k).or_else(|| {
+                    // Explicitly empty source values still show up
+                    self.source.iter().find_map(|i| match i {
+                        Item::Field { key, value, .. } if *key == k => Some(value.clone()),
+                        _ => None,
+                    })
+                })?;
+                Some(format!("{}:{}", serde_json::Value::String(k), v.to_json()))
+            })
+            .collect();
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+/// A markdown artifact: optional frontmatter and the body after it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    pub frontmatter: Option<Frontmatter>,
+    pub body: String,
+    /// The closing `---` ends the file without a newline.
+    closed_at_eof: bool,
+}
+
+impl Document {
+    pub fn new(frontmatter: Frontmatter, body: &str) -> Self {
+        Self {
+            frontmatter: Some(frontmatter),
+            body: body.to_string(),
+            closed_at_eof: false,
+        }
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let (yaml, body) = artifact::parse_frontmatter(content);
+        Self {
+            closed_at_eof: yaml.is_some() && body.is_empty() && !content.ends_with("\n---\n"),
+            frontmatter: yaml.map(Frontmatter::parse),
+            body: body.to_string(),
+        }
+    }
+
+    /// Frontmatter, or an empty one when the document has none.
+    pub fn meta(&self) -> Frontmatter {
+        self.frontmatter.clone().unwrap_or_default()
+    }
+
+    pub fn render(&self) -> String {
+        match &self.frontmatter {
+            None => self.body.clone(),
+            Some(fm) => {
+                let close = if self.closed_at_eof { "---" } else { "---\n" };
+                format!("---\n{}\n{close}{}", fm.to_yaml(), self.body)
+            }
+        }
+    }
+}
+
+/// Render a scalar, quoting it when plain style would read back differently.
+pub fn quote(s: &str) -> String {
+    let reserved = matches!(
+        s.to_ascii_lowercase().as_str(),
+        "true" | "false" | "null" | "~" | "yes" | "no" | "on" | "off"
+    );
+    let plain = !s.is_empty()
+        && !reserved
+        && s == s.trim()
+        && !s.starts_with([
+            '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%',
+            '@', '`',
+        ])
+        && !s.contains(": ")
+        && !s.contains(" #")
+        && !s.ends_with(':')
+        && !s.contains(['\n', '\r', '\t', '"', '\\', ',', '[', ']', '{', '}']);
+    if plain {
+        return s.to_string();
+    }
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn render_block_scalar(s: &str, pad: usize) -> String {
+    let chomp = if !s.ends_with('\n') {
+        "-"
+    } else if s.ends_with("\n\n") {
+        "+"
+    } else {
+        ""
+    };
+    let body: Vec<String> = s
+        .strip_suffix('\n')
+        .unwrap_or(s)
+        .split('\n')
+        .map(|l| {
+            if l.is_empty() {
+                String::new()
+            } else {
+                format!("{}{l}", " ".repeat(pad))
+            }
+        })
+        .collect();
+    format!("|{chomp}\n{}", body.join("\n"))
+}
+
+fn is_inline(v: &Value) -> bool {
+    matches!(v, Value::Scalar(s) if !s.contains('\n'))
+}
+
+fn render_entry(key: &str, value: &Value, pad: usize) -> String {
+    let p = " ".repeat(pad);
+    let key = quote(key);
+    match value {
+        Value::Null => format!("{p}{key}:"),
+        Value::Scalar(s) if s.contains('\n') => {
+            format!("{p}{key}: {}", render_block_scalar(s, pad + 2))
+        }
+        Value::Scalar(s) => format!("{p}{key}: {}", quote(s)),
+        Value::List(items) if items.is_empty() => format!("{p}{key}: []"),
+        Value::List(items) if items.iter().all(is_inline) => {
+            let flow: Vec<String> = items
+                .iter()
+                .map(|v| quote(v.as_str().unwrap_or_default()))
+                .collect();
+            let line = format!("{p}{key}: [{}]", flow.join(", "));
+            if line.len() <= 80 {
+                line
+            } else {
+                let items: Vec<String> = items.iter().map(|v| render_item(v, pad + 2)).collect();
+                format!("{p}{key}:\n{}", items.join("\n"))
+            }
+        }
+        Value::List(items) => {
+            let items: Vec<String> = items.iter().map(|v| render_item(v, pad + 2)).collect();
+            format!("{p}{key}:\n{}", items.join("\n"))
+        }
+        Value::Map(entries) if entries.is_empty() => format!("{p}{key}: {{}}"),
+        Value::Map(entries) => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(k, v)| render_entry(k, v, pad + 2))
+                .collect();
+            format!("{p}{key}:\n{}", entries.join("\n"))
+        }
+    }
+}
+
+fn render_item(value: &Value, pad: usize) -> String {
+    let p = " ".repeat(pad);
+    match value {
+        Value::Null => format!("{p}-"),
+        Value::Scalar(s) if s.contains('\n') => {
+            format!("{p}- {}", render_block_scalar(s, pad + 2))
+        }
+        Value::Scalar(s) => format!("{p}- {}", quote(s)),
+        Value::List(items) if items.is_empty() => format!("{p}- []"),
+        Value::List(items) => {
+            let items: Vec<String> = items.iter().map(|v| render_item(v, pad + 2)).collect();
+            format!("{p}-\n{}", items.join("\n"))
+        }
+        Value::Map(entries) if entries.is_empty() => format!("{p}- {{}}"),
+        Value::Map(entries) => {
+            // First entry shares the dash line, the rest align under it
+            let mut lines: Vec<String> = entries
+                .iter()
+                .map(|(k, v)| render_entry(k, v, pad + 2))
+                .collect();
+            lines[0] = format!("{p}- {}", &lines[0][pad + 2..]);
+            lines.join("\n")
+        }
+    }
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn ignorable(line: &str) -> bool {
+    let t = line.trim();
+    t.is_empty() || t.starts_with('#')
+}
+
+fn is_item(t: &str) -> bool {
+    t == "-" || t.starts_with("- ")
+}
+
+/// `key: rest` → `(key, rest)`. The key ends at the first `:` followed by
+/// whitespace or the end of the line, so URLs and times in values are safe.
+fn split_key(line: &str) -> Option<(String, &str)> {
+    let t = line.trim_start();
+    if t.starts_with(['-', '#', '[', '{', '|', '>']) {
+        return None;
+    }
+    if t.starts_with(['"', '\'']) {
+        let (key, len) = quoted(t)?;
+        let rest = t[len..].trim_start().strip_prefix(':')?;
+        return (rest.is_empty() || rest.starts_with([' ', '\t']))
+            .then(|| (key, rest.trim_start()));
+    }
+    let bytes = t.as_bytes();
+    let idx = (0..bytes.len()).find(|&i| {
+        bytes[i] == b':' && bytes.get(i + 1).is_none_or(|b| *b == b' ' || *b == b'\t')
+    })?;
+    let key = t[..idx].trim();
+    (!key.is_empty()).then(|| (key.to_string(), t[idx + 1..].trim_start()))
+}
+
+/// Parse a quoted scalar at the start of `s`, returning its value and the
+/// number of bytes consumed; `None` if the quote is not closed.
+fn quoted(s: &str) -> Option<(String, usize)> {
+    let quote = s.chars().next()?;
+    let mut out = String::new();
+    let mut chars = s.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        if quote == '\'' {
+            if c == '\'' {
+                if chars.peek().is_some_and(|(_, n)| *n == '\'') {
+                    chars.next();
+                    out.push('\'');
+                    continue;
+                }
+                return Some((out, i + 1));
+            }
+            out.push(c);
+            continue;
+        }
+        match c {
+            '"' => return Some((out, i + 1)),
+            '\\' => {
+                let (_, e) = chars.next()?;
+                match e {
+                    'n' => out.push('\n'),
+                    't' => out.push('\t'),
+                    'r' => out.push('\r'),
+                    '0' => out.push('\0'),
+                    'u' => {
+                        let hex: String = (0..4)
+                            .filter_map(|_| chars.next().map(|(_, h)| h))
+                            .collect();
+                        match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                            Some(ch) => out.push(ch),
+                            None => out.push_str(&format!("\\u{hex}")),
+                        }
+                    }
+                    other => out.push(other),
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    None
+}
+
+/// Scalar text from a single line: quoted, or plain with any trailing
+/// ` # comment` removed.
+fn scalar(t: &str) -> String {
+    if t.starts_with(['"', '\''])
+        && let Some((s, _)) = quoted(t)
+    {
+        return s;
+    }
+    let end = t.find(" #").unwrap_or(t.len());
+    t[..end].trim_end().to_string()
+}
+
+/// Brackets outside quotes are balanced. Quotes only open at the start of
+/// a token, so apostrophes in plain words are not mistaken for one.
+fn balanced(s: &str) -> bool {
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut prev = ' ';
+    for c in s.chars() {
+        match quote {
+            Some(q) => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' && q == '"' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '"' | '\'' if matches!(prev, ' ' | '\t' | '[' | '{' | ',' | ':') => quote = Some(c),
+                '[' | '{' => depth += 1,
+                ']' | '}' => depth -= 1,
+                _ => {}
+            },
+        }
+        prev = c;
+    }
+    depth <= 0 && quote.is_none()
+}
+
+/// Recursive descent over a flow collection (`[a, "b, c"]`, `{k: v}`).
+struct Flow<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl Flow<'_> {
+    fn skip_ws(&mut self) {
+        while self.s[self.pos..].starts_with([' ', '\t']) {
+            self.pos += 1;
+        }
+    }
+
+    /// Step over the `,` after a collection entry that began at `start`. An
+    /// entry that consumed nothing (a stray `}` in a list, say) has its
+    /// first character skipped so the loop always advances.
+    fn delimiter(&mut self, start: usize) {
+        self.skip_ws();
+        let rest = &self.s[self.pos..];
+        if rest.starts_with(',') {
+            self.pos += 1;
+        } else if self.pos == start
+            && let Some(c) = rest.chars().next()
+        {
+            self.pos += c.len_utf8();
+        }
+    }
+
+    fn value(&mut self) -> Value {
+        self.skip_ws();
+        let rest = &self.s[self.pos..];
+        if rest.starts_with('[') {
+            self.pos += 1;
+            let mut items = Vec::new();
+            loop {
+                self.skip_ws();
+                if self.s[self.pos..].is_empty() {
+                    break;
+                }
+                if self.s[self.pos..].starts_with(']') {
+                    self.pos += 1;
+                    break;
+                }
+                let start = self.pos;
+                let item = self.value();
+                self.skip_ws();
+                // `[a: b]` holds a single-pair map
+                if self.s[self.pos..].starts_with(':') {
+                    self.pos += 1;
+                    let key = match item {
+                        Value::Scalar(k) => k,
+                        _ => String::new(),
+                    };
+                    items.push(Value::Map(vec![(key, self.value())]));
+                } else {
+                    items.push(item);
+                }
+                self.delimiter(start);
+            }
+            return Value::List(items);
+        }
+        if rest.starts_with('{') {
+            self.pos += 1;
+            let mut entries = Vec::new();
+            loop {
+                self.skip_ws();
+                if self.s[self.pos..].is_empty() {
+                    break;
+                }
+                if self.s[self.pos..].starts_with('}') {
+                    self.pos += 1;
+                    break;
+                }
+                let start = self.pos;
+                let key = match self.value() {
+                    Value::Scalar(k) => k,
+                    _ => String::new(),
+                };
+                self.skip_ws();
+                let value = if self.s[self.pos..].starts_with(':') {
+                    self.pos += 1;
+                    self.value()
+                } else {
+                    Value::Null
+                };
+                entries.push((key, value));
+                self.delimiter(start);
+            }
+            return Value::Map(entries);
+        }
+        if rest.starts_with(['"', '\''])
+            && let Some((s, len)) = quoted(rest)
+        {
+            self.pos += len;
+            return Value::Scalar(s);
+        }
+        // Plain scalar up to a delimiter; `:` only ends it before a space
+        let bytes = rest.as_bytes();
+        let end = (0..bytes.len())
+            .find(|&i| {
+                matches!(bytes[i], b',' | b']' | b'}')
+                    || (bytes[i] == b':' && bytes.get(i + 1).is_none_or(|b| *b == b' '))
+            })
+            .unwrap_or(bytes.len());
+        self.pos += end;
+        let text = rest[..end].trim();
+        if text.is_empty() {
+            Value::Null
+        } else {
+            Value::Scalar(text.to_string())
+        }
+    }
+}
+
+struct Parser<'a> {
+    lines: Vec<&'a str>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    /// Index of the next line with content, without consuming anything.
+    fn next_content(&self) -> Option<usize> {
+        (self.pos..self.lines.len()).find(|&i| !ignorable(self.lines[i]))
+    }
+
+    /// Value following `key:` (or `- `) on a line indented by `parent`.
+    /// `in_map` allows a block list at the key's own indentation.
+    fn value(&mut self, rest: &str, parent: usize, in_map: bool) -> Value {
+        let t = rest.trim();
+        if t.is_empty() || t.starts_with('#') {
+            return self.nested(parent, in_map);
+        }
+        if let Some((literal, chomp)) = block_header(t) {
+            return Value::Scalar(self.block_scalar(literal, chomp, parent));
+        }
+        if t.starts_with(['[', '{']) {
+            let mut text = t.to_string();
+            while !balanced(&text) && self.pos < self.lines.len() {
+                text.push(' ');
+                text.push_str(self.lines[self.pos].trim());
+                self.pos += 1;
+            }
+            return Flow { s: &text, pos: 0 }.value();
+        }
+
+        // Quoted scalars may span lines until the closing quote
+        let mut text = t.to_string();
+        if t.starts_with(['"', '\'']) {
+            while quoted(&text).is_none() && self.pos < self.lines.len() {
+                let next = self.lines[self.pos].trim();
+                self.pos += 1;
+                text.push(if next.is_empty() { '\n' } else { ' ' });
+                text.push_str(next);
+            }
+            return Value::Scalar(scalar(&text));
+        }
+
+        // Plain scalars continue on more-indented lines, folded with spaces
+        let mut value = scalar(&text);
+        while let Some(line) = self.lines.get(self.pos)
+            && !ignorable(line)
+            && indent(line) > parent
+        {
+            value.push(' ');
+            value.push_str(&scalar(line.trim()));
+            self.pos += 1;
+        }
+        Value::Scalar(value)
+    }
+
+    /// A value that starts on the following lines: a block list or map, or
+    /// nothing.
+    fn nested(&mut self, parent: usize, in_map: bool) -> Value {
+        let Some(i) = self.next_content() else {
+            return Value::Null;
+        };
+        let line = self.lines[i];
+        let ind = indent(line);
+        let t = &line[ind..];
+        if ind > parent || (in_map && ind == parent && is_item(t)) {
+            self.pos = i;
+            if is_item(t) {
+                return Value::List(self.list(ind));
+            }
+            if split_key(t).is_some() {
+                return Value::Map(self.map(ind));
+            }
+            return self.value(t, parent, false);
+        }
+        Value::Null
+    }
+
+    fn map(&mut self, ind: usize) -> Vec<(String, Value)> {
+        let mut out = Vec::new();
+        while let Some(i) = self.next_content() {
+            let line = self.lines[i];
+            if indent(line) != ind || is_item(&line[ind..]) {
+                break;
+            }
+            let Some((key, rest)) = split_key(line) else {
+                break;
+            };
+            self.pos = i + 1;
+            let value = self.value(rest, ind, true);
+            out.push((key, value));
+        }
+        out
+    }
+
+    fn list(&mut self, ind: usize) -> Vec<Value> {
+        let mut out = Vec::new();
+        while let Some(i) = self.next_content() {
+            let line = self.lines[i];
+            if indent(line) != ind || !is_item(&line[ind..]) {
+                break;
+            }
+            self.pos = i + 1;
+            let after_dash = &line[ind + 1..];
+            let rest = after_dash.trim_start();
+            let item_ind = ind + 1 + (after_dash.len() - rest.len());
+
+            if rest.is_empty() {
+                out.push(self.nested(ind, false));
+            } else if !rest.starts_with(['"', '\'', '[', '{'])
+                && let Some((key, vrest)) = split_key(rest)
+            {
+                // `- key: value` opens a map whose keys align after the dash
+                let mut entries = vec![(key, self.value(vrest, item_ind, true))];
+                entries.extend(self.map(item_ind));
+                out.push(Value::Map(entries));
+            } else {
+                out.push(self.value(rest, ind, false));
+            }
+        }
+        out
+    }
+
+    fn block_scalar(&mut self, literal: bool, chomp: char, parent: usize) -> String {
+        let block_indent = (self.pos..self.lines.len())
+            .map(|i| self.lines[i])
+            .find(|l| !l.trim().is_empty())
+            .map(indent)
+            .filter(|&i| i > parent);
+        let Some(block_indent) = block_indent else {
+            return String::new();
+        };
+
+        let mut lines: Vec<&str> = Vec::new();
+        while let Some(line) = self.lines.get(self.pos) {
+            if line.trim().is_empty() {
+                lines.push("");
+            } else if indent(line) >= block_indent {
+                lines.push(&line[block_indent..]);
+            } else {
+                break;
+            }
+            self.pos += 1;
+        }
+        // Trailing blank lines belong to the block only under `+`
+        let trailing = lines.iter().rev().take_while(|l| l.is_empty()).count();
+        if chomp != '+' {
+            self.pos -= trailing;
+        }
+        let content = &lines[..lines.len() - trailing];
+
+        let mut text = if literal {
+            content.join("\n")
+        } else {
+            let mut folded = String::new();
+            for (i, l) in content.iter().enumerate() {
+                if i > 0 {
+                    let prev_blank = content[i - 1].is_empty();
+                    if l.is_empty() || prev_blank {
+                        folded.push('\n');
+                    } else {
+                        folded.push(' ');
+                    }
+                }
+                folded.push_str(l);
+            }
+            folded.replace("\n\n", "\n")
+        };
+        match chomp {
+            '-' => {}
+            '+' => text.push_str(&"\n".repeat(trailing + 1)),
+            _ => text.push('\n'),
+        }
+        text
+    }
+}
+
+/// `|`, `>`, with optional `-`/`+` chomping and indentation digit.
+fn block_header(t: &str) -> Option<(bool, char)> {
+    let literal = match t.chars().next()? {
+        '|' => true,
+        '>' => false,
+        _ => return None,
+    };
+    let rest = t[1..].split(" #").next().unwrap_or_default().trim();
+    let chomp = if rest.contains('-') {
+        '-'
+    } else if rest.contains('+') {
+        '+'
+    } else {
+        ' '
+    };
+    rest.chars()
+        .all(|c| matches!(c, '-' | '+' | '1'..='9'))
+        .then_some((literal, chomp))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRICKY: &str = r#"# written by hand
+topic: "Auth: login & sessions"
+project: /Users/me/src/app.git/wt1
+status: draft   # reviewed later
+tags: [auth, "a, b", 'it''s']
+links:
+  - https://example.com/a?b=c#frag
+  - ../specs/auth.md
+created: 2026-01-02T03:04:05Z
+summary: |
+  First line
+    indented
+
+  after blank
+folded: >-
+  one
+  two
+
+  three
+owner:
+  name: Ana
+  teams:
+  - core
+  - infra
+reviewers:
+  - name: Bo
+    ok: yes
+  - name: Cy
+escaped: "tab\there \"quoted\" é"
+empty:
+pairs: [a: b, : c]
+continued: a plain
+  scalar over lines"#;
+
+    #[test]
+    fn parses_typed_fields_and_extras() {
+        let fm = Frontmatter::parse(TRICKY);
+        assert_eq!(fm.title, "Auth: login & sessions");
+        assert_eq!(fm.project, "/Users/me/src/app.git/wt1");
+        assert_eq!(fm.status, "draft");
+        assert_eq!(fm.tags, vec!["auth", "a, b", "it's"]);
+        assert_eq!(
+            fm.links,
+            vec!["https://example.com/a?b=c#frag", "../specs/auth.md"]
+        );
+        assert_eq!(fm.created, "2026-01-02T03:04:05Z");
+
+        assert_eq!(
+            fm.get_str("summary"),
+            "First line\n  indented\n\nafter blank\n"
+        );
+        assert_eq!(fm.get_str("folded"), "one two\nthree");
+        assert_eq!(
+            fm.get("owner"),
+            Some(Value::Map(vec![
+                ("name".into(), Value::Scalar("Ana".into())),
+                (
+                    "teams".into(),
+                    Value::List(vec![
+                        Value::Scalar("core".into()),
+                        Value::Scalar("infra".into())
+                    ])
+                ),
+            ]))
+        );
+        let Some(Value::List(reviewers)) = fm.get("reviewers") else {
+            panic!("reviewers should be a list");
+        };
+        assert_eq!(
+            reviewers[0],
+            Value::Map(vec![
+                ("name".into(), Value::Scalar("Bo".into())),
+                ("ok".into(), Value::Scalar("yes".into())),
+            ])
+        );
+        assert_eq!(fm.get_str("escaped"), "tab\there \"quoted\" é");
+        assert_eq!(fm.get("empty"), Some(Value::Null));
+        assert_eq!(
+            fm.get("pairs"),
+            Some(Value::List(vec![
+                Value::Map(vec![("a".into(), Value::Scalar("b".into()))]),
+                Value::Map(vec![(String::new(), Value::Scalar("c".into()))]),
+            ]))
+        );
+        assert_eq!(fm.get_str("continued"), "a plain scalar over lines");
+    }
+
+    #[test]
+    fn round_trips_unchanged_and_keeps_untouched_lines() {
+        let doc = format!("---\n{TRICKY}\n---\n# Body\n");
+        assert_eq!(Document::parse(&doc).render(), doc);
+        let eof = format!("---\n{TRICKY}\n---");
+        assert_eq!(Document::parse(&eof).render(), eof);
+
+        let mut parsed = Document::parse(&doc);
+        let fm = parsed.frontmatter.as_mut().unwrap();
+        fm.status = "approved".into();
+        fm.tags.push("new".into());
+        fm.updated = "2026-02-01T00:00:00Z".into();
+        fm.extras.retain(|(k, _)| k != "empty");
+        let out = parsed.render();
+
+        assert!(out.starts_with("---\n# written by hand\ntopic: \"Auth: login & sessions\"\n"));
+        assert!(out.contains("\nstatus: approved\n"));
+        assert!(out.contains("\ntags: [auth, \"a, b\", it's, new]\n"));
+        assert!(out.contains("\nsummary: |\n  First line\n    indented\n\n  after blank\n"));
+        assert!(out.contains("\n  - name: Bo\n    ok: yes\n"));
+        assert!(!out.contains("\nempty:"));
+        assert!(out.ends_with("updated: 2026-02-01T00:00:00Z\n---\n# Body\n"));
+
+        let again = Document::parse(&out).meta();
+        assert_eq!(again.status, "approved");
+        assert_eq!(again.tags.len(), 4);
+        assert_eq!(
+            again.get_str("summary"),
+            "First line\n  indented\n\nafter blank\n"
+        );
+    }
+
+    #[test]
+    fn new_frontmatter_renders_and_reparses() {
+        let mut fm = Frontmatter {
+            title: "Fix: \"quotes\" # and hashes".into(),
+            project: "/p".into(),
+            created: "2026-01-01T00:00:00Z".into(),
+            tags: vec!["x".into(), "true".into()],
+            ..Default::default()
+        };
+        fm.extras
+            .push(("notes".into(), Value::Scalar("line one\nline two".into())));
+        fm.extras.push((
+            "owners".into(),
+            Value::List(vec![Value::Map(vec![
+                ("name".into(), Value::Scalar("Ana".into())),
+                ("role".into(), Value::Scalar("lead".into())),
+            ])]),
+        ));
+        let yaml = fm.to_yaml();
+        assert!(yaml.starts_with("topic: \"Fix: \\\"quotes\\\" # and hashes\"\nproject: /p\n"));
+        assert!(yaml.contains("tags: [x, \"true\"]"));
+        assert!(yaml.contains("notes: |-\n  line one\n  line two"));
+        assert!(yaml.contains("owners:\n  - name: Ana\n    role: lead"));
+
+        let back = Frontmatter::parse(&yaml);
+        assert!(back.typed_eq(&fm));
+        assert_eq!(
+            back.to_json(),
+            r#"{"topic":"Fix: \"quotes\" # and hashes","project":"/p","tags":["x","true"],"created":"2026-01-01T00:00:00Z","notes":"line one\nline two","owners":[{"name":"Ana","role":"lead"}]}"#
+        );
+    }
+
+    #[test]
+    fn quote_only_when_needed() {
+        assert_eq!(quote("Test Topic"), "Test Topic");
+        assert_eq!(quote("/some/project"), "/some/project");
+        assert_eq!(quote("2026-01-01T00:00:00Z"), "2026-01-01T00:00:00Z");
+        assert_eq!(quote("a: b"), "\"a: b\"");
+        assert_eq!(quote(""), "\"\"");
+        assert_eq!(quote("- x"), "\"- x\"");
+        assert_eq!(quote("null"), "\"null\"");
+        assert_eq!(quote("two\nlines"), "\"two\\nlines\"");
+    }
+
+    #[test]
+    fn malformed_flow_collections_terminate() {
+        let fm = Frontmatter::parse("topic: x\nmeta: [a: b, c]");
+        assert_eq!(
+            fm.get("meta"),
+            Some(Value::List(vec![
+                Value::Map(vec![("a".into(), Value::Scalar("b".into()))]),
+                Value::Scalar("c".into()),
+            ]))
+        );
+        let fm = Frontmatter::parse("topic: x\nmeta: [a, : b]");
+        assert_eq!(
+            fm.get("meta"),
+            Some(Value::List(vec![
+                Value::Scalar("a".into()),
+                Value::Map(vec![(String::new(), Value::Scalar("b".into()))]),
+            ]))
+        );
+        for yaml in [
+            "topic: x\ntags: [a: b]",
+            "topic: x\ntags: [a, : b]",
+            "topic: x\ntags: [a}b]",
+            "topic: x\nmeta: {a: b]}",
+            "topic: x\nmeta: {: , ::}",
+        ] {
+            assert_eq!(Frontmatter::parse(yaml).title, "x", "{yaml}");
+        }
+    }
+
+    #[test]
+    fn title_key_follows_source() {
+        let mut fm = Frontmatter::parse("title: Old\nproject: /p");
+        assert_eq!(fm.title, "Old");
+        fm.title = "New".into();
+        assert_eq!(fm.to_yaml(), "title: New\nproject: /p");
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::store::{Status, Task};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    /// `from` blocks `to`.
+    Blocks,
+    /// `from` is the parent of `to`.
+    Parent,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+}
+
+/// All edges between tasks in `tasks`. `blocks` and `blockedBy` describe the
+/// same relation from either end, so both are folded into one edge; links
+/// to tasks outside the slice are dropped.
+pub fn edges(tasks: &[Task]) -> Vec<Edge> {
+    let ids: HashSet<&str> = tasks.iter().map(|t| t.id.as_str()).collect();
+    let mut seen = HashSet::new();
+    let mut out = Vec::new();
+    let mut push = |from: &str, to: &str, kind: EdgeKind| {
+        if from != to && ids.contains(from) && ids.contains(to) {
+            let edge = Edge {
+                from: from.to_string(),
+                to: to.to_string(),
+                kind,
+            };
+            if seen.insert(edge.clone()) {
+                out.push(edge);
+            }
+        }
+    };
+    for t in tasks {
+        if !t.parent_id.is_empty() {
+            push(&t.parent_id, &t.id, EdgeKind::Parent);
+        }
+        for b in &t.blocked_by {
+            push(b, &t.id, EdgeKind::Blocks);
+        }
+        for b in &t.blocks {
+            push(&t.id, b, EdgeKind::Blocks);
+        }
+    }
+    out
+}
+
+/// Tasks connected to `root` through any edge, in either direction.
+pub fn component(tasks: &[Task], root: &str) -> Vec<Task> {
+    let mut adjacent: HashMap<&str, Vec<&str>> = HashMap::new();
+    let all = edges(tasks);
+    for e in &all {
+        adjacent.entry(&e.from).or_default().push(&e.to);
+        adjacent.entry(&e.to).or_default().push(&e.from);
+    }
+    let mut seen: HashSet<&str> = HashSet::from([root]);
+    let mut queue = VecDeque::from([root]);
+    while let Some(id) = queue.pop_front() {
+        for &next in adjacent.get(id).into_iter().flatten() {
+            if seen.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    tasks
+        .iter()
+        .filter(|t| seen.contains(t.id.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Node colour: the status colour (Catppuccin Mocha, matching ui/theme.rs
+/// status_style), muted once completed.
+fn node_color(status: &Status) -> &'static str {
+    match status {
+        Status::Pending | Status::Completed => "#6c7086", // muted
+        Status::InProgress => "#89b4fa",                  // accent
+        Status::Other(_) => "#cdd6f4",                    // text
+    }
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+pub fn to_dot(tasks: &[Task]) -> String {
+    let mut out = String::from("digraph tasks {\n");
+    out.push_str("  rankdir=LR;\n");
+    out.push_str("  node [shape=box, style=rounded, fontname=\"Helvetica\"];\n");
+    for t in tasks {
+        let color = node_color(&t.status);
+        let style = if t.status == Status::Completed {
+            ", style=\"rounded,dashed\""
+        } else {
+            ""
+        };
+        out.push_str(&format!(
+            "  \"{}\" [label=\"#{} {}\\n{}\", color=\"{color}\", fontcolor=\"{color}\"{style}];\n",
+            dot_escape(&t.id),
+            dot_escape(&t.id),
+            dot_escape(&t.subject),
+            dot_escape(t.status.as_str()),
+        ));
+    }
+    for e in edges(tasks) {
+        let attrs = match e.kind {
+            EdgeKind::Blocks => "",
+            EdgeKind::Parent => " [style=dashed, arrowhead=none]",
+        };
+        out.push_str(&format!(
+            "  \"{}\" -> \"{}\"{attrs};\n",
+            dot_escape(&e.from),
+            dot_escape(&e.to)
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Mermaid node IDs must be identifiers; task IDs are usually numeric.
+fn mermaid_id(id: &str) -> String {
+    let safe: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("t{safe}")
+}
+
+pub fn to_mermaid(tasks: &[Task]) -> String {
+    let mut out = String::from("flowchart LR\n");
+    for t in tasks {
+        let label = format!("#{} {}", t.id, t.subject).replace('"', "#quot;");
+        out.push_str(&format!("  {}[\"{label}\"]\n", mermaid_id(&t.id)));
+    }
+    for e in edges(tasks) {
+        let arrow = match e.kind {
+            EdgeKind::Blocks => "-->",
+            EdgeKind::Parent => "-.-",
+        };
+        out.push_str(&format!(
+            "  {} {arrow} {}\n",
+            mermaid_id(&e.from),
+            mermaid_id(&e.to)
+        ));
+    }
+    for t in tasks {
+        let color = node_color(&t.status);
+        let dash = if t.status == Status::Completed {
+            ",stroke-dasharray:4"
+        } else {
+            ""
+        };
+        out.push_str(&format!(
+            "  style {} stroke:{color},color:{color}{dash}\n",
+            mermaid_id(&t.id)
+        ));
+    }
+    out
+}
+
+/// The blocking graph around one task, layered by distance: `upstream[0]`
+/// holds direct blockers, `upstream[1]` their blockers, and so on.
+pub struct Neighborhood<'a> {
+    pub focus: &'a Task,
+    pub parent: Option<&'a Task>,
+    pub children: Vec<&'a Task>,
+    pub upstream: Vec<Vec<&'a Task>>,
+    pub downstream: Vec<Vec<&'a Task>>,
+}
+
+pub fn neighborhood<'a>(tasks: &'a [Task], id: &str, depth: usize) -> Option<Neighborhood<'a>> {
+    let by_id: HashMap<&str, &Task> = tasks.iter().map(|t| (t.id.as_str(), t)).collect();
+    let focus = *by_id.get(id)?;
+    let all = edges(tasks);
+
+    let mut preds: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut succs: HashMap<&str, Vec<&str>> = HashMap::new();
+    for e in all.iter().filter(|e| e.kind == EdgeKind::Blocks) {
+        succs.entry(&e.from).or_default().push(&e.to);
+        preds.entry(&e.to).or_default().push(&e.from);
+    }
+
+    let layers = |adj: &HashMap<&str, Vec<&str>>| {
+        let mut seen: HashSet<&str> = HashSet::from([id]);
+        let mut frontier = vec![id];
+        let mut out: Vec<Vec<&'a Task>> = Vec::new();
+        for _ in 0..depth {
+            let mut next: Vec<&str> = frontier
+                .iter()
+                .flat_map(|f| adj.get(f).into_iter().flatten().copied())
+                .filter(|n| seen.insert(n))
+                .collect();
+            if next.is_empty() {
+                break;
+            }
+            next.sort_by_key(|n| (n.parse::<u64>().unwrap_or(u64::MAX), n.to_string()));
+            out.push(next.iter().filter_map(|n| by_id.get(n).copied()).collect());
+            frontier = next;
+        }
+        out
+    };
+
+    Some(Neighborhood {
+        focus,
+        parent: by_id.get(focus.parent_id.as_str()).copied(),
+        children: tasks.iter().filter(|t| t.parent_id == focus.id).collect(),
+        upstream: layers(&preds),
+        downstream: layers(&succs),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tasks() -> Vec<Task> {
+        [
+            serde_json::json!({"id": "1", "subject": "Epic", "status": "in_progress"}),
+            serde_json::json!({"id": "2", "subject": "Schema", "status": "completed",
+                "blocks": ["3"], "metadata": {"parent_id": "1"}}),
+            serde_json::json!({"id": "3", "subject": "API \"v2\"", "status": "pending",
+                "blockedBy": ["2"], "blocks": ["4"], "metadata": {"parent_id": "1"}}),
+            serde_json::json!({"id": "4", "subject": "UI", "status": "pending",
+                "blockedBy": ["3", "99"]}),
+            serde_json::json!({"id": "5", "subject": "Unrelated", "status": "pending"}),
+        ]
+        .into_iter()
+        .map(Task::from_raw)
+        .collect()
+    }
+
+    #[test]
+    fn edges_merge_both_link_directions_and_drop_dangling() {
+        let e = edges(&tasks());
+        let blocks: Vec<(&str, &str)> = e
+            .iter()
+            .filter(|e| e.kind == EdgeKind::Blocks)
+            .map(|e| (e.from.as_str(), e.to.as_str()))
+            .collect();
+        assert_eq!(blocks, vec![("2", "3"), ("3", "4")]);
+        assert_eq!(e.iter().filter(|e| e.kind == EdgeKind::Parent).count(), 2);
+    }
+
+    #[test]
+    fn dot_and_mermaid_output() {
+        let t = tasks();
+        let dot = to_dot(&t);
+        assert!(dot.starts_with("digraph tasks {"));
+        assert!(dot.contains(r##""3" [label="#3 API \"v2\"\npending""##));
+        assert!(dot.contains("\"2\" -> \"3\";"));
+        assert!(dot.contains("\"1\" -> \"2\" [style=dashed, arrowhead=none];"));
+        assert!(dot.contains("style=\"rounded,dashed\""));
+
+        let mermaid = to_mermaid(&t);
+        assert!(mermaid.contains("t3[\"#3 API #quot;v2#quot;\"]"));
+        assert!(mermaid.contains("t3 --> t4"));
+        assert!(mermaid.contains("t1 -.- t2"));
+    }
+
+    #[test]
+    fn neighborhood_layers_and_component() {
+        let t = tasks();
+        let n = neighborhood(&t, "4", 3).unwrap();
+        let ids = |layer: &Vec<&Task>| layer.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            n.upstream.iter().map(ids).collect::<Vec<_>>(),
+            vec![vec!["3"], vec!["2"]]
+        );
+        assert!(n.downstream.is_empty());
+        assert!(n.parent.is_none());
+
+        let n = neighborhood(&t, "3", 1).unwrap();
+        assert_eq!(n.parent.map(|p| p.id.as_str()), Some("1"));
+        assert_eq!(n.upstream.len(), 1);
+
+        let ids: Vec<String> = component(&t, "4").into_iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec!["1", "2", "3", "4"]);
+    }
+}
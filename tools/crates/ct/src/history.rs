@@ -0,0 +1,270 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::artifact::chrono_rfc3339;
+
+/// Per-list append-only journal, one JSON object per line.
+pub const HISTORY_FILE: &str = ".history.jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Create,
+    Update,
+    Delete,
+    Archive,
+    Unarchive,
+}
+
+impl Op {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Delete => "delete",
+            Self::Archive => "archive",
+            Self::Unarchive => "unarchive",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "create" => Some(Self::Create),
+            "update" => Some(Self::Update),
+            "delete" => Some(Self::Delete),
+            "archive" => Some(Self::Archive),
+            "unarchive" => Some(Self::Unarchive),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub ts: String,
+    pub task_id: String,
+    pub op: Op,
+    pub actor: String,
+    pub changes: Vec<Change>,
+}
+
+impl Entry {
+    pub fn new(op: Op, task_id: &str, actor: String, changes: Vec<Change>) -> Self {
+        Self {
+            ts: chrono_rfc3339(),
+            task_id: task_id.to_string(),
+            op,
+            actor,
+            changes,
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let changes: Vec<Value> = self
+            .changes
+            .iter()
+            .map(|c| serde_json::json!({"field": c.field, "old": c.old, "new": c.new}))
+            .collect();
+        serde_json::json!({
+            "ts": self.ts,
+            "task_id": self.task_id,
+            "op": self.op.as_str(),
+            "actor": self.actor,
+            "changes": changes,
+        })
+    }
+
+    fn from_json(val: &Value) -> Option<Self> {
+        let s = |key: &str| val.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        let changes = val
+            .get("changes")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|c| {
+                        Some(Change {
+                            field: c.get("field")?.as_str()?.to_string(),
+                            old: c.get("old").cloned().unwrap_or(Value::Null),
+                            new: c.get("new").cloned().unwrap_or(Value::Null),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Some(Self {
+            ts: s("ts").unwrap_or_default(),
+            task_id: s("task_id")?,
+            op: Op::parse(&s("op")?)?,
+            actor: s("actor").unwrap_or_default(),
+            changes,
+        })
+    }
+
+    /// Comma-separated list of changed fields, for one-line summaries.
+    pub fn fields_summary(&self) -> String {
+        self.changes
+            .iter()
+            .map(|c| c.field.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Session ID of the agent running `ct`, empty outside a session.
+pub fn current_session() -> String {
+    std::env::var("CLAUDE_SESSION_ID").unwrap_or_default()
+}
+
+/// Who made the change: the `session` making it, falling back to the
+/// task's owner. Not the task's own `session_id`, which names its creator.
+pub fn actor_for(session: &str, task: &Value) -> String {
+    if !session.is_empty() {
+        return session.to_string();
+    }
+    task.get("owner")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Field-level diff of two task JSON objects. `metadata` keys are compared
+/// individually as `metadata.<key>`; a missing side is `null`.
+pub fn diff(old: &Value, new: &Value) -> Vec<Change> {
+    let old = flatten(old);
+    let new = flatten(new);
+    let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter_map(|k| {
+            let o = old.get(k).cloned().unwrap_or(Value::Null);
+            let n = new.get(k).cloned().unwrap_or(Value::Null);
+            (o != n).then(|| Change {
+                field: k.clone(),
+                old: o,
+                new: n,
+            })
+        })
+        .collect()
+}
+
+fn flatten(val: &Value) -> BTreeMap<String, Value> {
+    let mut out = BTreeMap::new();
+    let Some(obj) = val.as_object() else {
+        return out;
+    };
+    for (k, v) in obj {
+        match (k.as_str(), v.as_object()) {
+            ("metadata", Some(meta)) => {
+                for (mk, mv) in meta {
+                    out.insert(format!("metadata.{mk}"), mv.clone());
+                }
+            }
+            _ => {
+                out.insert(k.clone(), v.clone());
+            }
+        }
+    }
+    out
+}
+
+pub fn append(list_dir: &Path, entry: &Entry) -> Result<(), String> {
+    let line = serde_json::to_string(&entry.to_json()).map_err(|e| format!("serialize: {e}"))?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(list_dir.join(HISTORY_FILE))
+        .map_err(|e| format!("open history: {e}"))?;
+    writeln!(file, "{line}").map_err(|e| format!("write history: {e}"))
+}
+
+/// All journal entries for a task, oldest first. Malformed lines are skipped.
+pub fn read(list_dir: &Path, task_id: &str) -> Vec<Entry> {
+    let Ok(data) = fs::read_to_string(list_dir.join(HISTORY_FILE)) else {
+        return Vec::new();
+    };
+    data.lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter_map(|v| Entry::from_json(&v))
+        .filter(|e| e.task_id == task_id)
+        .collect()
+}
+
+/// Render a changed value for display: strings unquoted, null as empty.
+pub fn display_value(v: &Value) -> String {
+    match v {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_reports_changed_and_removed_fields() {
+        let old = serde_json::json!({
+            "subject": "a",
+            "description": "before",
+            "metadata": {"priority": "P1", "branch": "x"}
+        });
+        let new = serde_json::json!({
+            "subject": "a",
+            "description": "after",
+            "metadata": {"priority": "P1"}
+        });
+        let changes = diff(&old, &new);
+        let fields: Vec<&str> = changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["description", "metadata.branch"]);
+        assert_eq!(changes[0].old, Value::String("before".into()));
+        assert_eq!(changes[1].new, Value::Null);
+    }
+
+    #[test]
+    fn actor_prefers_session_over_owner() {
+        let task = serde_json::json!({"owner": "bob", "metadata": {"session_id": "s1"}});
+        assert_eq!(actor_for("s2", &task), "s2");
+        assert_eq!(actor_for("", &task), "bob");
+    }
+
+    #[test]
+    fn append_and_read_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let entry = Entry::new(
+            Op::Update,
+            "3",
+            "s1".into(),
+            diff(
+                &serde_json::json!({"subject": "a"}),
+                &serde_json::json!({"subject": "b"}),
+            ),
+        );
+        append(dir.path(), &entry).unwrap();
+        append(
+            dir.path(),
+            &Entry::new(Op::Archive, "4", String::new(), vec![]),
+        )
+        .unwrap();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(dir.path().join(HISTORY_FILE))
+            .unwrap()
+            .write_all(b"garbage\n")
+            .unwrap();
+
+        let entries = read(dir.path(), "3");
+        assert_eq!(entries, vec![entry]);
+    }
+}
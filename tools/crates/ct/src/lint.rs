@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::phases;
+use crate::validate::Severity;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintKind {
+    NearMissHeader,
+    EmptyPhase,
+    DuplicatePhase,
+    MissingDep,
+    BrokenLink,
+    NoPhases,
+}
+
+impl LintKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::NearMissHeader => "near_miss_header",
+            Self::EmptyPhase => "empty_phase",
+            Self::DuplicatePhase => "duplicate_phase",
+            Self::MissingDep => "missing_dep",
+            Self::BrokenLink => "broken_link",
+            Self::NoPhases => "no_phases",
+        }
+    }
+
+    pub fn severity(self) -> Severity {
+        match self {
+            Self::NearMissHeader | Self::DuplicatePhase | Self::MissingDep => Severity::Error,
+            Self::EmptyPhase | Self::BrokenLink | Self::NoPhases => Severity::Warning,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub kind: LintKind,
+    /// 1-based line in the plan file.
+    pub line: usize,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+
+    pub fn to_json(&self, file: &str) -> serde_json::Value {
+        serde_json::json!({
+            "file": file,
+            "line": self.line,
+            "severity": self.severity().as_str(),
+            "kind": self.kind.as_str(),
+            "message": self.message,
+        })
+    }
+}
+
+pub fn has_errors(diags: &[Diagnostic]) -> bool {
+    diags.iter().any(|d| d.severity() == Severity::Error)
+}
+
+/// The header a line that mentions a phase was probably meant to be, when
+/// `parse_phases` would skip it. Only decorated lines (headings, bold)
+/// count, so prose like "Phase 2 adds caching" is left alone.
+fn near_miss(trimmed: &str) -> Option<String> {
+    let bold = trimmed.starts_with("**") || trimmed.starts_with("__");
+    if !bold && !trimmed.starts_with('#') {
+        return None;
+    }
+    let bare = trimmed
+        .trim_start_matches('#')
+        .trim_matches(|c| c == '*' || c == '_')
+        .trim();
+    let rest = bare
+        .get(..5)
+        .filter(|w| w.eq_ignore_ascii_case("phase"))
+        .map(|_| bare[5..].trim_start())?;
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return None;
+    }
+    let title = rest[digits..]
+        .trim_start_matches(|c: char| c.is_whitespace() || ":-–—.)".contains(c))
+        .trim();
+    let num = &rest[..digits];
+    Some(if bold {
+        format!("**Phase {num}: {title}**")
+    } else {
+        format!("### Phase {num}: {title}")
+    })
+}
+
+/// Local targets of markdown links and images on a line, outside inline
+/// code. URLs and in-page anchors are skipped.
+fn local_links(line: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    for (i, segment) in line.split('`').enumerate() {
+        if i % 2 == 1 {
+            continue;
+        }
+        let mut rest = segment;
+        while let Some(start) = rest.find("](") {
+            let after = &rest[start + 2..];
+            let Some(end) = after.find(')') else {
+                break;
+            };
+            let target = after[..end]
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .trim_start_matches('<')
+                .trim_end_matches('>');
+            let target = target.split(['#', '?']).next().unwrap_or_default();
+            if !target.is_empty() && !target.contains("://") && !target.starts_with("mailto:") {
+                out.push(target);
+            }
+            rest = &after[end..];
+        }
+    }
+    out
+}
+
+fn link_exists(dir: &Path, target: &str) -> bool {
+    match target.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().is_some_and(|h| h.join(rest).exists()),
+        None => dir.join(target).exists(),
+    }
+}
+
+/// Check a plan's phase structure as `parse_phases` sees it. Relative links
+/// resolve against `dir`, the plan's directory. Diagnostics are sorted by
+/// line.
+pub fn lint_plan(content: &str, dir: &Path) -> Vec<Diagnostic> {
+    let body = phases::strip_frontmatter(content);
+    let offset = content[..content.len() - body.len()].lines().count();
+    let lines: Vec<&str> = body.lines().collect();
+    let parsed = phases::declared_deps(phases::parse_phases(content));
+    let mut out = Vec::new();
+    let mut push = |kind, line, message| {
+        out.push(Diagnostic {
+            kind,
+            line,
+            message,
+        })
+    };
+
+    // Header line index of each parsed phase; the parser doesn't know about
+    // code fences, so headers count inside them too
+    let mut headers: Vec<usize> = Vec::new();
+    let mut in_fence = false;
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if phases::phase_header(trimmed).is_some() {
+            headers.push(i);
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        if let Some(fix) = near_miss(trimmed) {
+            push(
+                LintKind::NearMissHeader,
+                offset + i + 1,
+                format!("not parsed as a phase header; expected `{fix}`"),
+            );
+        }
+        for target in local_links(line) {
+            if !link_exists(dir, target) {
+                push(
+                    LintKind::BrokenLink,
+                    offset + i + 1,
+                    format!("link target not found: {target}"),
+                );
+            }
+        }
+    }
+
+    let numbers: Vec<u32> = parsed.iter().map(|p| p.phase).collect();
+    let mut first_seen: HashMap<u32, usize> = HashMap::new();
+    for (idx, (phase, &start)) in parsed.iter().zip(&headers).enumerate() {
+        let end = headers.get(idx + 1).copied().unwrap_or(lines.len());
+        let section = &lines[start + 1..end];
+        let line_no = offset + start + 1;
+        let n = phase.phase;
+
+        match first_seen.get(&n) {
+            Some(first) => push(
+                LintKind::DuplicatePhase,
+                line_no,
+                format!("phase {n} is already defined at line {first}"),
+            ),
+            None => {
+                first_seen.insert(n, line_no);
+            }
+        }
+
+        if phase.tasks.is_empty() {
+            let bullets = section
+                .iter()
+                .any(|l| l.starts_with("- ") || l.starts_with("* "));
+            let hint = if bullets {
+                "; list items need numbers (`1. item`) or checkboxes (`- [ ] item`)"
+            } else {
+                ""
+            };
+            push(
+                LintKind::EmptyPhase,
+                line_no,
+                format!("phase {n} has no tasks{hint}"),
+            );
+        }
+
+        // Deps come from the title and items, so point at the first of
+        // those naming one
+        let dep_line = lines[start..end]
+            .iter()
+            .position(|l| l.to_lowercase().contains("depends on phase"))
+            .map_or(line_no, |i| line_no + i);
+        for dep in phase.deps.iter().filter(|d| !numbers.contains(d)) {
+            push(
+                LintKind::MissingDep,
+                dep_line,
+                format!("phase {n} depends on phase {dep}, which does not exist"),
+            );
+        }
+    }
+
+    if parsed.is_empty() {
+        push(
+            LintKind::NoPhases,
+            offset + 1,
+            "no phases found; expected `### Phase N: Title` or `**Phase N: Title**` headers"
+                .to_string(),
+        );
+    }
+    out.sort_by_key(|d| d.line);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(diags: &[Diagnostic]) -> Vec<(usize, &'static str)> {
+        diags.iter().map(|d| (d.line, d.kind.as_str())).collect()
+    }
+
+    #[test]
+    fn clean_plan_has_no_diagnostics() {
+        let plan = "---\ntopic: X\n---\n### Phase 1: Setup\n1. install\n\n**Phase 2: Build**\n- [ ] compile\n";
+        assert!(lint_plan(plan, Path::new("/nonexistent")).is_empty());
+    }
+
+    #[test]
+    fn reports_structure_problems_at_their_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.md"), "").unwrap();
+        let plan = "\
+---
+topic: X
+---
+### Phase 1: Setup
+1. install, see [notes](notes.md#deps), [spec](../missing.md) and [docs](https://x.y)
+
+## Phase 2 - Build
+- compile
+
+### Phase 2: Test
+Run everything.
+1. run `[not](a-link.md)`, depends on phase 7
+
+### Phase 1: Ship
+- deploy it
+";
+        let diags = lint_plan(plan, dir.path());
+        assert_eq!(
+            kinds(&diags),
+            [
+                (5, "broken_link"),
+                (7, "near_miss_header"),
+                (12, "missing_dep"),
+                (14, "duplicate_phase"),
+                (14, "empty_phase"),
+            ]
+        );
+        assert_eq!(diags[0].message, "link target not found: ../missing.md");
+        assert!(diags[1].message.contains("`### Phase 2: Build`"));
+        assert!(diags[3].message.contains("line 4"));
+        assert!(diags[4].message.contains("checkboxes"));
+        assert!(has_errors(&diags));
+
+        assert_eq!(
+            near_miss("**phase 3 — Ship**").as_deref(),
+            Some("**Phase 3: Ship**")
+        );
+        assert_eq!(near_miss("Phase 3 adds caching"), None);
+        assert_eq!(near_miss("## Phases overview"), None);
+
+        let none = lint_plan("# Notes\n", dir.path());
+        assert_eq!(kinds(&none), [(1, "no_phases")]);
+        assert!(!has_errors(&none));
+    }
+}
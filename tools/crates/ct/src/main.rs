@@ -3,19 +3,36 @@ mod app;
 mod artifact;
 mod cli;
 mod cochanges;
+mod doctor;
 mod editor;
+mod frontmatter;
 mod gitcontext;
+mod graph;
+mod history;
+mod lint;
+mod notes;
 mod notify;
 mod phases;
 mod plan;
 mod planfile;
+mod plantasks;
+mod progress;
+mod query;
+mod revisions;
+mod search;
 mod slug;
 mod spec;
 mod specfile;
 mod store;
+mod template;
+mod trace;
 mod ui;
+mod undo;
+mod validate;
+mod views;
 
 use std::io;
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, mpsc};
 use std::time::Duration;
@@ -53,16 +70,30 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             cli::TaskAction::List {
                 status,
                 sort,
+                query,
+                view,
                 json,
                 tree,
             } => {
                 let (store, cwd) = store_and_cwd();
-                cli::run_list(&store, &cwd, status, sort, json, tree)
+                cli::run_list(&store, &cwd, status, sort, query, view, json, tree)
+            }
+            cli::TaskAction::Ready { json } => {
+                let (store, cwd) = store_and_cwd();
+                cli::run_ready(&store, &cwd, json)
+            }
+            cli::TaskAction::Check { json } => {
+                let (store, cwd) = store_and_cwd();
+                cli::run_check(&store, &cwd, json)
             }
             cli::TaskAction::Show { id, json } => {
                 let (store, cwd) = store_and_cwd();
                 cli::run_show(&store, &cwd, &id, json)
             }
+            cli::TaskAction::History { id, json } => {
+                let (store, cwd) = store_and_cwd();
+                cli::run_history(&store, &cwd, &id, json)
+            }
             cli::TaskAction::Create {
                 subject,
                 description,
@@ -85,6 +116,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 let (store, cwd) = store_and_cwd();
                 cli::run_status(&store, &cwd, &id, &status)
             }
+            cli::TaskAction::Graph { format, root } => {
+                let (store, cwd) = store_and_cwd();
+                cli::run_graph(&store, &cwd, &format, root.as_deref())
+            }
+            cli::TaskAction::Move { id, to, copy } => {
+                let (store, cwd) = store_and_cwd();
+                cli::run_move(&store, &cwd, &id, &to, copy)
+            }
+            cli::TaskAction::Archived { json } => {
+                let (store, cwd) = store_and_cwd();
+                cli::run_archived(&store, &cwd, json)
+            }
+            cli::TaskAction::Unarchive { id } => {
+                let (store, cwd) = store_and_cwd();
+                cli::run_unarchive(&store, &cwd, &id)
+            }
             cli::TaskAction::Prune {
                 days,
                 dry_run,
@@ -110,13 +157,50 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 slug,
                 prefix,
                 body,
-            } => cli::run_plan_create(topic, project, slug, prefix, body),
+                spec,
+                template,
+            } => cli::run_plan_create(topic, project, slug, prefix, body, spec, template),
             cli::PlanAction::Read { file, frontmatter } => cli::run_plan_read(file, frontmatter),
             cli::PlanAction::Latest { project, task_file } => {
                 cli::run_plan_latest(project, task_file)
             }
             cli::PlanAction::Archive { file } => cli::run_plan_archive(file),
             cli::PlanAction::Show { id } => cli::run_plan_show(&id),
+            cli::PlanAction::Note {
+                file,
+                commit,
+                repo,
+                archive,
+            } => {
+                let (_, cwd) = store_and_cwd();
+                cli::run_note(
+                    "plan",
+                    &cwd,
+                    file.as_deref(),
+                    &commit,
+                    repo.as_deref(),
+                    archive,
+                )
+            }
+            cli::PlanAction::History { file } => cli::run_revision_history(&file),
+            cli::PlanAction::Diff { file, rev } => cli::run_revision_diff(&file, rev.as_deref()),
+            cli::PlanAction::Link { file, spec } => {
+                let (_, cwd) = store_and_cwd();
+                cli::run_plan_link(&cwd, &file, spec.as_deref())
+            }
+            cli::PlanAction::Lint { file, json } => cli::run_plan_lint(&file, json),
+            cli::PlanAction::Tick { file, item, undo } => cli::run_plan_tick(&file, &item, undo),
+            cli::PlanAction::Status { file, state } => {
+                cli::run_artifact_status(&file, state.as_deref())
+            }
+            cli::PlanAction::Progress { file, json } => {
+                let (store, cwd) = store_and_cwd();
+                cli::run_plan_progress(&store, &cwd, &file, json)
+            }
+            cli::PlanAction::Tasks { file, dry_run } => {
+                let (store, cwd) = store_and_cwd();
+                cli::run_plan_tasks(&store, &cwd, &file, dry_run)
+            }
             cli::PlanAction::Prune {
                 days,
                 dry_run,
@@ -139,13 +223,44 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 slug,
                 prefix,
                 body,
-            } => cli::run_spec_create(topic, project, slug, prefix, body),
+                template,
+            } => cli::run_spec_create(topic, project, slug, prefix, body, template),
             cli::SpecAction::Read { file, frontmatter } => cli::run_spec_read(file, frontmatter),
             cli::SpecAction::Latest { project, task_file } => {
                 cli::run_spec_latest(project, task_file)
             }
             cli::SpecAction::Archive { file } => cli::run_spec_archive(file),
             cli::SpecAction::Show { id } => cli::run_spec_show(&id),
+            cli::SpecAction::Note {
+                file,
+                commit,
+                repo,
+                archive,
+            } => {
+                let (_, cwd) = store_and_cwd();
+                cli::run_note(
+                    "spec",
+                    &cwd,
+                    file.as_deref(),
+                    &commit,
+                    repo.as_deref(),
+                    archive,
+                )
+            }
+            cli::SpecAction::History { file } => cli::run_revision_history(&file),
+            cli::SpecAction::Diff { file, rev } => cli::run_revision_diff(&file, rev.as_deref()),
+            cli::SpecAction::Status { file, state } => {
+                cli::run_artifact_status(&file, state.as_deref())
+            }
+            cli::SpecAction::Trace {
+                spec,
+                json,
+                all,
+                project,
+            } => {
+                let (store, cwd) = store_and_cwd();
+                cli::run_spec_trace(&store, &cwd, spec.as_deref(), json, all, project)
+            }
             cli::SpecAction::Prune {
                 days,
                 dry_run,
@@ -163,9 +278,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         },
         Some(cli::Command::Notify) => notify::run(),
+        Some(cli::Command::Doctor { fix, json }) => {
+            let (store, _) = store_and_cwd();
+            doctor::run(&store, fix, json)
+        }
+        Some(cli::Command::Search {
+            terms,
+            kind,
+            limit,
+            json,
+        }) => {
+            let (store, cwd) = store_and_cwd();
+            cli::run_search(&store, &cwd, &terms, kind.as_deref(), limit, json)
+        }
         Some(cli::Command::Tool { action }) => match action {
             cli::ToolAction::Slug { words } => cli::run_slug(words),
-            cli::ToolAction::Phases { file } => phases::run_phases(file),
+            cli::ToolAction::Phases { file, analyze } => phases::run_phases(file, analyze),
             cli::ToolAction::Completion { shell } => cli::run_completion(shell),
             cli::ToolAction::Gitcontext {
                 base,
@@ -203,7 +331,8 @@ fn run_tui() -> Result<(), Box<dyn std::error::Error>> {
     let active_list = lists[0].id.clone();
     let tasks = store.list_tasks(&active_list);
 
-    let mut app = app::App::new(store, lists, tasks, active_list);
+    let views_path = views::project_path(&cwd);
+    let mut app = app::App::new(store, lists, tasks, active_list, views_path);
 
     let editor_cmd = std::env::var("VISUAL")
         .or_else(|_| std::env::var("EDITOR"))
@@ -284,10 +413,23 @@ fn run_loop(
             execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
             terminal.show_cursor()?;
 
+            // Plans and specs are edited in place; keep the prior version
+            let before = req
+                .task_id
+                .is_empty()
+                .then(|| std::fs::read_to_string(&req.path).ok())
+                .flatten();
+
             let status = std::process::Command::new(editor_cmd)
                 .arg(&req.path)
                 .status();
 
+            if let Some(before) = before
+                && std::fs::read_to_string(&req.path).is_ok_and(|after| after != before)
+            {
+                let _ = revisions::record(Path::new(&req.path), &before);
+            }
+
             execute!(terminal.backend_mut(), EnterAlternateScreen)?;
             terminal::enable_raw_mode()?;
             terminal.hide_cursor()?;
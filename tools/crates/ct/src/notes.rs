@@ -0,0 +1,257 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::artifact;
+use crate::frontmatter::{Document, Frontmatter};
+
+/// One document inside a git note. Notes hold several plans or specs
+/// concatenated, each starting with its own frontmatter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteDoc {
+    pub index: usize,
+    pub topic: String,
+    pub project: String,
+    pub content: String,
+}
+
+fn frontmatter_value(doc: &str, key: &str) -> String {
+    Document::parse(doc).meta().get_str(key)
+}
+
+pub fn docs(note: &str) -> Vec<NoteDoc> {
+    artifact::split_notes(note)
+        .into_iter()
+        .enumerate()
+        .map(|(index, content)| NoteDoc {
+            index,
+            topic: frontmatter_value(&content, "topic"),
+            project: frontmatter_value(&content, "project"),
+            content: format!("{}\n", content.trim_end()),
+        })
+        .collect()
+}
+
+fn join(docs: &[String]) -> String {
+    docs.iter()
+        .map(|d| format!("{}\n", d.trim_end()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Same artifact: matching `topic` and `created`, or identical content for
+/// documents without them.
+fn same_doc(a: &str, b: &str) -> bool {
+    let key = |d: &str| {
+        (
+            frontmatter_value(d, "topic"),
+            frontmatter_value(d, "created"),
+        )
+    };
+    let (ka, kb) = (key(a), key(b));
+    if ka.0.is_empty() && ka.1.is_empty() {
+        return a.trim_end() == b.trim_end();
+    }
+    ka == kb
+}
+
+/// Add `doc` to `note`, replacing an earlier copy of the same artifact.
+/// Returns the new note, the document's index and whether it replaced one.
+pub fn upsert(note: &str, doc: &str, fallback_topic: &str) -> (String, usize, bool) {
+    // Splitting relies on each document opening with frontmatter
+    let doc = if artifact::parse_frontmatter(doc).0.is_some() {
+        doc.to_string()
+    } else {
+        Document::new(Frontmatter::new(fallback_topic), doc).render()
+    };
+    let mut all: Vec<String> = artifact::split_notes(note);
+    let (index, replaced) = match all.iter().position(|d| same_doc(d, &doc)) {
+        Some(i) => {
+            all[i] = doc;
+            (i, true)
+        }
+        None => {
+            all.push(doc);
+            (all.len() - 1, false)
+        }
+    };
+    (join(&all), index, replaced)
+}
+
+/// Take document `index` out of `note`, keeping the others. Returns the
+/// remaining note (empty when nothing is left) and the removed document.
+pub fn remove(note: &str, index: usize) -> Result<(String, String), String> {
+    let mut all = artifact::split_notes(note);
+    if index >= all.len() {
+        return Err(format!(
+            "no document {index} in note ({} present)",
+            all.len()
+        ));
+    }
+    let removed = all.remove(index);
+    Ok((join(&all), format!("{}\n", removed.trim_end())))
+}
+
+fn git(repo: &str, args: &[&str]) -> Result<String, String> {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .map_err(|e| format!("running git: {e}"))?;
+    if !out.status.success() {
+        return Err(String::from_utf8_lossy(&out.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).to_string())
+}
+
+pub fn repo_root(dir: &str) -> Result<String, String> {
+    git(dir, &["rev-parse", "--show-toplevel"])
+        .map(|s| s.trim().to_string())
+        .map_err(|_| format!("not a git repository: {dir}"))
+}
+
+/// Repository an artifact belongs to: its `project` frontmatter if that is
+/// a git checkout, else `cwd`'s.
+pub fn repo_for(content: &str, cwd: &str) -> Result<String, String> {
+    let project = frontmatter_value(content, "project");
+    if !project.is_empty()
+        && let Ok(root) = repo_root(&project)
+    {
+        return Ok(root);
+    }
+    repo_root(cwd)
+}
+
+pub fn resolve_commit(repo: &str, rev: &str) -> Result<String, String> {
+    git(
+        repo,
+        &["rev-parse", "--verify", &format!("{rev}^{{commit}}")],
+    )
+    .map(|s| s.trim().to_string())
+    .map_err(|_| format!("unknown commit: {rev}"))
+}
+
+/// Commits carrying a note under `refs/notes/<ref_name>`.
+pub fn annotated_commits(repo: &str, ref_name: &str) -> Vec<String> {
+    git(repo, &["notes", &format!("--ref={ref_name}"), "list"])
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|l| l.split_whitespace().nth(1))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Note content under `refs/notes/<ref_name>`, empty when there is none.
+pub fn read(repo: &str, ref_name: &str, commit: &str) -> String {
+    git(
+        repo,
+        &["notes", &format!("--ref={ref_name}"), "show", commit],
+    )
+    .unwrap_or_default()
+}
+
+/// Replace the note on `commit`; an empty note is removed.
+pub fn write(repo: &str, ref_name: &str, commit: &str, note: &str) -> Result<(), String> {
+    let ref_arg = format!("--ref={ref_name}");
+    if note.trim().is_empty() {
+        return git(
+            repo,
+            &["notes", &ref_arg, "remove", "--ignore-missing", commit],
+        )
+        .map(|_| ());
+    }
+    let mut child = Command::new("git")
+        .args([
+            "-C", repo, "notes", &ref_arg, "add", "-f", "-F", "-", commit,
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("running git: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(note.as_bytes())
+            .map_err(|e| format!("writing note: {e}"))?;
+    }
+    let out = child
+        .wait_with_output()
+        .map_err(|e| format!("running git: {e}"))?;
+    if !out.status.success() {
+        return Err(String::from_utf8_lossy(&out.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+/// Add or replace `doc` in the note on `commit`. Returns its index and
+/// whether an earlier copy was replaced.
+pub fn add(
+    repo: &str,
+    ref_name: &str,
+    commit: &str,
+    doc: &str,
+    fallback_topic: &str,
+) -> Result<(usize, bool), String> {
+    let (note, index, replaced) = upsert(&read(repo, ref_name, commit), doc, fallback_topic);
+    write(repo, ref_name, commit, &note)?;
+    Ok((index, replaced))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: &str = "---\ntopic: Auth\ncreated: 2026-01-01T00:00:00Z\n---\n# Auth\nbody a\n";
+    const B: &str = "---\ntopic: Search\ncreated: 2026-01-02T00:00:00Z\n---\nbody b\n";
+
+    #[test]
+    fn upsert_appends_then_replaces_same_artifact() {
+        let (note, idx, replaced) = upsert("", A, "x");
+        assert_eq!((idx, replaced), (0, false));
+        let (note, idx, replaced) = upsert(&note, B, "x");
+        assert_eq!((idx, replaced), (1, false));
+
+        let edited = A.replace("body a", "body a v2");
+        let (note, idx, replaced) = upsert(&note, &edited, "x");
+        assert_eq!((idx, replaced), (0, true));
+
+        let d = docs(&note);
+        assert_eq!(d.len(), 2);
+        assert_eq!(d[0].topic, "Auth");
+        assert!(d[0].content.contains("body a v2"));
+        assert_eq!(d[1].topic, "Search");
+
+        let (_, idx, _) = upsert(&note, "plain body\n", "Loose");
+        assert_eq!(idx, 2);
+    }
+
+    #[test]
+    fn remove_keeps_other_documents() {
+        let (note, _, _) = upsert("", A, "x");
+        let (note, _, _) = upsert(&note, B, "x");
+        let (rest, removed) = remove(&note, 0).unwrap();
+        assert!(removed.contains("body a"));
+        let d = docs(&rest);
+        assert_eq!(d.len(), 1);
+        assert_eq!(d[0].topic, "Search");
+
+        let (empty, _) = remove(&rest, 0).unwrap();
+        assert!(empty.is_empty());
+        assert!(remove(&rest, 3).is_err());
+    }
+
+    #[test]
+    fn horizontal_rules_in_a_body_do_not_split_documents() {
+        let ruled = "---\ntopic: Auth\ncreated: 2026-01-01T00:00:00Z\n---\n# Auth\n\n---\n\nafter rule\n---\nNote: not yaml\n";
+        let (note, _, _) = upsert("", ruled, "x");
+        let (note, _, _) = upsert(&note, B, "x");
+        let d = docs(&note);
+        assert_eq!(d.len(), 2);
+        assert!(d[0].content.ends_with("after rule\n---\nNote: not yaml\n"));
+
+        let (rest, removed) = remove(&note, 1).unwrap();
+        assert_eq!(removed, B);
+        assert_eq!(docs(&rest)[0].content, d[0].content);
+        assert_eq!(docs(&rest).len(), 1);
+    }
+}
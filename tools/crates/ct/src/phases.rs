@@ -1,10 +1,20 @@
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::io::{self, IsTerminal, Read};
 
+#[derive(Debug, PartialEq)]
+pub struct SubTask {
+    pub text: String,
+    pub done: bool,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Task {
     pub text: String,
-    pub sub_tasks: Vec<String>,
+    pub sub_tasks: Vec<SubTask>,
+    /// Ticked `[x]` checkbox in the plan.
+    pub done: bool,
 }
 
 #[derive(Debug)]
@@ -15,7 +25,7 @@ pub struct Phase {
     pub deps: Vec<u32>,
 }
 
-fn strip_frontmatter(content: &str) -> &str {
+pub fn strip_frontmatter(content: &str) -> &str {
     if !content.starts_with("---\n") {
         return content;
     }
@@ -45,6 +55,12 @@ fn parse_heading_phase(line: &str) -> Option<(u32, String)> {
     Some((num, title.trim().to_string()))
 }
 
+/// Phase number and title of a `### Phase N: Title` or `**Phase N: Title**`
+/// line (trimmed).
+pub fn phase_header(line: &str) -> Option<(u32, String)> {
+    parse_bold_phase(line).or_else(|| parse_heading_phase(line))
+}
+
 fn parse_numbered_item(line: &str) -> Option<String> {
     let trimmed = line.trim();
     let dot_pos = trimmed.find('.')?;
@@ -59,6 +75,35 @@ fn parse_numbered_item(line: &str) -> Option<String> {
     Some(rest.to_string())
 }
 
+/// A GitHub task-list checkbox (`[ ]`, `[x]`) at the front of `text`,
+/// followed by a space or nothing: whether it is ticked and the rest.
+fn checkbox_marker(text: &str) -> Option<(bool, &str)> {
+    [("[ ]", false), ("[x]", true), ("[X]", true)]
+        .into_iter()
+        .find_map(|(marker, done)| {
+            let rest = text.strip_prefix(marker)?;
+            (rest.is_empty() || rest.starts_with(' ')).then_some((done, rest))
+        })
+}
+
+/// Split a checkbox off the front of `text`, if it has one.
+fn checkbox(text: &str) -> (bool, String) {
+    match checkbox_marker(text) {
+        Some((done, rest)) => (done, rest.trim().to_string()),
+        None => (false, text.to_string()),
+    }
+}
+
+/// Unindented `- [ ] item`: a top-level checklist entry. Plain unindented
+/// bullets, including ones that open with a `[link](...)`, are prose and
+/// stay ignored.
+fn parse_checklist_item(line: &str) -> Option<(bool, String)> {
+    let rest = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))?;
+    checkbox_marker(rest).map(|(done, rest)| (done, rest.trim().to_string()))
+}
+
 fn is_sub_item(line: &str) -> Option<String> {
     // Indented line starting with - or *
     if !line.starts_with("  ") && !line.starts_with('\t') {
@@ -76,20 +121,57 @@ fn is_independent(text: &str) -> bool {
     lower.contains("independent of") || lower.contains("no dependency")
 }
 
+/// Explicit `depends on phase 1, 3` / `depends on phases 2 and 4` references.
+fn explicit_deps(text: &str) -> Option<Vec<u32>> {
+    let lower = text.to_lowercase();
+    let idx = lower.find("depends on phase")?;
+    let rest = &lower[idx + "depends on phase".len()..];
+    let mut deps = Vec::new();
+    for word in rest.split(|c: char| !c.is_alphanumeric()) {
+        match word {
+            "" | "s" | "and" | "phase" | "phases" => {}
+            w => match w.parse::<u32>() {
+                Ok(n) => deps.push(n),
+                Err(_) => break,
+            },
+        }
+    }
+    (!deps.is_empty()).then_some(deps)
+}
+
 pub fn parse_phases(content: &str) -> Vec<Phase> {
+    parse(content).0
+}
+
+/// `phases` with deps taken from explicit `depends on phase N` references
+/// where a phase has them. `parse_phases` keeps reporting the implicit
+/// previous-phase dep; only the graph analysis and the linter read these.
+pub fn declared_deps(mut phases: Vec<Phase>) -> Vec<Phase> {
+    for p in &mut phases {
+        let texts: Vec<&str> = p.tasks.iter().map(|t| t.text.as_str()).collect();
+        if let Some(deps) = explicit_deps(&format!("{} {}", p.title, texts.join(" "))) {
+            p.deps = deps;
+        }
+    }
+    phases
+}
+
+/// Parse phases, also returning the line index (into `content`) of every
+/// item keyed `N.i` / `N.i.j`, as used by `ct plan tick`.
+fn parse(content: &str) -> (Vec<Phase>, HashMap<String, usize>) {
     let body = strip_frontmatter(content);
+    let offset = content[..content.len() - body.len()].lines().count();
     let lines: Vec<&str> = body.lines().collect();
 
     let mut phases: Vec<Phase> = Vec::new();
+    let mut item_lines: HashMap<String, usize> = HashMap::new();
     let mut last_task_idx: Option<usize> = None;
 
-    for line in &lines {
+    for (line_idx, line) in lines.iter().enumerate() {
         let trimmed = line.trim();
 
         // Check for phase markers
-        if let Some((num, title)) =
-            parse_bold_phase(trimmed).or_else(|| parse_heading_phase(trimmed))
-        {
+        if let Some((num, title)) = phase_header(trimmed) {
             phases.push(Phase {
                 phase: num,
                 title,
@@ -109,18 +191,34 @@ pub fn parse_phases(content: &str) -> Vec<Phase> {
             if let Some(idx) = last_task_idx
                 && let Some(task) = current.tasks.get_mut(idx)
             {
-                task.sub_tasks.push(sub);
+                let (done, text) = checkbox(&sub);
+                task.sub_tasks.push(SubTask { text, done });
+                item_lines
+                    .entry(format!(
+                        "{}.{}.{}",
+                        current.phase,
+                        idx + 1,
+                        task.sub_tasks.len()
+                    ))
+                    .or_insert(offset + line_idx);
             }
             continue;
         }
 
-        // Numbered list items
-        if let Some(text) = parse_numbered_item(trimmed) {
+        // Numbered list items and top-level checklist entries
+        if let Some((done, text)) = parse_numbered_item(trimmed)
+            .map(|t| checkbox(&t))
+            .or_else(|| parse_checklist_item(line))
+        {
             current.tasks.push(Task {
                 text,
                 sub_tasks: Vec::new(),
+                done,
             });
             last_task_idx = Some(current.tasks.len() - 1);
+            item_lines
+                .entry(format!("{}.{}", current.phase, current.tasks.len()))
+                .or_insert(offset + line_idx);
             continue;
         }
     }
@@ -136,7 +234,47 @@ pub fn parse_phases(content: &str) -> Vec<Phase> {
         }
     }
 
-    phases
+    (phases, item_lines)
+}
+
+/// Rewrite the checkbox of one line, adding a checkbox if it has none.
+fn set_checkbox(line: &str, done: bool) -> String {
+    let indent = line.len() - line.trim_start().len();
+    let rest = &line[indent..];
+    let marker = if rest.starts_with("- ") || rest.starts_with("* ") {
+        2
+    } else {
+        let digits = rest.find('.').unwrap_or(0);
+        let after = &rest[digits + 1..];
+        digits + 1 + (after.len() - after.trim_start().len())
+    };
+    let (_, text) = checkbox(&rest[marker..]);
+    let mark = if done { "[x]" } else { "[ ]" };
+    format!("{}{mark} {text}", &line[..indent + marker])
+}
+
+/// Tick (or untick) plan item `key` (`N.i` or `N.i.j`) in `content`.
+/// Returns the rewritten content and the item's text.
+pub fn set_checked(content: &str, key: &str, done: bool) -> Result<(String, String), String> {
+    let (_, item_lines) = parse(content);
+    let &target = item_lines
+        .get(key)
+        .ok_or_else(|| format!("no plan item {key} (expected PHASE.ITEM or PHASE.ITEM.SUB)"))?;
+
+    let mut out = String::with_capacity(content.len() + 4);
+    let mut text = String::new();
+    for (i, line) in content.split_inclusive('\n').enumerate() {
+        if i != target {
+            out.push_str(line);
+            continue;
+        }
+        let body = line.trim_end_matches(['\n', '\r']);
+        let updated = set_checkbox(body, done);
+        text = updated[updated.find("] ").map_or(0, |i| i + 2)..].to_string();
+        out.push_str(&updated);
+        out.push_str(&line[body.len()..]);
+    }
+    Ok((out, text))
 }
 
 pub fn to_json(phases: &[Phase]) -> String {
@@ -161,6 +299,7 @@ pub fn to_json(phases: &[Phase]) -> String {
                     .iter()
                     .map(|s| {
                         let escaped = s
+                            .text
                             .replace('\\', "\\\\")
                             .replace('"', "\\\"")
                             .replace('\n', "\\n")
@@ -169,9 +308,15 @@ pub fn to_json(phases: &[Phase]) -> String {
                         format!("\"{escaped}\"")
                     })
                     .collect();
+                // Sub-task texts stay a plain string array for existing
+                // consumers; their checkbox state is a parallel array
+                let subs_done: Vec<String> =
+                    t.sub_tasks.iter().map(|s| s.done.to_string()).collect();
                 format!(
-                    "{{\"text\": \"{text_escaped}\", \"sub_tasks\": [{}]}}",
-                    subs.join(", ")
+                    "{{\"text\": \"{text_escaped}\", \"sub_tasks\": [{}], \"done\": {}, \"sub_tasks_done\": [{}]}}",
+                    subs.join(", "),
+                    t.done,
+                    subs_done.join(", ")
                 )
             })
             .collect();
@@ -198,6 +343,201 @@ pub fn to_json(phases: &[Phase]) -> String {
     out
 }
 
+/// Validation and scheduling of the phase dependency graph.
+#[derive(Debug, Default)]
+pub struct Analysis {
+    /// Phases grouped into waves; every phase in a wave only depends on
+    /// phases in earlier waves, so a wave can run concurrently.
+    pub waves: Vec<Vec<u32>>,
+    /// Longest dependency chain, weighted by task count.
+    pub critical_path: Vec<u32>,
+    pub critical_tasks: usize,
+    pub cycles: Vec<Vec<u32>>,
+    pub self_deps: Vec<u32>,
+    /// `(phase, dep)` pairs where `dep` is not a phase in the plan.
+    pub missing_deps: Vec<(u32, u32)>,
+    /// Phase numbers skipped between the lowest and highest phase.
+    pub gaps: Vec<u32>,
+    pub duplicates: Vec<u32>,
+}
+
+impl Analysis {
+    /// Gaps in numbering are only a warning; anything else makes the
+    /// schedule untrustworthy.
+    pub fn is_valid(&self) -> bool {
+        self.cycles.is_empty()
+            && self.self_deps.is_empty()
+            && self.missing_deps.is_empty()
+            && self.duplicates.is_empty()
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "valid": self.is_valid(),
+            "waves": self.waves,
+            "critical_path": {
+                "phases": self.critical_path,
+                "tasks": self.critical_tasks,
+            },
+            "cycles": self.cycles,
+            "self_deps": self.self_deps,
+            "missing_deps": self.missing_deps.iter()
+                .map(|(phase, dep)| serde_json::json!({"phase": phase, "dep": dep}))
+                .collect::<Vec<_>>(),
+            "gaps": self.gaps,
+            "duplicates": self.duplicates,
+        })
+    }
+}
+
+pub fn analyze(phases: &[Phase]) -> Analysis {
+    let mut out = Analysis::default();
+
+    // First definition of a phase number wins
+    let mut by_num: BTreeMap<u32, &Phase> = BTreeMap::new();
+    for p in phases {
+        match by_num.entry(p.phase) {
+            Entry::Vacant(e) => {
+                e.insert(p);
+            }
+            Entry::Occupied(_) => {
+                if !out.duplicates.contains(&p.phase) {
+                    out.duplicates.push(p.phase);
+                }
+            }
+        }
+    }
+    if let (Some(&lo), Some(&hi)) = (by_num.keys().next(), by_num.keys().next_back()) {
+        out.gaps = (lo..=hi).filter(|n| !by_num.contains_key(n)).collect();
+    }
+
+    let mut preds: BTreeMap<u32, BTreeSet<u32>> =
+        by_num.keys().map(|&n| (n, BTreeSet::new())).collect();
+    for p in by_num.values() {
+        for &dep in &p.deps {
+            if dep == p.phase {
+                if !out.self_deps.contains(&dep) {
+                    out.self_deps.push(dep);
+                }
+            } else if by_num.contains_key(&dep) {
+                preds.entry(p.phase).or_default().insert(dep);
+            } else {
+                out.missing_deps.push((p.phase, dep));
+            }
+        }
+    }
+
+    // Kahn's algorithm, one wave per round
+    let mut remaining: BTreeMap<u32, BTreeSet<u32>> = preds.clone();
+    while !remaining.is_empty() {
+        let wave: Vec<u32> = remaining
+            .iter()
+            .filter(|(_, deps)| deps.is_empty())
+            .map(|(&n, _)| n)
+            .collect();
+        if wave.is_empty() {
+            break;
+        }
+        for n in &wave {
+            remaining.remove(n);
+        }
+        for deps in remaining.values_mut() {
+            deps.retain(|d| !wave.contains(d));
+        }
+        out.waves.push(wave);
+    }
+    out.cycles = cycles(&remaining);
+
+    // Longest path in wave order: every predecessor is settled first
+    let mut best: HashMap<u32, (usize, Option<u32>)> = HashMap::new();
+    for &n in out.waves.iter().flatten() {
+        let weight = by_num[&n].tasks.len();
+        let (prev_len, prev) = preds[&n]
+            .iter()
+            .filter_map(|d| best.get(d).map(|&(len, _)| (len, Some(*d))))
+            .max_by_key(|&(len, d)| (len, std::cmp::Reverse(d)))
+            .unwrap_or((0, None));
+        best.insert(n, (prev_len + weight, prev));
+    }
+    let end = out
+        .waves
+        .iter()
+        .flatten()
+        .max_by_key(|&&n| (best[&n].0, std::cmp::Reverse(n)));
+    if let Some(&end) = end {
+        out.critical_tasks = best[&end].0;
+        let mut cur = Some(end);
+        while let Some(n) = cur {
+            out.critical_path.push(n);
+            cur = best[&n].1;
+        }
+        out.critical_path.reverse();
+    }
+    out
+}
+
+/// Strongly connected groups among phases Kahn's algorithm couldn't place.
+/// Phases that are merely downstream of a cycle are not reported.
+fn cycles(remaining: &BTreeMap<u32, BTreeSet<u32>>) -> Vec<Vec<u32>> {
+    let reach = |from: u32| {
+        let mut seen = HashSet::new();
+        let mut stack = vec![from];
+        while let Some(n) = stack.pop() {
+            for &d in remaining.get(&n).into_iter().flatten() {
+                if seen.insert(d) {
+                    stack.push(d);
+                }
+            }
+        }
+        seen
+    };
+    let reachable: HashMap<u32, HashSet<u32>> = remaining.keys().map(|&n| (n, reach(n))).collect();
+
+    let mut out = Vec::new();
+    let mut placed = HashSet::new();
+    for &n in remaining.keys() {
+        if placed.contains(&n) || !reachable[&n].contains(&n) {
+            continue;
+        }
+        let group: Vec<u32> = remaining
+            .keys()
+            .copied()
+            .filter(|m| reachable[&n].contains(m) && reachable[m].contains(&n))
+            .collect();
+        placed.extend(group.iter().copied());
+        out.push(group);
+    }
+    out
+}
+
+pub fn run_phases(
+    file_arg: Option<String>,
+    analyze_graph: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let content = if let Some(file) = file_arg {
+        fs::read_to_string(file)?
+    } else if !io::stdin().is_terminal() {
+        let mut buf = String::new();
+        io::stdin().read_to_string(&mut buf)?;
+        buf
+    } else {
+        println!("[]");
+        return Ok(());
+    };
+
+    let phases = parse_phases(&content);
+    if analyze_graph {
+        let analysis = analyze(&declared_deps(phases));
+        println!("{}", serde_json::to_string_pretty(&analysis.to_json())?);
+        if !analysis.is_valid() {
+            std::process::exit(1);
+        }
+    } else {
+        println!("{}", to_json(&phases));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -215,13 +555,25 @@ mod tests {
         Task {
             text: text.to_string(),
             sub_tasks: Vec::new(),
+            done: false,
         }
     }
 
+    fn sub_texts(task: &Task) -> Vec<&str> {
+        task.sub_tasks.iter().map(|s| s.text.as_str()).collect()
+    }
+
     fn task_with_subs(text: &str, subs: &[&str]) -> Task {
         Task {
             text: text.to_string(),
-            sub_tasks: subs.iter().map(|s| s.to_string()).collect(),
+            sub_tasks: subs
+                .iter()
+                .map(|s| SubTask {
+                    text: s.to_string(),
+                    done: false,
+                })
+                .collect(),
+            done: false,
         }
     }
 
@@ -274,6 +626,50 @@ mod tests {
         assert!(phases[1].deps.is_empty());
     }
 
+    #[test]
+    fn explicit_depends_on_overrides_predecessor() {
+        let input = "### Phase 1: A\n1. a\n\n### Phase 2: B\n1. b\n\n\
+### Phase 3: C (depends on phases 1 and 2)\n1. c\n\n\
+### Phase 4: D\n1. depends on phase 1, 3";
+        let phases = parse_phases(input);
+        assert_eq!(phases[2].deps, vec![2]);
+        assert_eq!(phases[3].deps, vec![3]);
+        let phases = declared_deps(phases);
+        assert_eq!(phases[1].deps, vec![1]);
+        assert_eq!(phases[2].deps, vec![1, 2]);
+        assert_eq!(phases[3].deps, vec![1, 3]);
+    }
+
+    #[test]
+    fn analyze_waves_and_critical_path() {
+        let input = "### Phase 1: Schema\n1. a\n\n\
+### Phase 2: API (depends on phase 1)\n1. b\n1. c\n1. d\n\n\
+### Phase 3: CLI (depends on phase 1)\n1. e\n\n\
+### Phase 4: Docs (depends on phases 2 and 3)\n1. f";
+        let a = analyze(&declared_deps(parse_phases(input)));
+        assert!(a.is_valid());
+        assert_eq!(a.waves, vec![vec![1], vec![2, 3], vec![4]]);
+        assert_eq!(a.critical_path, vec![1, 2, 4]);
+        assert_eq!(a.critical_tasks, 5);
+    }
+
+    #[test]
+    fn analyze_reports_invalid_graphs() {
+        let input = "### Phase 1: A (depends on phase 1)\n\n\
+### Phase 2: B (depends on phase 4)\n\n\
+### Phase 4: C (depends on phase 2)\n\n\
+### Phase 5: D (depends on phases 4 and 9)\n\n\
+### Phase 5: Again";
+        let a = analyze(&declared_deps(parse_phases(input)));
+        assert!(!a.is_valid());
+        assert_eq!(a.self_deps, vec![1]);
+        assert_eq!(a.cycles, vec![vec![2, 4]]);
+        assert_eq!(a.missing_deps, vec![(5, 9)]);
+        assert_eq!(a.gaps, vec![3]);
+        assert_eq!(a.duplicates, vec![5]);
+        assert_eq!(a.waves, vec![vec![1]]);
+    }
+
     #[test]
     fn frontmatter_stripped_before_parsing() {
         let input = "---\ntopic: test\n---\n### Phase 1: Real\n1. actual task";
@@ -355,19 +751,19 @@ mod tests {
         // sub_tasks populated correctly
         assert_eq!(phases[0].tasks[0].text, "Setup database");
         assert_eq!(
-            phases[0].tasks[0].sub_tasks,
+            sub_texts(&phases[0].tasks[0]),
             vec!["create schema", "add migrations"]
         );
 
         assert_eq!(phases[0].tasks[1].text, "Configure auth");
         assert_eq!(
-            phases[0].tasks[1].sub_tasks,
+            sub_texts(&phases[0].tasks[1]),
             vec!["jwt setup", "session store"]
         );
 
         assert_eq!(phases[1].tasks[0].text, "User CRUD");
         assert_eq!(
-            phases[1].tasks[0].sub_tasks,
+            sub_texts(&phases[1].tasks[0]),
             vec!["create endpoint", "delete endpoint"]
         );
 
@@ -406,6 +802,50 @@ mod tests {
         assert!(json.contains(r#""sub_tasks": ["install", "configure"]"#));
     }
 
+    #[test]
+    fn checkboxes_parsed_at_both_levels() {
+        let input = "### Phase 1: Setup\n- [x] Install deps\n  - [ ] use npm\n  - [X] pin versions\n\
+1. [ ] Configure\n- plain bullet\n- [docs](a.md) overview\n- [x]done\n";
+        let phases = parse_phases(input);
+        let tasks = &phases[0].tasks;
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].text, "Install deps");
+        assert!(tasks[0].done);
+        assert_eq!(sub_texts(&tasks[0]), vec!["use npm", "pin versions"]);
+        assert!(!tasks[0].sub_tasks[0].done);
+        assert!(tasks[0].sub_tasks[1].done);
+        assert_eq!(tasks[1].text, "Configure");
+        assert!(!tasks[1].done);
+
+        let json = to_json(&phases);
+        assert!(json.contains(
+            r#""text": "Install deps", "sub_tasks": ["use npm", "pin versions"], "done": true, "sub_tasks_done": [false, true]"#
+        ));
+    }
+
+    #[test]
+    fn set_checked_rewrites_only_target_line() {
+        let input = "---\ntopic: t\n---\n### Phase 1: Setup\n1. Install deps\n  - [ ] use npm\n\
+- [ ] Configure\n\n### Phase 2: Ship\n2.  [x] Release\n";
+
+        let (out, text) = set_checked(input, "1.1", true).unwrap();
+        assert_eq!(text, "Install deps");
+        assert!(out.contains("\n1. [x] Install deps\n"));
+
+        let (out, text) = set_checked(&out, "1.1.1", true).unwrap();
+        assert_eq!(text, "use npm");
+        assert!(out.contains("\n  - [x] use npm\n"));
+
+        let (out, _) = set_checked(&out, "1.2", true).unwrap();
+        assert!(out.contains("\n- [x] Configure\n"));
+
+        let (out, _) = set_checked(&out, "2.1", false).unwrap();
+        assert!(out.ends_with("\n2.  [ ] Release\n"));
+        assert!(out.starts_with("---\ntopic: t\n---\n"));
+
+        assert!(set_checked(input, "3.1", true).is_err());
+    }
+
     #[test]
     fn to_json_escapes_newlines_and_tabs() {
         let phases = vec![Phase {
@@ -420,20 +860,3 @@ mod tests {
         assert!(!json.contains('\n') || !json.contains("line1\nline2"));
     }
 }
-
-pub fn run_phases(file_arg: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
-    let content = if let Some(file) = file_arg {
-        fs::read_to_string(file)?
-    } else if !io::stdin().is_terminal() {
-        let mut buf = String::new();
-        io::stdin().read_to_string(&mut buf)?;
-        buf
-    } else {
-        println!("[]");
-        return Ok(());
-    };
-
-    let phases = parse_phases(&content);
-    println!("{}", to_json(&phases));
-    Ok(())
-}
@@ -1,11 +1,12 @@
 use std::collections::HashSet;
 use std::fs;
-use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::SystemTime;
 
-use crate::artifact;
+use crate::artifact::{self, Lifecycle};
+use crate::frontmatter::Document;
+use crate::revisions;
 
 #[derive(Debug, Clone)]
 pub struct Plan {
@@ -13,6 +14,9 @@ pub struct Plan {
     pub path: PathBuf,
     pub title: String,
     pub project: String,
+    /// Frontmatter `spec` link, as written.
+    pub spec: String,
+    pub status: Lifecycle,
     pub mod_time: SystemTime,
     pub size: u64,
 }
@@ -118,7 +122,7 @@ fn list_git_notes_plans(project: &str) -> Vec<Plan> {
         // Each plan starts with "---\n" followed by YAML, then "---\n"
         let short_sha = &commit_sha[..7.min(commit_sha.len())];
         for (idx, chunk) in artifact::split_notes(&content).into_iter().enumerate() {
-            let (title, proj) = extract_frontmatter_from_str(&chunk);
+            let (title, proj, status, spec) = extract_frontmatter_from_str(&chunk);
             let label = if title.is_empty() {
                 format!("note:{short_sha}#{idx}")
             } else {
@@ -133,6 +137,8 @@ fn list_git_notes_plans(project: &str) -> Vec<Plan> {
                 } else {
                     proj
                 },
+                spec,
+                status,
                 mod_time: commit_time,
                 size: chunk.len() as u64,
             });
@@ -141,32 +147,23 @@ fn list_git_notes_plans(project: &str) -> Vec<Plan> {
     plans
 }
 
-fn extract_frontmatter_from_str(content: &str) -> (String, String) {
-    let mut title = String::new();
-    let mut project = String::new();
-    let mut in_frontmatter = false;
-
-    for line in content.lines() {
-        let trimmed = line.trim();
-        if trimmed == "---" {
-            if in_frontmatter {
-                break;
-            }
-            in_frontmatter = true;
-            continue;
-        }
-        if in_frontmatter {
-            if let Some(val) = trimmed.strip_prefix("topic:") {
-                title = val.trim().to_string();
-            } else if let Some(val) = trimmed.strip_prefix("project:") {
-                project = val.trim().to_string();
-            }
-        } else if let Some(t) = trimmed.strip_prefix("# ") {
-            title = t.to_string();
-            break;
-        }
-    }
-    (title, project)
+/// Title, project, status and spec link of a plan: frontmatter `topic`/
+/// `project`/`status`/`spec`, with the first `# ` heading as the fallback
+/// title.
+fn extract_frontmatter_from_str(content: &str) -> (String, String, Lifecycle, String) {
+    let doc = Document::parse(content);
+    let fm = doc.meta();
+    let status = Lifecycle::from_frontmatter(&fm);
+    let title = if fm.title.is_empty() {
+        doc.body
+            .lines()
+            .find_map(|l| l.trim().strip_prefix("# "))
+            .unwrap_or_default()
+            .to_string()
+    } else {
+        fm.title
+    };
+    (title, fm.project, status, fm.spec)
 }
 
 fn list_plans_filtered(archived: bool) -> Vec<Plan> {
@@ -188,6 +185,12 @@ fn collect_plans(base: &Path, dir: &Path, archived: bool, out: &mut Vec<Plan>) {
     for entry in entries.flatten() {
         let path = entry.path();
         if path.is_dir() {
+            if path
+                .file_name()
+                .is_some_and(|n| n == revisions::HISTORY_DIR)
+            {
+                continue;
+            }
             let is_archive = path.file_name().is_some_and(|n| n == "archive");
             // Skip archive dirs when listing active plans, skip non-archive when listing archived
             if is_archive != archived {
@@ -213,12 +216,14 @@ fn collect_plans(base: &Path, dir: &Path, archived: bool, out: &mut Vec<Plan>) {
             .with_extension("")
             .to_string_lossy()
             .to_string();
-        let (title, project) = extract_frontmatter(&path);
+        let (title, project, status, spec) = extract_frontmatter(&path);
         out.push(Plan {
             name,
             path,
             title,
             project,
+            spec,
+            status,
             mod_time: info.modified().unwrap_or(SystemTime::UNIX_EPOCH),
             size: info.len(),
         });
@@ -245,36 +250,10 @@ pub fn load_content(path: &PathBuf) -> String {
     fs::read_to_string(path).unwrap_or_else(|e| format!("Error loading plan: {e}"))
 }
 
-fn extract_frontmatter(path: &Path) -> (String, String) {
-    let Ok(f) = fs::File::open(path) else {
-        return (String::new(), String::new());
-    };
-    let reader = BufReader::new(f);
-    let mut title = String::new();
-    let mut project = String::new();
-    let mut in_frontmatter = false;
-
-    for line in reader.lines().map_while(Result::ok) {
-        let trimmed = line.trim();
-        if trimmed == "---" {
-            if in_frontmatter {
-                break; // end of frontmatter
-            }
-            in_frontmatter = true;
-            continue;
-        }
-        if in_frontmatter {
-            if let Some(val) = trimmed.strip_prefix("topic:") {
-                title = val.trim().to_string();
-            } else if let Some(val) = trimmed.strip_prefix("project:") {
-                project = val.trim().to_string();
-            }
-        } else if let Some(t) = trimmed.strip_prefix("# ") {
-            title = t.to_string();
-            break;
-        }
-    }
-    (title, project)
+fn extract_frontmatter(path: &Path) -> (String, String, Lifecycle, String) {
+    fs::read_to_string(path)
+        .map(|c| extract_frontmatter_from_str(&c))
+        .unwrap_or_default()
 }
 
 pub fn format_size(bytes: u64) -> String {
@@ -3,9 +3,11 @@ use std::fs;
 use std::io::{self, IsTerminal, Read};
 use std::path::{Path, PathBuf};
 use std::process;
-use std::time::SystemTime;
 
 use crate::artifact;
+use crate::frontmatter::{Document, Frontmatter};
+use crate::notes;
+use crate::revisions;
 
 pub use crate::artifact::project_name;
 
@@ -24,6 +26,8 @@ pub fn cmd_create(args: &[String]) {
     let mut slug_flag = String::new();
     let mut prefix = String::new();
     let mut body = String::new();
+    let mut template: Option<String> = None;
+    let mut spec_ref = String::new();
 
     let mut i = 0;
     while i < args.len() {
@@ -48,6 +52,14 @@ pub fn cmd_create(args: &[String]) {
                 i += 1;
                 body = args.get(i).cloned().unwrap_or_default();
             }
+            "--template" => {
+                i += 1;
+                template = args.get(i).cloned();
+            }
+            "--spec" => {
+                i += 1;
+                spec_ref = args.get(i).cloned().unwrap_or_default();
+            }
             _ => {}
         }
         i += 1;
@@ -60,6 +72,17 @@ pub fn cmd_create(args: &[String]) {
         fatal("--project is required");
     }
 
+    let spec_link = if spec_ref.is_empty() {
+        String::new()
+    } else {
+        let cwd = env::current_dir().unwrap_or_default();
+        let specs = crate::spec::list_specs();
+        let found = crate::trace::find_spec(&spec_ref, &cwd, &project, &specs)
+            .unwrap_or_else(|e| fatal(&e))
+            .unwrap_or_else(|| fatal(&format!("spec not found: {spec_ref}")));
+        found.name.clone()
+    };
+
     let s = if slug_flag.is_empty() {
         crate::slug::slug(&topic)
     } else {
@@ -89,19 +112,14 @@ pub fn cmd_create(args: &[String]) {
 
     let now = artifact::chrono_rfc3339();
 
-    let mut buf = String::new();
-    buf.push_str("---\n");
-    buf.push_str(&format!("topic: {}\n", artifact::yaml_quote(&topic)));
-    buf.push_str(&format!("project: {}\n", artifact::yaml_quote(&project)));
-    buf.push_str(&format!("created: {now}\n"));
-    buf.push_str("---\n");
-    if !body.is_empty() {
-        buf.push_str(&body);
-        if !body.ends_with('\n') {
-            buf.push('\n');
-        }
-    }
+    let mut fm = Frontmatter::new(&topic);
+    fm.project = project;
+    fm.spec = spec_link;
+    fm.created = now;
+    let buf = crate::template::compose("plan", template.as_deref(), fm, &s, &body)
+        .unwrap_or_else(|e| fatal(&e));
 
+    revisions::snapshot(&full_path).unwrap_or_else(|e| fatal(&format!("saving revision: {e}")));
     fs::write(&full_path, &buf).unwrap_or_else(|e| fatal(&format!("writing file: {e}")));
     println!("{}", full_path.display());
 }
@@ -125,31 +143,17 @@ pub fn cmd_read(args: &[String]) {
     let content =
         fs::read_to_string(&file_path).unwrap_or_else(|e| fatal(&format!("reading file: {e}")));
 
-    let (yaml, body) = artifact::parse_frontmatter(&content);
+    let doc = Document::parse(&content);
 
     if frontmatter_mode {
-        match yaml {
-            None => println!("{{}}"),
-            Some(y) => {
-                let pairs = artifact::parse_yaml_map(y);
-                print!("{{");
-                for (i, (k, v)) in pairs.iter().enumerate() {
-                    if i > 0 {
-                        print!(",");
-                    }
-                    let k_escaped = k.replace('\\', "\\\\").replace('"', "\\\"");
-                    let v_escaped = v.replace('\\', "\\\\").replace('"', "\\\"");
-                    print!("\"{k_escaped}\":\"{v_escaped}\"");
-                }
-                println!("}}");
-            }
-        }
+        println!("{}", doc.meta().to_json());
     } else {
-        print!("{body}");
+        print!("{}", doc.body);
     }
 }
 
-/// Core logic for `ck plan latest`, extracted for testability.
+/// Core logic for `ck plan latest`, extracted for testability. Approved or
+/// in-progress plans win over newer drafts and finished ones.
 /// Returns `Ok(path)` on success or `Err(message)` on failure.
 pub fn latest_plan(task_file: Option<&str>, project: &str) -> Result<PathBuf, String> {
     // --task-file short-circuits the mtime heuristic entirely.
@@ -162,27 +166,10 @@ pub fn latest_plan(task_file: Option<&str>, project: &str) -> Result<PathBuf, St
     }
 
     let dir = plans_dir(project);
-    let entries = fs::read_dir(&dir)
+    let latest = artifact::latest_in(&dir)
         .map_err(|e| format!("cannot read plans directory {}: {e}", dir.display()))?;
 
-    let mut latest_path: Option<PathBuf> = None;
-    let mut latest_time = SystemTime::UNIX_EPOCH;
-
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.is_dir() || path.extension().and_then(|e| e.to_str()) != Some("md") {
-            continue;
-        }
-        if let Ok(meta) = entry.metadata()
-            && let Ok(modified) = meta.modified()
-            && modified > latest_time
-        {
-            latest_time = modified;
-            latest_path = Some(path);
-        }
-    }
-
-    latest_path.ok_or_else(|| format!("no plan files found in {}", dir.display()))
+    latest.ok_or_else(|| format!("no plan files found in {}", dir.display()))
 }
 
 pub fn cmd_latest(args: &[String]) {
@@ -242,41 +229,20 @@ pub fn cmd_archive(args: &[String]) {
     let content = fs::read_to_string(path).unwrap_or_else(|e| fatal(&format!("reading file: {e}")));
 
     // Extract project path from frontmatter to locate the git repo
-    let (yaml, _) = artifact::parse_frontmatter(&content);
-    let project = yaml
-        .map(|y| {
-            artifact::parse_yaml_map(y)
-                .into_iter()
-                .find(|(k, _)| k == "project")
-                .map(|(_, v)| v)
-                .unwrap_or_default()
-        })
-        .unwrap_or_default();
+    let project = Document::parse(&content).meta().project;
 
     if project.is_empty() {
         fatal("plan has no project field — cannot determine git repo");
     }
 
-    // Find the git toplevel for the project
-    let git_dir = process::Command::new("git")
-        .args(["-C", &project, "rev-parse", "--show-toplevel"])
-        .output()
-        .ok()
-        .filter(|o| o.status.success())
-        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
-        .unwrap_or_else(|| fatal(&format!("not a git repository: {project}")));
-
-    // Store plan content as a git note on HEAD under refs/notes/plans
-    let note_status = process::Command::new("git")
-        .args(["-C", &git_dir, "notes", "--ref=plans", "append", "-F"])
-        .arg(path)
-        .arg("HEAD")
-        .status()
-        .unwrap_or_else(|e| fatal(&format!("running git notes: {e}")));
-
-    if !note_status.success() {
-        fatal("git notes append failed — plan file preserved");
-    }
+    let git_dir = notes::repo_root(&project).unwrap_or_else(|e| fatal(&e));
+    let head = notes::resolve_commit(&git_dir, "HEAD").unwrap_or_else(|e| fatal(&e));
+
+    // Store plan content in the note on HEAD under refs/notes/plans,
+    // replacing an earlier copy rather than duplicating it
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    notes::add(&git_dir, "plans", &head, &content, &stem)
+        .unwrap_or_else(|e| fatal(&format!("git notes failed — plan file preserved: {e}")));
 
     // Note stored successfully — move to archive/ subfolder
     let parent = path
@@ -290,13 +256,19 @@ pub fn cmd_archive(args: &[String]) {
         .unwrap_or_else(|| fatal("cannot determine file name"));
     let dest = archive_dir.join(file_name);
     fs::rename(path, &dest).unwrap_or_else(|e| fatal(&format!("archiving file: {e}")));
+    if let Err(e) = revisions::move_history(path, &dest) {
+        eprintln!(
+            "Warning: revisions left in {}: {e}",
+            revisions::history_dir(path).display()
+        );
+    }
     eprintln!("Archived: {file_path} → git notes + {}", dest.display());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::artifact::{chrono_rfc3339, parse_frontmatter, parse_yaml_map, yaml_quote};
+    use crate::artifact::{Lifecycle, chrono_rfc3339};
 
     #[test]
     fn worktree_path_gets_repo_prefix() {
@@ -352,6 +324,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn latest_prefers_approved_or_in_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, content: &str, age_secs: u64| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            let mtime = std::time::SystemTime::now() - std::time::Duration::from_secs(age_secs);
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(mtime)
+                .unwrap();
+            path
+        };
+        let approved = write("old.md", "---\ntopic: Old\nstatus: approved\n---\n", 300);
+        let draft = write("new.md", "---\ntopic: New\n---\n", 10);
+        assert_eq!(
+            artifact::latest_in(dir.path()).unwrap(),
+            Some(approved.clone())
+        );
+
+        let done = artifact::set_status(
+            &std::fs::read_to_string(&approved).unwrap(),
+            Lifecycle::Done,
+        );
+        assert!(done.contains("status: done\nupdated: "));
+        write("old.md", &done, 300);
+        assert_eq!(artifact::latest_in(dir.path()).unwrap(), Some(draft));
+    }
+
     #[test]
     fn cmd_create_frontmatter_has_no_status_field() {
         let tmp = std::env::temp_dir().join(format!("ck-test-{}", std::process::id()));
@@ -364,24 +367,22 @@ mod tests {
         let slug = crate::slug::slug("Test Topic");
         let file_path = project_dir.join(format!("{slug}.md"));
 
-        let now = chrono_rfc3339();
-        let mut buf = String::new();
-        buf.push_str("---\n");
-        buf.push_str(&format!("topic: {}\n", yaml_quote("Test Topic")));
-        buf.push_str(&format!("project: {}\n", yaml_quote(project_path)));
-        buf.push_str(&format!("created: {now}\n"));
-        buf.push_str("---\n");
+        let mut fm = Frontmatter::new("Test Topic");
+        fm.project = project_path.to_string();
+        fm.created = chrono_rfc3339();
+        let buf = Document::new(fm, "").render();
         std::fs::write(&file_path, &buf).unwrap();
 
         let content = std::fs::read_to_string(&file_path).unwrap();
 
         // Confirm frontmatter does NOT contain a status field.
-        let (yaml, _) = parse_frontmatter(&content);
-        let yaml = yaml.expect("frontmatter must be present");
-        let keys: Vec<_> = parse_yaml_map(yaml).into_iter().map(|(k, _)| k).collect();
+        let fm = Document::parse(&content)
+            .frontmatter
+            .expect("frontmatter must be present");
         assert!(
-            !keys.contains(&"status".to_string()),
-            "frontmatter must not contain a 'status' field, got keys: {keys:?}"
+            fm.get("status").is_none(),
+            "frontmatter must not contain a 'status' field, got: {}",
+            fm.to_json()
         );
 
         std::fs::remove_dir_all(&tmp).ok();
@@ -0,0 +1,558 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::frontmatter::Document;
+use crate::phases::{self, Phase};
+use crate::progress;
+use crate::store::{Store, Task, meta_str_raw, set_links};
+
+/// Metadata key identifying which plan node a task was materialized from:
+/// `epic`, `phase:N`, `item:N.i` or `item:N.i.j`. Together with `plan_file`
+/// it makes re-runs update tasks in place instead of duplicating them.
+pub const PLAN_KEY: &str = "plan_key";
+
+/// Metadata key holding a phase or item task's identity (see `Entry`).
+/// Tasks are matched on it before their position, so inserting, removing or
+/// reordering phases and items leaves the other tasks bound to the same text.
+pub const PLAN_ITEM: &str = "plan_item";
+
+/// Identity of a plan title or item: its text lowercased with whitespace
+/// collapsed, under its parent's identity when it has one.
+pub fn item_ident(parent: Option<&str>, text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let text = text.to_lowercase();
+    match parent {
+        Some(p) => format!("{p} / {text}"),
+        None => text,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Level {
+    Phase,
+    Item,
+    SubItem,
+}
+
+/// A phase, item or sub-item of a plan, with the key and identity its task
+/// is stamped with.
+pub struct Entry<'a> {
+    /// `phase:N`, `item:N.i` or `item:N.i.j`.
+    pub key: String,
+    /// The phase title's `item_ident`; items and sub-items nest theirs under
+    /// it, so the same text in two phases stays apart.
+    pub ident: String,
+    /// Key of the entry it sits under, `epic` for phases.
+    pub parent: String,
+    pub subject: String,
+    pub level: Level,
+    pub phase: &'a Phase,
+    /// Ticked in the plan itself.
+    pub checked: bool,
+}
+
+/// Every phase, item and sub-item of `phases` in plan order. The one place
+/// keys and identities are made, so `sync` and `progress::compute` pair
+/// tasks with the plan the same way.
+pub fn entries(phases: &[Phase]) -> Vec<Entry<'_>> {
+    let mut out = Vec::new();
+    for p in phases {
+        let phase_key = format!("phase:{}", p.phase);
+        let phase_ident = item_ident(None, &p.title);
+        out.push(Entry {
+            key: phase_key.clone(),
+            ident: phase_ident.clone(),
+            parent: "epic".to_string(),
+            subject: format!("Phase {}: {}", p.phase, p.title),
+            level: Level::Phase,
+            phase: p,
+            checked: false,
+        });
+        for (i, item) in p.tasks.iter().enumerate() {
+            let item_key = format!("item:{}.{}", p.phase, i + 1);
+            let ident = item_ident(Some(&phase_ident), &item.text);
+            out.push(Entry {
+                key: item_key.clone(),
+                ident: ident.clone(),
+                parent: phase_key.clone(),
+                subject: item.text.clone(),
+                level: Level::Item,
+                phase: p,
+                checked: item.done,
+            });
+            for (j, sub) in item.sub_tasks.iter().enumerate() {
+                out.push(Entry {
+                    key: format!("{item_key}.{}", j + 1),
+                    ident: item_ident(Some(&ident), &sub.text),
+                    parent: item_key.clone(),
+                    subject: sub.text.clone(),
+                    level: Level::SubItem,
+                    phase: p,
+                    checked: sub.done,
+                });
+            }
+        }
+    }
+    out
+}
+
+/// A task the plan says should exist.
+struct Node {
+    key: String,
+    /// `Entry::ident`, empty for the epic.
+    ident: String,
+    subject: String,
+    task_type: &'static str,
+    parent: Option<String>,
+    deps: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepKind {
+    Create,
+    Update(Vec<&'static str>),
+    /// Item found at a new position (its old `plan_key`), plus any other
+    /// fields that changed.
+    Move(String, Vec<&'static str>),
+    Unchanged,
+    /// Task made for an item the plan no longer has; left as is.
+    Orphan,
+}
+
+#[derive(Debug, Clone)]
+pub struct Step {
+    pub key: String,
+    /// Task ID, or `+key` for a task a dry run would create.
+    pub id: String,
+    pub subject: String,
+    pub kind: StepKind,
+}
+
+/// Plan title: frontmatter `topic`, else the first `# ` heading, else the
+/// file stem.
+fn plan_topic(path: &Path, content: &str) -> String {
+    let doc = Document::parse(content);
+    let topic = doc.meta().title;
+    if !topic.is_empty() {
+        return topic;
+    }
+    if let Some(heading) = doc.body.lines().find_map(|l| l.strip_prefix("# ")) {
+        return heading.trim().to_string();
+    }
+    path.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn nodes(topic: &str, phases: &[Phase]) -> Vec<Node> {
+    let epic = Node {
+        key: "epic".to_string(),
+        ident: String::new(),
+        subject: topic.to_string(),
+        task_type: "epic",
+        parent: None,
+        deps: Vec::new(),
+    };
+    let entries = entries(phases).into_iter().map(|e| {
+        let phase = e.level == Level::Phase;
+        Node {
+            key: e.key,
+            ident: e.ident,
+            subject: e.subject,
+            task_type: if phase { "phase" } else { "" },
+            parent: Some(e.parent),
+            deps: if phase {
+                e.phase.deps.iter().map(|d| format!("phase:{d}")).collect()
+            } else {
+                Vec::new()
+            },
+        }
+    });
+    std::iter::once(epic).chain(entries).collect()
+}
+
+fn set_meta(task: &mut Task, key: &str, value: &str) {
+    if let Some(obj) = task.raw.as_object_mut() {
+        let meta = obj
+            .entry("metadata")
+            .or_insert_with(|| Value::Object(serde_json::Map::new()));
+        if let Some(m) = meta.as_object_mut() {
+            m.insert(key.to_string(), Value::String(value.to_string()));
+        }
+    }
+}
+
+/// Replace the plan-managed entries of `current` with `desired`, keeping
+/// links the user added to tasks outside the plan.
+fn merge_links(current: &[String], desired: &[String], managed: &HashSet<String>) -> Vec<String> {
+    let mut out: Vec<String> = current
+        .iter()
+        .filter(|id| !managed.contains(*id))
+        .cloned()
+        .collect();
+    for id in desired {
+        if !out.contains(id) {
+            out.push(id.clone());
+        }
+    }
+    out
+}
+
+/// Create or update the epic → phase → item → sub-item task tree for the
+/// plan at `path`. Phase `deps` become `blockedBy`/`blocks` links. The list
+/// stays locked for the whole run, and each task is created with its
+/// `plan_key` already set, so an interrupted run never leaves one a re-run
+/// can't find. With `dry_run`, nothing is written and the returned steps
+/// describe what would happen.
+pub fn sync(
+    store: &Store,
+    list_id: &str,
+    path: &Path,
+    content: &str,
+    dry_run: bool,
+) -> Result<Vec<Step>, String> {
+    let plan_file = path.to_string_lossy().to_string();
+    let topic = plan_topic(path, content);
+    let phases = phases::parse_phases(content);
+    if phases.is_empty() {
+        return Err(format!("no phases found in {plan_file}"));
+    }
+    let nodes = nodes(&topic, &phases);
+    let _lock = if dry_run {
+        None
+    } else {
+        Some(store.lock_list(list_id)?)
+    };
+
+    let mut pool: Vec<Task> = store
+        .list_tasks(list_id)
+        .into_iter()
+        .filter(|t| t.plan_file == plan_file && !meta_str_raw(&t.raw, PLAN_KEY).is_empty())
+        .collect();
+
+    // Phases and items by identity first, then anything left by position. A
+    // task that already has an identity only takes a position whose text is
+    // close to its subject, so a removed item's task isn't handed to its
+    // neighbour
+    let mut existing: HashMap<String, Task> = HashMap::new();
+    for node in nodes.iter().filter(|n| !n.ident.is_empty()) {
+        if let Some(i) = pool
+            .iter()
+            .position(|t| meta_str_raw(&t.raw, PLAN_ITEM) == node.ident)
+        {
+            existing.insert(node.key.clone(), pool.remove(i));
+        }
+    }
+    for node in &nodes {
+        if existing.contains_key(&node.key) {
+            continue;
+        }
+        if let Some(i) = pool.iter().position(|t| {
+            meta_str_raw(&t.raw, PLAN_KEY) == node.key
+                && (meta_str_raw(&t.raw, PLAN_ITEM).is_empty()
+                    || progress::similar(&t.subject, &node.subject))
+        }) {
+            existing.insert(node.key.clone(), pool.remove(i));
+        }
+    }
+
+    // Pass 1: make sure every node has a task so links can be resolved
+    let mut ids: HashMap<String, String> = HashMap::new();
+    let mut created: HashSet<String> = HashSet::new();
+    for node in &nodes {
+        if let Some(task) = existing.get(&node.key) {
+            ids.insert(node.key.clone(), task.id.clone());
+            continue;
+        }
+        created.insert(node.key.clone());
+        if dry_run {
+            ids.insert(node.key.clone(), format!("+{}", node.key));
+            continue;
+        }
+        let parent_id = node
+            .parent
+            .as_ref()
+            .and_then(|k| ids.get(k))
+            .cloned()
+            .unwrap_or_default();
+        let mut task = Task::from_raw(serde_json::json!({"subject": node.subject}));
+        task.task_type = node.task_type.to_string();
+        task.parent_id = parent_id;
+        task.plan_file = plan_file.clone();
+        set_meta(&mut task, PLAN_KEY, &node.key);
+        if !node.ident.is_empty() {
+            set_meta(&mut task, PLAN_ITEM, &node.ident);
+        }
+        let task = store.insert_task(list_id, &task)?;
+        ids.insert(node.key.clone(), task.id.clone());
+        existing.insert(node.key.clone(), task);
+    }
+
+    // Pass 2: bring subjects, parents and dependency links in line
+    let managed: HashSet<String> = ids.values().cloned().collect();
+    let mut blocks: HashMap<&str, Vec<String>> = HashMap::new();
+    for node in &nodes {
+        for dep in &node.deps {
+            if ids.contains_key(dep) {
+                blocks.entry(dep).or_default().push(ids[&node.key].clone());
+            }
+        }
+    }
+
+    let mut steps = Vec::new();
+    for node in &nodes {
+        let id = ids[&node.key].clone();
+        let step = |kind| Step {
+            key: node.key.clone(),
+            id: id.clone(),
+            subject: node.subject.clone(),
+            kind,
+        };
+        let Some(current) = existing.get(&node.key) else {
+            steps.push(step(StepKind::Create));
+            continue;
+        };
+
+        let parent_id = node
+            .parent
+            .as_ref()
+            .and_then(|k| ids.get(k))
+            .cloned()
+            .unwrap_or_default();
+        let deps: Vec<String> = node
+            .deps
+            .iter()
+            .filter_map(|k| ids.get(k))
+            .cloned()
+            .collect();
+        let blocked_by = merge_links(&current.blocked_by, &deps, &managed);
+        let blocks = merge_links(
+            &current.blocks,
+            blocks.get(node.key.as_str()).map_or(&[][..], Vec::as_slice),
+            &managed,
+        );
+
+        let mut changed = Vec::new();
+        if current.subject != node.subject {
+            changed.push("subject");
+        }
+        if current.task_type != node.task_type {
+            changed.push("type");
+        }
+        if current.parent_id != parent_id {
+            changed.push("parent");
+        }
+        if current.blocked_by != blocked_by {
+            changed.push("blocked_by");
+        }
+        if current.blocks != blocks {
+            changed.push("blocks");
+        }
+        let old_key = meta_str_raw(&current.raw, PLAN_KEY);
+        // Tasks from before identities were recorded pick one up silently
+        let restamp = old_key != node.key || meta_str_raw(&current.raw, PLAN_ITEM) != node.ident;
+
+        if !dry_run && (restamp || !changed.is_empty()) {
+            let mut task = current.clone();
+            task.subject = node.subject.clone();
+            task.task_type = node.task_type.to_string();
+            task.parent_id = parent_id;
+            set_links(&mut task, blocks, blocked_by);
+            set_meta(&mut task, PLAN_KEY, &node.key);
+            if !node.ident.is_empty() {
+                set_meta(&mut task, PLAN_ITEM, &node.ident);
+            }
+            store.write_task(list_id, &task)?;
+        }
+        let kind = if created.contains(&node.key) {
+            StepKind::Create
+        } else if old_key != node.key {
+            StepKind::Move(old_key, changed)
+        } else if changed.is_empty() {
+            StepKind::Unchanged
+        } else {
+            StepKind::Update(changed)
+        };
+        steps.push(step(kind));
+    }
+
+    for task in pool {
+        steps.push(Step {
+            key: meta_str_raw(&task.raw, PLAN_KEY),
+            id: task.id,
+            subject: task.subject,
+            kind: StepKind::Orphan,
+        });
+    }
+    Ok(steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Status;
+
+    const PLAN: &str = "---\ntopic: Auth rewrite\n---\n\
+### Phase 1: Schema\n1. Add users table\n  - index email\n\n\
+### Phase 2: API\n1. Login endpoint\n2. Logout endpoint\n";
+
+    fn setup() -> (tempfile::TempDir, Store, std::path::PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("proj")).unwrap();
+        let plan = dir.path().join("plan.md");
+        std::fs::write(&plan, PLAN).unwrap();
+        let store = Store::with_base(dir.path().to_path_buf());
+        (dir, store, plan)
+    }
+
+    fn by_key(store: &Store) -> HashMap<String, Task> {
+        store
+            .list_tasks("proj")
+            .into_iter()
+            .map(|t| (meta_str_raw(&t.raw, PLAN_KEY), t))
+            .collect()
+    }
+
+    #[test]
+    fn dry_run_writes_nothing() {
+        let (_dir, store, plan) = setup();
+        let steps = sync(&store, "proj", &plan, PLAN, true).unwrap();
+        assert_eq!(steps.len(), 7);
+        assert!(steps.iter().all(|s| s.kind == StepKind::Create));
+        assert_eq!(steps[0].subject, "Auth rewrite");
+        assert!(store.list_tasks("proj").is_empty());
+    }
+
+    #[test]
+    fn builds_tree_with_phase_links() {
+        let (_dir, store, plan) = setup();
+        sync(&store, "proj", &plan, PLAN, false).unwrap();
+        let t = by_key(&store);
+        assert_eq!(t.len(), 7);
+
+        let epic = &t["epic"];
+        assert_eq!(epic.task_type, "epic");
+        assert_eq!(epic.plan_file, plan.to_string_lossy());
+        let p1 = &t["phase:1"];
+        let p2 = &t["phase:2"];
+        assert_eq!(p1.parent_id, epic.id);
+        assert_eq!(p2.blocked_by, vec![p1.id.clone()]);
+        assert_eq!(p1.blocks, vec![p2.id.clone()]);
+        assert_eq!(t["item:1.1"].parent_id, p1.id);
+        assert_eq!(t["item:1.1.1"].subject, "index email");
+        assert_eq!(t["item:1.1.1"].parent_id, t["item:1.1"].id);
+
+        // Written whole in one go, so a crash can't leave it unkeyed
+        let history = store.task_history("proj", &t["item:1.1"].id);
+        assert_eq!(history.len(), 1);
+        assert!(
+            history[0]
+                .changes
+                .iter()
+                .any(|c| c.field == "metadata.plan_key")
+        );
+    }
+
+    #[test]
+    fn rerun_updates_instead_of_duplicating() {
+        let (_dir, store, plan) = setup();
+        sync(&store, "proj", &plan, PLAN, false).unwrap();
+        let mut done = by_key(&store).remove("item:2.1").unwrap();
+        done.status = Status::Completed;
+        store.save_task("proj", &done).unwrap();
+
+        let steps = sync(&store, "proj", &plan, PLAN, false).unwrap();
+        assert!(steps.iter().all(|s| s.kind == StepKind::Unchanged));
+
+        let edited = PLAN.replace("Login endpoint", "Login endpoint (OAuth)");
+        let edited = format!("{edited}\n### Phase 3: Docs\n1. Write guide\n");
+        let steps = sync(&store, "proj", &plan, &edited, false).unwrap();
+        let t = by_key(&store);
+        assert_eq!(t.len(), 9);
+        assert_eq!(t["item:2.1"].subject, "Login endpoint (OAuth)");
+        assert_eq!(t["item:2.1"].status, Status::Completed);
+        assert_eq!(t["phase:3"].blocked_by, vec![t["phase:2"].id.clone()]);
+        assert!(
+            steps
+                .iter()
+                .any(|s| s.key == "item:2.1" && s.kind == StepKind::Update(vec!["subject"]))
+        );
+        assert!(
+            steps
+                .iter()
+                .any(|s| s.key == "phase:2" && s.kind == StepKind::Update(vec!["blocks"]))
+        );
+    }
+
+    #[test]
+    fn items_keep_their_tasks_when_the_plan_is_reordered() {
+        let (_dir, store, plan) = setup();
+        sync(&store, "proj", &plan, PLAN, false).unwrap();
+        let mut logout = by_key(&store).remove("item:2.2").unwrap();
+        logout.status = Status::Completed;
+        store.save_task("proj", &logout).unwrap();
+        let login = by_key(&store).remove("item:2.1").unwrap();
+
+        // Login dropped, Logout moved up, a new item after it
+        let edited = PLAN.replace(
+            "1. Login endpoint\n2. Logout endpoint\n",
+            "1. Logout endpoint\n2. Rate limiting\n",
+        );
+        let steps = sync(&store, "proj", &plan, &edited, false).unwrap();
+        let kind = |id: &str| steps.iter().find(|s| s.id == id).map(|s| s.kind.clone());
+        assert_eq!(
+            kind(&logout.id),
+            Some(StepKind::Move("item:2.2".into(), Vec::new()))
+        );
+        assert_eq!(kind(&login.id), Some(StepKind::Orphan));
+        let limit = steps.iter().find(|s| s.subject == "Rate limiting").unwrap();
+        assert_eq!(limit.kind, StepKind::Create);
+
+        let tasks = store.list_tasks("proj");
+        let get = |id: &str| tasks.iter().find(|t| t.id == id).unwrap();
+        assert_eq!(get(&logout.id).status, Status::Completed);
+        assert_eq!(meta_str_raw(&get(&logout.id).raw, PLAN_KEY), "item:2.1");
+        assert_eq!(get(&login.id).subject, "Login endpoint");
+        assert_eq!(meta_str_raw(&get(&limit.id).raw, PLAN_KEY), "item:2.2");
+
+        let steps = sync(&store, "proj", &plan, &edited, false).unwrap();
+        let orphans: Vec<_> = steps
+            .iter()
+            .filter(|s| s.kind != StepKind::Unchanged)
+            .map(|s| (s.id.as_str(), s.kind.clone()))
+            .collect();
+        assert_eq!(orphans, [(login.id.as_str(), StepKind::Orphan)]);
+        assert_eq!(
+            item_ident(Some("add users table"), "  Index   Email "),
+            "add users table / index email"
+        );
+    }
+
+    #[test]
+    fn repeated_items_stay_with_their_phase() {
+        let (_dir, store, plan) = setup();
+        let two = "### Phase 1: Schema\n1. Write tests\n\n### Phase 2: API\n1. Write tests\n";
+        sync(&store, "proj", &plan, two, false).unwrap();
+        let mut api_tests = by_key(&store).remove("item:2.1").unwrap();
+        api_tests.status = Status::Completed;
+        store.save_task("proj", &api_tests).unwrap();
+
+        // A phase inserted in front and the other two swapped
+        let edited = "### Phase 1: Setup\n1. Write tests\n\n\
+### Phase 2: API\n1. Write tests\n\n### Phase 3: Schema\n1. Write tests\n";
+        sync(&store, "proj", &plan, edited, false).unwrap();
+        let t = by_key(&store);
+        assert_eq!(t["item:2.1"].id, api_tests.id);
+        assert_eq!(t["item:2.1"].status, Status::Completed);
+        assert_eq!(t["item:2.1"].parent_id, t["phase:2"].id);
+        assert_eq!(t["phase:2"].subject, "Phase 2: API");
+        assert_eq!(t["item:1.1"].status, Status::Pending);
+        assert_eq!(t["item:3.1"].status, Status::Pending);
+        assert_eq!(
+            meta_str_raw(&t["item:3.1"].raw, PLAN_ITEM),
+            "schema / write tests"
+        );
+    }
+}
@@ -0,0 +1,376 @@
+use std::collections::{HashMap, HashSet};
+
+use serde_json::Value;
+
+use crate::phases;
+use crate::plantasks::{self, Level, PLAN_ITEM, PLAN_KEY};
+use crate::store::{Status, Task, meta_str_raw};
+
+/// Minimum word overlap (Jaccard) for a task subject to count as a plan item.
+const MATCH_THRESHOLD: f64 = 0.5;
+
+#[derive(Debug, Clone)]
+pub struct ItemProgress {
+    /// `item:N.i` or `item:N.i.j`, as used by `ct plan tasks`.
+    pub key: String,
+    pub text: String,
+    pub sub_item: bool,
+    /// Ticked in the plan itself.
+    pub checked: bool,
+    pub task: Option<Task>,
+}
+
+impl ItemProgress {
+    pub fn done(&self) -> bool {
+        self.checked
+            || self
+                .task
+                .as_ref()
+                .is_some_and(|t| t.status == Status::Completed)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PhaseProgress {
+    pub phase: u32,
+    pub title: String,
+    pub task: Option<Task>,
+    pub items: Vec<ItemProgress>,
+}
+
+impl PhaseProgress {
+    /// Completed vs total numbered items. Sub-items are tracked for drift but
+    /// don't count separately; a phase without items counts as one unit.
+    pub fn counts(&self) -> (usize, usize) {
+        let items: Vec<&ItemProgress> = self.items.iter().filter(|i| !i.sub_item).collect();
+        if items.is_empty() {
+            let done = self
+                .task
+                .as_ref()
+                .is_some_and(|t| t.status == Status::Completed);
+            return (usize::from(done), 1);
+        }
+        (items.iter().filter(|i| i.done()).count(), items.len())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Progress {
+    pub phases: Vec<PhaseProgress>,
+    /// Tasks linked to the plan that match no phase or item.
+    pub unplanned: Vec<Task>,
+}
+
+impl Progress {
+    pub fn counts(&self) -> (usize, usize) {
+        self.phases
+            .iter()
+            .map(PhaseProgress::counts)
+            .fold((0, 0), |acc, c| (acc.0 + c.0, acc.1 + c.1))
+    }
+
+    /// Open plan items that no task covers.
+    pub fn missing(&self) -> impl Iterator<Item = (&PhaseProgress, &ItemProgress)> {
+        self.phases
+            .iter()
+            .flat_map(|p| p.items.iter().map(move |i| (p, i)))
+            .filter(|(_, i)| i.task.is_none() && !i.checked)
+    }
+
+    pub fn to_json(&self) -> Value {
+        let task_ref = |t: &Option<Task>| {
+            t.as_ref()
+                .map(|t| serde_json::json!({"id": t.id, "status": t.status.as_str()}))
+                .unwrap_or(Value::Null)
+        };
+        let phases: Vec<Value> = self
+            .phases
+            .iter()
+            .map(|p| {
+                let (done, total) = p.counts();
+                serde_json::json!({
+                    "phase": p.phase,
+                    "title": p.title,
+                    "task": task_ref(&p.task),
+                    "done": done,
+                    "total": total,
+                    "items": p.items.iter().map(|i| serde_json::json!({
+                        "key": i.key,
+                        "text": i.text,
+                        "checked": i.checked,
+                        "task": task_ref(&i.task),
+                    })).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        let (done, total) = self.counts();
+        serde_json::json!({
+            "done": done,
+            "total": total,
+            "phases": phases,
+            "missing": self.missing().map(|(_, i)| i.key.clone()).collect::<Vec<_>>(),
+            "unplanned": self.unplanned.iter().map(|t| serde_json::json!({
+                "id": t.id,
+                "subject": t.subject,
+                "status": t.status.as_str(),
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+fn words(s: &str) -> HashSet<String> {
+    s.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (words(a), words(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+/// Whether a task subject is close enough to a plan item's text to be it.
+pub fn similar(a: &str, b: &str) -> bool {
+    similarity(a, b) >= MATCH_THRESHOLD
+}
+
+/// Map a plan's phases and items onto the tasks linked to `plan_file`.
+/// Tasks carrying a `plan_key` (written by `ct plan tasks`) match exactly;
+/// the rest are paired with the most similar unmatched item or phase title.
+pub fn compute(plan_file: &str, content: &str, tasks: &[Task]) -> Progress {
+    let linked: Vec<&Task> = tasks.iter().filter(|t| t.plan_file == plan_file).collect();
+
+    let parsed = phases::parse_phases(content);
+    let entries = plantasks::entries(&parsed);
+
+    // Every slot a task can fill: (phase index, item index or None for the
+    // phase), with the plan entry behind it
+    let mut phases: Vec<PhaseProgress> = Vec::new();
+    let mut slots: Vec<((usize, Option<usize>), &plantasks::Entry)> = Vec::new();
+    for e in &entries {
+        let slot = match e.level {
+            Level::Phase => {
+                phases.push(PhaseProgress {
+                    phase: e.phase.phase,
+                    title: e.phase.title.clone(),
+                    task: None,
+                    items: Vec::new(),
+                });
+                (phases.len() - 1, None)
+            }
+            Level::Item | Level::SubItem => {
+                let pi = phases.len() - 1;
+                phases[pi].items.push(ItemProgress {
+                    key: e.key.clone(),
+                    text: e.subject.clone(),
+                    sub_item: e.level == Level::SubItem,
+                    checked: e.checked,
+                    task: None,
+                });
+                (pi, Some(phases[pi].items.len() - 1))
+            }
+        };
+        slots.push((slot, e));
+    }
+    let slot_text: HashMap<(usize, Option<usize>), &str> = slots
+        .iter()
+        .map(|(slot, e)| (*slot, e.subject.as_str()))
+        .collect();
+
+    let mut filled: HashSet<(usize, Option<usize>)> = HashSet::new();
+    let mut matched: HashSet<&str> = HashSet::new();
+
+    // Explicit markers first: a phase or item identity, else its key, the
+    // same way `ct plan tasks` pairs them
+    let by_key: HashMap<&str, (usize, Option<usize>)> =
+        slots.iter().map(|(s, e)| (e.key.as_str(), *s)).collect();
+    let by_ident: HashMap<&str, (usize, Option<usize>)> = slots
+        .iter()
+        .filter(|(_, e)| !e.ident.is_empty())
+        .map(|(s, e)| (e.ident.as_str(), *s))
+        .collect();
+    let mut marked: Vec<(&Task, (usize, Option<usize>))> = Vec::new();
+    for task in &linked {
+        let ident = meta_str_raw(&task.raw, PLAN_ITEM);
+        let slot = by_ident.get(ident.as_str()).or_else(|| {
+            by_key
+                .get(meta_str_raw(&task.raw, PLAN_KEY).as_str())
+                .filter(|&s| ident.is_empty() || similar(&task.subject, slot_text[s]))
+        });
+        if let Some(&slot) = slot {
+            marked.push((task, slot));
+        }
+    }
+    for (task, slot) in marked {
+        if filled.insert(slot) {
+            assign(&mut phases, slot, task);
+            matched.insert(&task.id);
+        }
+    }
+
+    // Then the best remaining subject matches, greedily by score
+    let mut candidates: Vec<(f64, usize, (usize, Option<usize>))> = Vec::new();
+    for (ti, task) in linked.iter().enumerate() {
+        if matched.contains(task.id.as_str()) || !meta_str_raw(&task.raw, PLAN_KEY).is_empty() {
+            continue;
+        }
+        for &(slot, e) in slots.iter().filter(|(s, _)| !filled.contains(s)) {
+            let score = similarity(&task.subject, &e.subject);
+            if score >= MATCH_THRESHOLD {
+                candidates.push((score, ti, slot));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+    for (_, ti, slot) in candidates {
+        let task = linked[ti];
+        if matched.contains(task.id.as_str()) || !filled.insert(slot) {
+            continue;
+        }
+        assign(&mut phases, slot, task);
+        matched.insert(&task.id);
+    }
+
+    // The epic is the plan itself, not drift
+    let unplanned = linked
+        .into_iter()
+        .filter(|t| !matched.contains(t.id.as_str()))
+        .filter(|t| meta_str_raw(&t.raw, PLAN_KEY) != "epic" && t.task_type != "epic")
+        .cloned()
+        .collect();
+
+    Progress { phases, unplanned }
+}
+
+/// Text progress bar, e.g. `███░░░` for 1/2 at width 6.
+pub fn bar(done: usize, total: usize, width: usize) -> String {
+    let filled = (done * width).checked_div(total).unwrap_or(0).min(width);
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+fn assign(phases: &mut [PhaseProgress], (pi, ii): (usize, Option<usize>), task: &Task) {
+    match ii {
+        Some(ii) => phases[pi].items[ii].task = Some(task.clone()),
+        None => phases[pi].task = Some(task.clone()),
+    }
+}
+
+/// Completed/total units per plan path, for plans with linked tasks.
+pub fn plan_counts<'a>(
+    plans: impl IntoIterator<Item = (&'a str, String)>,
+    tasks: &[Task],
+) -> HashMap<String, (usize, usize)> {
+    plans
+        .into_iter()
+        .map(|(path, content)| (path.to_string(), compute(path, &content, tasks).counts()))
+        .filter(|(_, (_, total))| *total > 0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN: &str = "### Phase 1: Schema\n1. Add users table\n2. Add sessions table\n\n\
+### Phase 2: API\n1. Implement login endpoint\n";
+
+    fn task(id: &str, subject: &str, status: &str, meta: Value) -> Task {
+        let mut meta = meta;
+        meta["plan_file"] = Value::String("/p.md".into());
+        Task::from_raw(serde_json::json!({
+            "id": id, "subject": subject, "status": status, "metadata": meta
+        }))
+    }
+
+    #[test]
+    fn matches_by_marker_then_similarity() {
+        let tasks = vec![
+            task(
+                "1",
+                "Plan",
+                "pending",
+                serde_json::json!({"plan_key": "epic"}),
+            ),
+            task(
+                "2",
+                "whatever",
+                "completed",
+                serde_json::json!({"plan_key": "item:1.1"}),
+            ),
+            task(
+                "3",
+                "Implement the login endpoint",
+                "in_progress",
+                serde_json::json!({}),
+            ),
+            task("4", "Refactor logging", "pending", serde_json::json!({})),
+            Task::from_raw(serde_json::json!({"id": "5", "subject": "Add sessions table"})),
+        ];
+        let p = compute("/p.md", PLAN, &tasks);
+
+        assert_eq!(p.phases[0].items[0].task.as_ref().unwrap().id, "2");
+        assert_eq!(p.phases[1].items[0].task.as_ref().unwrap().id, "3");
+        assert_eq!(p.phases[0].counts(), (1, 2));
+        assert_eq!(p.counts(), (1, 3));
+
+        let missing: Vec<&str> = p.missing().map(|(_, i)| i.key.as_str()).collect();
+        assert_eq!(missing, vec!["item:1.2"]);
+        let unplanned: Vec<&str> = p.unplanned.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(unplanned, vec!["4"]);
+    }
+
+    #[test]
+    fn identity_beats_position() {
+        // Items swapped since the tasks were made
+        let plan = "### Phase 1: Schema\n1. Add sessions table\n2. Add users table\n";
+        let tasks = vec![
+            task(
+                "1",
+                "Add users table",
+                "completed",
+                serde_json::json!({"plan_key": "item:1.1", "plan_item": "schema / add users table"}),
+            ),
+            task(
+                "2",
+                "Drop legacy table",
+                "pending",
+                serde_json::json!({"plan_key": "item:1.2", "plan_item": "schema / drop legacy table"}),
+            ),
+        ];
+        let p = compute("/p.md", plan, &tasks);
+        assert!(p.phases[0].items[0].task.is_none());
+        assert_eq!(p.phases[0].items[1].task.as_ref().unwrap().id, "1");
+        let unplanned: Vec<&str> = p.unplanned.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(unplanned, vec!["2"]);
+    }
+
+    #[test]
+    fn phase_without_items_uses_phase_task() {
+        let plan = "### Phase 1: Spike\n\n### Phase 2: Build\n1. Do it\n";
+        let tasks = vec![task(
+            "1",
+            "Phase 1: Spike",
+            "completed",
+            serde_json::json!({}),
+        )];
+        let p = compute("/p.md", plan, &tasks);
+        assert_eq!(p.phases[0].counts(), (1, 1));
+        assert_eq!(p.counts(), (1, 2));
+        assert!(p.unplanned.is_empty());
+    }
+
+    #[test]
+    fn ticked_items_count_as_done_without_tasks() {
+        let plan = "### Phase 1: Setup\n- [x] Install deps\n- [ ] Configure\n";
+        let p = compute("/p.md", plan, &[]);
+        assert_eq!(p.counts(), (1, 2));
+        let missing: Vec<&str> = p.missing().map(|(_, i)| i.key.as_str()).collect();
+        assert_eq!(missing, vec!["item:1.2"]);
+    }
+}
@@ -0,0 +1,309 @@
+use std::collections::HashSet;
+
+use crate::store::{Task, is_ready, meta_str_raw};
+
+/// A parsed task query such as
+/// `status:pending priority:P1 branch:feat/* has:plan_file -owner:bot "free text"`.
+///
+/// Terms are ANDed. `key:value` matches a task field or any metadata key
+/// (case-insensitive, `*`/`?` globs, `a,b` alternatives); `has:key` requires
+/// a non-empty value; a leading `-` negates a term; anything else is free
+/// text matched against subject and description.
+///
+/// Parsing never fails, so a half-typed query in the TUI still filters.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    negated: bool,
+    kind: TermKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TermKind {
+    Field { key: String, patterns: Vec<String> },
+    Has(String),
+    Text(String),
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Self {
+        let terms = tokenize(input)
+            .into_iter()
+            .filter_map(|(token, quoted)| parse_term(&token, quoted))
+            .collect();
+        Self { terms }
+    }
+
+    /// True when the query filters on status itself, in which case callers
+    /// should not additionally hide completed tasks.
+    pub fn mentions_status(&self) -> bool {
+        self.terms
+            .iter()
+            .any(|t| matches!(&t.kind, TermKind::Field { key, .. } if key.eq_ignore_ascii_case("status")))
+    }
+
+    /// `completed` is the set of completed task IDs, needed for `status:ready`.
+    pub fn matches(&self, task: &Task, completed: &HashSet<&str>) -> bool {
+        self.terms
+            .iter()
+            .all(|term| term.kind.matches(task, completed) != term.negated)
+    }
+}
+
+impl TermKind {
+    fn matches(&self, task: &Task, completed: &HashSet<&str>) -> bool {
+        match self {
+            Self::Text(text) => {
+                let text = text.to_lowercase();
+                task.subject.to_lowercase().contains(&text)
+                    || task.description.to_lowercase().contains(&text)
+            }
+            Self::Has(key) => field_values(task, key).iter().any(|v| !v.is_empty()),
+            Self::Field { key, patterns } => {
+                let lower = key.to_lowercase();
+                if lower == "status" && patterns.iter().any(|p| p == "ready") {
+                    return is_ready(task, completed)
+                        || patterns
+                            .iter()
+                            .any(|p| p != "ready" && glob_match(p, task.status.as_str()));
+                }
+                let values = field_values(task, key);
+                let substring = matches!(lower.as_str(), "subject" | "description");
+                patterns.iter().any(|p| {
+                    values.iter().any(|v| {
+                        let v = v.to_lowercase();
+                        if substring && !has_glob(p) {
+                            v.contains(p.as_str())
+                        } else {
+                            glob_match(p, &v)
+                        }
+                    })
+                })
+            }
+        }
+    }
+}
+
+/// Values a key resolves to. Typed fields come first, matched
+/// case-insensitively; anything else falls back to `metadata.<key>` and then
+/// a top-level string, with the key as written.
+fn field_values(task: &Task, key: &str) -> Vec<String> {
+    let one = |s: &str| vec![s.to_string()];
+    match key.to_lowercase().as_str() {
+        "id" => one(&task.id),
+        "subject" => one(&task.subject),
+        "description" | "desc" => one(&task.description),
+        "status" => one(task.status.as_str()),
+        "priority" | "pri" => match task.priority.sort_key() {
+            n @ 1..=3 => vec![task.priority.as_str().to_string(), n.to_string()],
+            _ => Vec::new(),
+        },
+        "owner" => one(&task.owner),
+        "type" => one(&task.task_type),
+        "branch" => one(&task.branch),
+        "parent" | "parent_id" => one(&task.parent_id),
+        "project" => one(&task.project),
+        "plan" | "plan_file" => one(&task.plan_file),
+        "spec" | "spec_file" => one(&task.spec_file),
+        "slug" => one(&task.slug),
+        "session" | "session_id" => one(&task.session_id),
+        "blocks" => task.blocks.clone(),
+        "blocked_by" | "blockedby" => task.blocked_by.clone(),
+        _ => {
+            let meta = meta_str_raw(&task.raw, key);
+            if !meta.is_empty() {
+                return vec![meta];
+            }
+            task.raw
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(|s| vec![s.to_string()])
+                .unwrap_or_default()
+        }
+    }
+}
+
+fn parse_term(token: &str, quoted: bool) -> Option<Term> {
+    if token.is_empty() {
+        return None;
+    }
+    if quoted {
+        return Some(Term {
+            negated: false,
+            kind: TermKind::Text(token.to_string()),
+        });
+    }
+    let (negated, body) = match token.strip_prefix('-') {
+        Some(rest) if !rest.is_empty() => (true, rest),
+        _ => (false, token),
+    };
+    let kind = match body.split_once(':') {
+        Some((key, value)) if !key.is_empty() && !value.is_empty() => {
+            if key.eq_ignore_ascii_case("has") {
+                TermKind::Has(value.to_string())
+            } else {
+                TermKind::Field {
+                    key: key.to_string(),
+                    patterns: value
+                        .split(',')
+                        .filter(|p| !p.is_empty())
+                        .map(str::to_lowercase)
+                        .collect(),
+                }
+            }
+        }
+        _ => TermKind::Text(body.to_string()),
+    };
+    Some(Term { negated, kind })
+}
+
+/// Split on whitespace, keeping `"double quoted"` runs together. A quote
+/// inside a token (`owner:"a b"`) continues that token. Returns each token
+/// with whether it was entirely quoted.
+fn tokenize(input: &str) -> Vec<(String, bool)> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut whole_quoted = false;
+
+    for c in input.chars() {
+        match c {
+            '"' => {
+                if !in_quotes && current.is_empty() {
+                    whole_quoted = true;
+                }
+                in_quotes = !in_quotes;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push((std::mem::take(&mut current), whole_quoted));
+                }
+                whole_quoted = false;
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push((current, whole_quoted));
+    }
+    tokens
+}
+
+fn has_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+/// Case-sensitive glob over chars; callers lowercase both sides.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if let Some((sp, st)) = star {
+            pi = sp + 1;
+            ti = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(val: serde_json::Value) -> Task {
+        Task::from_raw(val)
+    }
+
+    fn matches(q: &str, t: &Task) -> bool {
+        Query::parse(q).matches(t, &HashSet::new())
+    }
+
+    #[test]
+    fn tokenize_keeps_quoted_runs() {
+        assert_eq!(
+            tokenize(r#"status:pending "free text" owner:"a b""#),
+            vec![
+                ("status:pending".to_string(), false),
+                ("free text".to_string(), true),
+                ("owner:a b".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn field_terms_and_negation() {
+        let t = task(serde_json::json!({
+            "id": "12",
+            "subject": "Fix login",
+            "status": "pending",
+            "owner": "bot",
+            "metadata": {"priority": "P1", "type": "bug", "branch": "feat/login"}
+        }));
+        assert!(matches("status:pending priority:P1 type:bug", &t));
+        assert!(matches("priority:1", &t));
+        assert!(matches("branch:feat/*", &t));
+        assert!(!matches("branch:fix/*", &t));
+        assert!(!matches("-owner:bot", &t));
+        assert!(matches("-owner:alice", &t));
+        assert!(matches("status:in_progress,pending", &t));
+        assert!(matches("LOGIN", &t));
+        assert!(matches("\"fix login\"", &t));
+        assert!(!matches("\"login fix\"", &t));
+    }
+
+    #[test]
+    fn has_and_arbitrary_metadata() {
+        let t = task(serde_json::json!({
+            "id": "3",
+            "subject": "x",
+            "metadata": {"plan_file": "/p.md", "epic_design": "yes", "points": 5}
+        }));
+        assert!(matches("has:plan_file", &t));
+        assert!(!matches("has:spec_file", &t));
+        assert!(matches("-has:spec_file", &t));
+        assert!(matches("epic_design:yes", &t));
+        assert!(matches("points:5", &t));
+        let camel = task(serde_json::json!({"id": "5", "metadata": {"myKey": "Foo"}}));
+        assert!(matches("myKey:foo", &camel));
+        assert!(matches("has:myKey", &camel));
+        assert!(!matches("mykey:foo", &camel));
+        assert!(matches("STATUS:pending", &camel));
+        assert!(matches(
+            "parent:12",
+            &task(serde_json::json!({"id": "4", "metadata": {"parent_id": "12"}}))
+        ));
+    }
+
+    #[test]
+    fn status_ready_uses_completed_set() {
+        let t = task(serde_json::json!({"id": "2", "status": "pending", "blockedBy": ["1"]}));
+        let q = Query::parse("status:ready");
+        assert!(!q.matches(&t, &HashSet::new()));
+        assert!(q.matches(&t, &HashSet::from(["1"])));
+        assert!(q.mentions_status());
+    }
+
+    #[test]
+    fn glob_matching() {
+        assert!(glob_match("feat/*", "feat/a/b"));
+        assert!(glob_match("*login*", "fix-login-bug"));
+        assert!(glob_match("p?", "p1"));
+        assert!(!glob_match("p?", "p12"));
+        assert!(glob_match("*", ""));
+    }
+}
@@ -0,0 +1,400 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::artifact::chrono_rfc3339;
+
+/// Snapshots live next to the artifact in `.history/<file name>/`, one file
+/// per superseded version, named by the time it was replaced.
+pub const HISTORY_DIR: &str = ".history";
+
+/// Context lines around each hunk in unified diffs.
+pub const CONTEXT: usize = 3;
+
+#[derive(Debug, Clone)]
+pub struct Revision {
+    /// 1-based, oldest first.
+    pub number: usize,
+    pub path: PathBuf,
+    /// RFC 3339 time the version was replaced.
+    pub ts: String,
+}
+
+impl Revision {
+    pub fn load(&self) -> Result<String, String> {
+        fs::read_to_string(&self.path).map_err(|e| format!("{}: {e}", self.path.display()))
+    }
+}
+
+pub fn history_dir(path: &Path) -> PathBuf {
+    let parent = path.parent().unwrap_or(Path::new("."));
+    let name = path.file_name().unwrap_or_default();
+    parent.join(HISTORY_DIR).join(name)
+}
+
+/// `20261018T120501Z` or `20261018T120501Z-2` → `2026-10-18T12:05:01Z`.
+fn stem_to_ts(stem: &str) -> Option<String> {
+    let base = stem.split('-').next()?;
+    let b = base.as_bytes();
+    if b.len() != 16 || b[8] != b'T' || b[15] != b'Z' {
+        return None;
+    }
+    Some(format!(
+        "{}-{}-{}T{}:{}:{}Z",
+        &base[0..4],
+        &base[4..6],
+        &base[6..8],
+        &base[9..11],
+        &base[11..13],
+        &base[13..15]
+    ))
+}
+
+/// Sort key: timestamp, then same-second sequence number.
+fn stem_key(stem: &str) -> (String, u32) {
+    match stem.split_once('-') {
+        Some((base, seq)) => (base.to_string(), seq.parse().unwrap_or(0)),
+        None => (stem.to_string(), 0),
+    }
+}
+
+pub fn list(path: &Path) -> Vec<Revision> {
+    let Ok(entries) = fs::read_dir(history_dir(path)) else {
+        return Vec::new();
+    };
+    let mut found: Vec<(String, PathBuf, String)> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|p| {
+            let stem = p.file_stem()?.to_string_lossy().to_string();
+            let ts = stem_to_ts(&stem)?;
+            Some((stem, p, ts))
+        })
+        .collect();
+    found.sort_by_key(|(stem, _, _)| stem_key(stem));
+    found
+        .into_iter()
+        .enumerate()
+        .map(|(i, (_, path, ts))| Revision {
+            number: i + 1,
+            path,
+            ts,
+        })
+        .collect()
+}
+
+/// Store `previous` as the newest revision of `path`, unless it matches the
+/// newest revision already.
+pub fn record(path: &Path, previous: &str) -> Result<Option<PathBuf>, String> {
+    if let Some(last) = list(path).last()
+        && last.load().is_ok_and(|c| c == previous)
+    {
+        return Ok(None);
+    }
+    let dir = history_dir(path);
+    fs::create_dir_all(&dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+
+    let stem: String = chrono_rfc3339()
+        .chars()
+        .filter(|c| *c != '-' && *c != ':')
+        .collect();
+    let mut dest = dir.join(format!("{stem}.md"));
+    let mut seq = 2;
+    while dest.exists() {
+        dest = dir.join(format!("{stem}-{seq}.md"));
+        seq += 1;
+    }
+    fs::write(&dest, previous).map_err(|e| format!("{}: {e}", dest.display()))?;
+    Ok(Some(dest))
+}
+
+/// Carry the revisions of `from` over to `to` after the file was moved
+/// there, merging into any history `to` already has.
+pub fn move_history(from: &Path, to: &Path) -> Result<(), String> {
+    let src = history_dir(from);
+    if !src.is_dir() {
+        return Ok(());
+    }
+    let dest = history_dir(to);
+    if !dest.exists() {
+        let parent = dest.parent().unwrap_or(Path::new("."));
+        fs::create_dir_all(parent).map_err(|e| format!("{}: {e}", parent.display()))?;
+        fs::rename(&src, &dest).map_err(|e| format!("{}: {e}", src.display()))?;
+    } else {
+        let entries = fs::read_dir(&src).map_err(|e| format!("{}: {e}", src.display()))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            let base = stem.split('-').next().unwrap_or_default();
+            let mut target = dest.join(entry.file_name());
+            let mut seq = 2;
+            while target.exists() {
+                target = dest.join(format!("{base}-{seq}.md"));
+                seq += 1;
+            }
+            fs::rename(&path, &target).map_err(|e| format!("{}: {e}", path.display()))?;
+        }
+        fs::remove_dir(&src).map_err(|e| format!("{}: {e}", src.display()))?;
+    }
+    // Drop the old .history/ once no other artifact uses it
+    if let Some(parent) = src.parent() {
+        let _ = fs::remove_dir(parent);
+    }
+    Ok(())
+}
+
+/// Snapshot the current content of `path` before it is overwritten. A
+/// missing file has nothing to preserve.
+pub fn snapshot(path: &Path) -> Result<Option<PathBuf>, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => record(path, &content),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Find a revision by number (`3`), or count back from the newest (`0` is
+/// the newest, `-1` the one before it).
+pub fn resolve(path: &Path, rev: &str) -> Result<Revision, String> {
+    let revs = list(path);
+    if revs.is_empty() {
+        return Err(format!("no revisions recorded for {}", path.display()));
+    }
+    let n: i64 = rev
+        .parse()
+        .map_err(|_| format!("invalid revision '{rev}' (expected a number)"))?;
+    let idx = if n > 0 {
+        n - 1
+    } else {
+        revs.len() as i64 - 1 + n
+    };
+    usize::try_from(idx)
+        .ok()
+        .and_then(|i| revs.get(i))
+        .cloned()
+        .ok_or_else(|| format!("no revision {rev} (have 1-{})", revs.len()))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    Context(String),
+    Added(String),
+    Removed(String),
+}
+
+/// Line diff via longest common subsequence. Plans are small enough that
+/// the quadratic table is not a concern.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let (n, m) = (a.len(), b.len());
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            out.push(DiffLine::Context(a[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            out.push(DiffLine::Removed(a[i].to_string()));
+            i += 1;
+        } else {
+            out.push(DiffLine::Added(b[j].to_string()));
+            j += 1;
+        }
+    }
+    out.extend(a[i..].iter().map(|l| DiffLine::Removed(l.to_string())));
+    out.extend(b[j..].iter().map(|l| DiffLine::Added(l.to_string())));
+    out
+}
+
+/// `(added, removed)` line counts.
+pub fn stats(lines: &[DiffLine]) -> (usize, usize) {
+    lines.iter().fold((0, 0), |(a, r), l| match l {
+        DiffLine::Added(_) => (a + 1, r),
+        DiffLine::Removed(_) => (a, r + 1),
+        DiffLine::Context(_) => (a, r),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hunk {
+    pub header: String,
+    pub lines: Vec<DiffLine>,
+}
+
+/// Group a diff into unified-diff hunks with `context` lines around changes.
+pub fn hunks(lines: &[DiffLine], context: usize) -> Vec<Hunk> {
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| !matches!(l, DiffLine::Context(_)))
+        .map(|(i, _)| i)
+        .collect();
+    let Some(&first) = changed.first() else {
+        return Vec::new();
+    };
+
+    // Merge change runs whose context windows touch
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    let mut start = first.saturating_sub(context);
+    let mut end = (first + context + 1).min(lines.len());
+    for &c in &changed[1..] {
+        let lo = c.saturating_sub(context);
+        if lo <= end {
+            end = (c + context + 1).min(lines.len());
+        } else {
+            ranges.push((start, end));
+            start = lo;
+            end = (c + context + 1).min(lines.len());
+        }
+    }
+    ranges.push((start, end));
+
+    // 1-based line numbers in old and new files at each diff position
+    let mut old_no = Vec::with_capacity(lines.len());
+    let mut new_no = Vec::with_capacity(lines.len());
+    let (mut o, mut n) = (1, 1);
+    for l in lines {
+        old_no.push(o);
+        new_no.push(n);
+        match l {
+            DiffLine::Context(_) => {
+                o += 1;
+                n += 1;
+            }
+            DiffLine::Removed(_) => o += 1,
+            DiffLine::Added(_) => n += 1,
+        }
+    }
+
+    ranges
+        .into_iter()
+        .map(|(s, e)| {
+            let slice = &lines[s..e];
+            let old_len = slice
+                .iter()
+                .filter(|l| !matches!(l, DiffLine::Added(_)))
+                .count();
+            let new_len = slice
+                .iter()
+                .filter(|l| !matches!(l, DiffLine::Removed(_)))
+                .count();
+            // An empty side is reported at the line before, per diff(1)
+            let old_start = if old_len == 0 {
+                old_no[s] - 1
+            } else {
+                old_no[s]
+            };
+            let new_start = if new_len == 0 {
+                new_no[s] - 1
+            } else {
+                new_no[s]
+            };
+            Hunk {
+                header: format!("@@ -{old_start},{old_len} +{new_start},{new_len} @@"),
+                lines: slice.to_vec(),
+            }
+        })
+        .collect()
+}
+
+/// Plain unified diff text, empty when the inputs are identical.
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    let hunks = hunks(&diff_lines(old, new), CONTEXT);
+    if hunks.is_empty() {
+        return String::new();
+    }
+    let mut out = format!("--- {old_label}\n+++ {new_label}\n");
+    for h in hunks {
+        out.push_str(&h.header);
+        out.push('\n');
+        for l in h.lines {
+            let (prefix, text) = match l {
+                DiffLine::Context(t) => (' ', t),
+                DiffLine::Added(t) => ('+', t),
+                DiffLine::Removed(t) => ('-', t),
+            };
+            out.push(prefix);
+            out.push_str(&text);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_dedupes_and_resolves_revisions() {
+        let dir = tempfile::tempdir().unwrap();
+        let plan = dir.path().join("plan.md");
+        assert!(snapshot(&plan).unwrap().is_none());
+
+        fs::write(&plan, "v1\n").unwrap();
+        assert!(snapshot(&plan).unwrap().is_some());
+        assert!(snapshot(&plan).unwrap().is_none());
+        fs::write(&plan, "v2\n").unwrap();
+        snapshot(&plan).unwrap();
+
+        let revs = list(&plan);
+        assert_eq!(revs.len(), 2);
+        assert_eq!(revs[1].number, 2);
+        assert!(revs[0].ts.ends_with('Z'));
+        assert_eq!(resolve(&plan, "1").unwrap().load().unwrap(), "v1\n");
+        assert_eq!(resolve(&plan, "0").unwrap().load().unwrap(), "v2\n");
+        assert_eq!(resolve(&plan, "-1").unwrap().number, 1);
+        assert!(resolve(&plan, "3").is_err());
+        assert!(
+            history_dir(&plan).starts_with(dir.path().join(HISTORY_DIR)),
+            "history stays next to the artifact"
+        );
+    }
+
+    #[test]
+    fn archived_plan_keeps_its_revisions() {
+        let dir = tempfile::tempdir().unwrap();
+        let plan = dir.path().join("plan.md");
+        fs::write(&plan, "v1\n").unwrap();
+        snapshot(&plan).unwrap();
+        fs::write(&plan, "v2\n").unwrap();
+        snapshot(&plan).unwrap();
+
+        let archived = dir.path().join("archive").join("plan.md");
+        fs::create_dir_all(archived.parent().unwrap()).unwrap();
+        fs::rename(&plan, &archived).unwrap();
+        move_history(&plan, &archived).unwrap();
+
+        assert_eq!(list(&archived).len(), 2);
+        assert_eq!(resolve(&archived, "1").unwrap().load().unwrap(), "v1\n");
+        assert!(list(&plan).is_empty());
+        assert!(!dir.path().join(HISTORY_DIR).exists());
+    }
+
+    #[test]
+    fn unified_diff_hunks() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let new = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+        let diff = unified_diff(old, new, "old", "new");
+        assert_eq!(
+            diff,
+            "--- old\n+++ new\n\
+@@ -1,5 +1,5 @@\n a\n-b\n+B\n c\n d\n e\n\
+@@ -8,3 +8,4 @@\n h\n i\n j\n+k\n"
+        );
+        assert_eq!(stats(&diff_lines(old, new)), (2, 1));
+        assert!(unified_diff(old, old, "a", "b").is_empty());
+    }
+}
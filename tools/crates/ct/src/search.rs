@@ -0,0 +1,440 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use crate::artifact;
+use crate::frontmatter::Document;
+use crate::plan::{self, Plan};
+use crate::spec::{self, Spec};
+use crate::store::{Store, Task};
+
+/// Characters of context kept around the first match in a snippet.
+const SNIPPET_WIDTH: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Kind {
+    Task,
+    Plan,
+    Spec,
+}
+
+impl Kind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Task => "task",
+            Self::Plan => "plan",
+            Self::Spec => "spec",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "task" => Some(Self::Task),
+            "plan" => Some(Self::Plan),
+            "spec" => Some(Self::Spec),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Source {
+    Active,
+    Archived,
+    GitNotes,
+}
+
+impl Source {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Archived => "archived",
+            Self::GitNotes => "git notes",
+        }
+    }
+}
+
+/// Something searchable: a task (subject + description) or a plan/spec
+/// (title + body).
+#[derive(Debug, Clone)]
+pub struct Doc {
+    pub kind: Kind,
+    pub source: Source,
+    /// Task ID, or the plan/spec name as listed in its tab.
+    pub key: String,
+    /// Task list for tasks, project path for plans and specs.
+    pub scope: String,
+    /// File or `git-notes://` path; empty for tasks.
+    pub path: PathBuf,
+    pub title: String,
+    pub body: String,
+    /// Lines before `body` in the file (the frontmatter), so hits report
+    /// file line numbers.
+    pub line_offset: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Hit {
+    pub doc: Doc,
+    pub score: usize,
+    /// 1-based line of the snippet in the file or description; 0 when only
+    /// the title matched.
+    pub line: usize,
+    pub snippet: String,
+}
+
+pub fn task_docs(tasks: &[Task], list_id: &str, source: Source) -> Vec<Doc> {
+    tasks
+        .iter()
+        .map(|t| Doc {
+            kind: Kind::Task,
+            source,
+            key: t.id.clone(),
+            scope: list_id.to_string(),
+            path: PathBuf::new(),
+            title: t.subject.clone(),
+            body: t.description.clone(),
+            line_offset: 0,
+        })
+        .collect()
+}
+
+/// Body and frontmatter line count of a plan or spec; the caller fills in
+/// what the listing knows.
+fn artifact_doc(kind: Kind, source: Source, content: &str) -> Doc {
+    let body = Document::parse(content).body;
+    let line_offset = content[..content.len() - body.len()].lines().count();
+    Doc {
+        kind,
+        source,
+        key: String::new(),
+        scope: String::new(),
+        path: PathBuf::new(),
+        title: String::new(),
+        body,
+        line_offset,
+    }
+}
+
+/// Content of each listed plan or spec. Git-notes entries share one note
+/// per commit, so each gets the note chunk at its position.
+fn contents<'a>(
+    paths: impl Iterator<Item = &'a PathBuf>,
+    source: Source,
+    load: fn(&PathBuf) -> String,
+) -> Vec<String> {
+    let mut chunks: HashMap<&PathBuf, (Vec<String>, usize)> = HashMap::new();
+    paths
+        .map(|path| {
+            if source != Source::GitNotes {
+                return load(path);
+            }
+            let (docs, next) = chunks
+                .entry(path)
+                .or_insert_with(|| (artifact::split_notes(&load(path)), 0));
+            *next += 1;
+            docs.get(*next - 1).cloned().unwrap_or_default()
+        })
+        .collect()
+}
+
+pub fn plan_docs(plans: &[Plan], source: Source) -> Vec<Doc> {
+    let contents = contents(plans.iter().map(|p| &p.path), source, plan::load_content);
+    plans
+        .iter()
+        .zip(contents)
+        .map(|(p, content)| Doc {
+            key: p.name.clone(),
+            scope: p.project.clone(),
+            path: p.path.clone(),
+            title: p.title.clone(),
+            ..artifact_doc(Kind::Plan, source, &content)
+        })
+        .collect()
+}
+
+pub fn spec_docs(specs: &[Spec], source: Source) -> Vec<Doc> {
+    let contents = contents(specs.iter().map(|s| &s.path), source, spec::load_content);
+    specs
+        .iter()
+        .zip(contents)
+        .map(|(s, content)| Doc {
+            key: s.name.clone(),
+            scope: s.project.clone(),
+            path: s.path.clone(),
+            title: s.title.clone(),
+            ..artifact_doc(Kind::Spec, source, &content)
+        })
+        .collect()
+}
+
+/// Plans and specs from every source. The archived listings also walk the
+/// active files, so those are dropped there to avoid duplicate hits.
+pub fn artifact_docs() -> Vec<Doc> {
+    let mut docs = plan_docs(&plan::list_plans(), Source::Active);
+    docs.extend(spec_docs(&spec::list_specs(), Source::Active));
+    let active: HashSet<PathBuf> = docs.iter().map(|d| d.path.clone()).collect();
+    let mut archived = plan_docs(&plan::list_archived_plans(), Source::Archived);
+    archived.extend(spec_docs(&spec::list_archived_specs(), Source::Archived));
+    archived.retain(|d| !active.contains(&d.path));
+    docs.extend(archived);
+    docs.extend(plan_docs(
+        &plan::list_git_notes_plans_all(),
+        Source::GitNotes,
+    ));
+    docs.extend(spec_docs(
+        &spec::list_git_notes_specs_all(),
+        Source::GitNotes,
+    ));
+    docs
+}
+
+/// Active and archived tasks of each list.
+pub fn list_docs(store: &Store, list_ids: &[String]) -> Vec<Doc> {
+    let mut docs = Vec::new();
+    for id in list_ids {
+        docs.extend(task_docs(&store.list_tasks(id), id, Source::Active));
+        docs.extend(task_docs(
+            &store.list_archived_tasks(id),
+            id,
+            Source::Archived,
+        ));
+    }
+    docs
+}
+
+/// Lowercased search terms; `"quoted phrases"` stay together.
+pub fn terms(query: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    for (i, part) in query.split('"').enumerate() {
+        if i % 2 == 1 {
+            let phrase = part.trim();
+            if !phrase.is_empty() {
+                out.push(phrase.to_ascii_lowercase());
+            }
+        } else {
+            out.extend(part.split_whitespace().map(str::to_ascii_lowercase));
+        }
+    }
+    out
+}
+
+/// Rank `docs` against `query`. Every term must appear in the title or
+/// body; title matches weigh most, then body occurrences, with a bonus
+/// when the whole query appears as a phrase.
+pub fn search(docs: &[Doc], query: &str) -> Vec<Hit> {
+    let terms = terms(query);
+    if terms.is_empty() {
+        return Vec::new();
+    }
+    let phrase = (terms.len() > 1).then(|| terms.join(" "));
+
+    let mut hits: Vec<Hit> = docs
+        .iter()
+        .filter_map(|doc| {
+            let title = doc.title.to_ascii_lowercase();
+            let body = doc.body.to_ascii_lowercase();
+            let mut score = 0;
+            let mut rarest: Option<(&str, usize)> = None;
+            for term in &terms {
+                let in_title = title.matches(term.as_str()).count();
+                let in_body = body.matches(term.as_str()).count();
+                if in_title + in_body == 0 {
+                    return None;
+                }
+                score += in_title * 10 + in_body.min(10);
+                if in_body > 0 && rarest.is_none_or(|(_, n)| in_body < n) {
+                    rarest = Some((term, in_body));
+                }
+            }
+            if let Some(p) = &phrase
+                && (title.contains(p.as_str()) || body.contains(p.as_str()))
+            {
+                score += 5;
+            }
+            let (line, snippet) = match rarest {
+                Some((term, _)) => snippet(&doc.body, term),
+                None => (0, String::new()),
+            };
+            Some(Hit {
+                doc: doc.clone(),
+                score,
+                line: if line == 0 { 0 } else { line + doc.line_offset },
+                snippet,
+            })
+        })
+        .collect();
+    hits.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(a.doc.kind.cmp(&b.doc.kind))
+            .then(a.doc.title.cmp(&b.doc.title))
+    });
+    hits
+}
+
+/// First line of `body` containing `term` (lowercase), trimmed to a window
+/// around the match. Returns the 1-based line number and the text.
+fn snippet(body: &str, term: &str) -> (usize, String) {
+    let Some((idx, line)) = body
+        .lines()
+        .enumerate()
+        .find(|(_, l)| l.to_ascii_lowercase().contains(term))
+    else {
+        return (0, String::new());
+    };
+    let line = line.trim();
+    let chars: Vec<char> = line.chars().collect();
+    if chars.len() <= SNIPPET_WIDTH {
+        return (idx + 1, line.to_string());
+    }
+    let byte = line.to_ascii_lowercase().find(term).unwrap_or(0);
+    let at = line[..byte].chars().count();
+    let start = at.saturating_sub(SNIPPET_WIDTH / 3);
+    let end = (start + SNIPPET_WIDTH).min(chars.len());
+    let start = end.saturating_sub(SNIPPET_WIDTH);
+    let mut text: String = chars[start..end].iter().collect();
+    if start > 0 {
+        text.insert(0, '…');
+    }
+    if end < chars.len() {
+        text.push('…');
+    }
+    (idx + 1, text)
+}
+
+/// Byte ranges of `terms` in `text`, case-insensitively, merged and sorted,
+/// for highlighting.
+pub fn match_ranges(text: &str, terms: &[String]) -> Vec<(usize, usize)> {
+    let lower = text.to_ascii_lowercase();
+    let mut ranges: Vec<(usize, usize)> = terms
+        .iter()
+        .filter(|t| !t.is_empty())
+        .flat_map(|t| {
+            lower
+                .match_indices(t.as_str())
+                .map(|(i, m)| (i, i + m.len()))
+        })
+        .collect();
+    ranges.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (s, e) in ranges {
+        match merged.last_mut() {
+            Some(last) if s <= last.1 => last.1 = last.1.max(e),
+            _ => merged.push((s, e)),
+        }
+    }
+    merged
+}
+
+pub fn to_json(hits: &[Hit]) -> serde_json::Value {
+    hits.iter()
+        .map(|h| {
+            serde_json::json!({
+                "kind": h.doc.kind.as_str(),
+                "source": h.doc.source.label(),
+                "key": h.doc.key,
+                "scope": h.doc.scope,
+                "path": h.doc.path.to_string_lossy(),
+                "title": h.doc.title,
+                "score": h.score,
+                "line": h.line,
+                "snippet": h.snippet,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(kind: Kind, title: &str, body: &str) -> Doc {
+        Doc {
+            kind,
+            source: Source::Active,
+            key: title.to_lowercase(),
+            scope: String::new(),
+            path: PathBuf::new(),
+            title: title.to_string(),
+            body: body.to_string(),
+            line_offset: 0,
+        }
+    }
+
+    #[test]
+    fn ranks_title_over_body_and_requires_all_terms() {
+        let docs = vec![
+            doc(
+                Kind::Plan,
+                "Caching layer",
+                "We decided to use Redis.\nTTL is 5m.",
+            ),
+            doc(Kind::Task, "Add redis client", "wire it up"),
+            doc(Kind::Spec, "Storage", "no cache here"),
+        ];
+        let hits = search(&docs, "redis");
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].doc.title, "Add redis client");
+        assert_eq!(hits[1].line, 1);
+        assert_eq!(hits[1].snippet, "We decided to use Redis.");
+
+        assert_eq!(search(&docs, "redis ttl").len(), 1);
+        assert!(search(&docs, "redis postgres").is_empty());
+        assert!(search(&docs, "  ").is_empty());
+    }
+
+    #[test]
+    fn phrases_snippets_and_highlights() {
+        assert_eq!(
+            terms(r#"use "Redis cache" now"#),
+            vec!["use", "redis cache", "now"]
+        );
+
+        let long = format!("{} decided on sqlite {}", "x ".repeat(80), "y ".repeat(80));
+        let mut d = doc(Kind::Plan, "DB", &format!("intro\n{long}"));
+        d.line_offset = 4;
+        let hits = search(&[d], "sqlite");
+        assert_eq!(hits[0].line, 6);
+        assert!(hits[0].snippet.starts_with('…') && hits[0].snippet.ends_with('…'));
+        assert!(hits[0].snippet.contains("decided on sqlite"));
+
+        let text = "Redis and redis-cache";
+        assert_eq!(
+            match_ranges(text, &terms("redis cache")),
+            vec![(0, 5), (10, 15), (16, 21)]
+        );
+    }
+
+    #[test]
+    fn indexes_each_spec_in_a_git_note() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        let git = |args: &[&str]| {
+            let ok = std::process::Command::new("git")
+                .args(["-C", repo])
+                .args(args)
+                .output()
+                .unwrap()
+                .status
+                .success();
+            assert!(ok, "git {args:?}");
+        };
+        git(&["init", "-q"]);
+        git(&["config", "user.name", "t"]);
+        git(&["config", "user.email", "t@t"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "init"]);
+        let note = "---\ntopic: Auth\n---\nUse tokens.\n\n\
+---\ntopic: Storage\n---\n# Storage\n\nPick sqlite.\n";
+        crate::notes::write(repo, "specs", "HEAD", note).unwrap();
+
+        let docs = spec_docs(&spec::list_git_notes_specs(repo), Source::GitNotes);
+        assert_eq!(docs.len(), 2);
+        assert!(docs.iter().all(|d| d.kind == Kind::Spec));
+
+        let hits = search(&docs, "sqlite");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].doc.title, "Storage");
+        assert!(hits[0].doc.key.ends_with("/Storage"));
+        assert_eq!(hits[0].line, 6);
+    }
+}
@@ -1,8 +1,12 @@
+use std::collections::HashSet;
 use std::fs;
-use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
+
+use crate::artifact::{self, Lifecycle};
+use crate::frontmatter::Document;
+use crate::{notes, revisions};
 
 #[derive(Debug, Clone)]
 pub struct Spec {
@@ -10,6 +14,7 @@ pub struct Spec {
     pub path: PathBuf,
     pub title: String,
     pub project: String,
+    pub status: Lifecycle,
     pub mod_time: SystemTime,
     pub size: u64,
 }
@@ -22,6 +27,61 @@ pub fn list_archived_specs() -> Vec<Spec> {
     list_specs_filtered(true)
 }
 
+/// List specs from git notes across all known projects and the current
+/// directory's repo.
+pub fn list_git_notes_specs_all() -> Vec<Spec> {
+    let mut repos: HashSet<String> = list_specs()
+        .into_iter()
+        .chain(list_archived_specs())
+        .filter_map(|s| notes::repo_root(&s.project).ok())
+        .collect();
+    repos.extend(notes::repo_root(".").ok());
+
+    let mut all: Vec<Spec> = repos.iter().flat_map(|r| list_git_notes_specs(r)).collect();
+    all.sort_by_key(|a| std::cmp::Reverse(a.mod_time));
+    all
+}
+
+/// Specs stored in git notes (refs/notes/specs) in `repo`, one per note
+/// chunk, in note order.
+pub fn list_git_notes_specs(repo: &str) -> Vec<Spec> {
+    let mut specs = Vec::new();
+    for commit in notes::annotated_commits(repo, "specs") {
+        let mod_time = Command::new("git")
+            .args(["-C", repo, "log", "-1", "--format=%ct", &commit])
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .and_then(|o| String::from_utf8_lossy(&o.stdout).trim().parse().ok())
+            .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let short_sha = &commit[..7.min(commit.len())];
+        let note = notes::read(repo, "specs", &commit);
+        for (idx, chunk) in artifact::split_notes(&note).into_iter().enumerate() {
+            let (title, project, status) = extract_frontmatter_from_str(&chunk);
+            let label = if title.is_empty() {
+                format!("note:{short_sha}#{idx}")
+            } else {
+                title.clone()
+            };
+            specs.push(Spec {
+                name: format!("git-note:{short_sha}/{label}"),
+                path: PathBuf::from(format!("git-notes://{repo}/{commit}")),
+                title,
+                project: if project.is_empty() {
+                    repo.to_string()
+                } else {
+                    project
+                },
+                status,
+                mod_time,
+                size: chunk.len() as u64,
+            });
+        }
+    }
+    specs
+}
+
 fn list_specs_filtered(archived: bool) -> Vec<Spec> {
     let Some(home) = dirs::home_dir() else {
         return Vec::new();
@@ -41,6 +101,12 @@ fn collect_specs(base: &Path, dir: &Path, archived: bool, out: &mut Vec<Spec>) {
     for entry in entries.flatten() {
         let path = entry.path();
         if path.is_dir() {
+            if path
+                .file_name()
+                .is_some_and(|n| n == revisions::HISTORY_DIR)
+            {
+                continue;
+            }
             let is_archive = path.file_name().is_some_and(|n| n == "archive");
             if is_archive != archived {
                 if is_archive {
@@ -64,12 +130,13 @@ fn collect_specs(base: &Path, dir: &Path, archived: bool, out: &mut Vec<Spec>) {
             .with_extension("")
             .to_string_lossy()
             .to_string();
-        let (title, project) = extract_frontmatter(&path);
+        let (title, project, status) = extract_frontmatter(&path);
         out.push(Spec {
             name,
             path,
             title,
             project,
+            status,
             mod_time: info.modified().unwrap_or(SystemTime::UNIX_EPOCH),
             size: info.len(),
         });
@@ -95,34 +162,26 @@ pub fn load_content(path: &PathBuf) -> String {
     fs::read_to_string(path).unwrap_or_else(|e| format!("Error loading spec: {e}"))
 }
 
-fn extract_frontmatter(path: &Path) -> (String, String) {
-    let Ok(f) = fs::File::open(path) else {
-        return (String::new(), String::new());
-    };
-    let reader = BufReader::new(f);
-    let mut title = String::new();
-    let mut project = String::new();
-    let mut in_frontmatter = false;
+/// Title, project and status of a spec: frontmatter `topic`/`project`/
+/// `status`, with the first `# ` heading as the fallback title.
+fn extract_frontmatter(path: &Path) -> (String, String, Lifecycle) {
+    fs::read_to_string(path)
+        .map(|c| extract_frontmatter_from_str(&c))
+        .unwrap_or_default()
+}
 
-    for line in reader.lines().map_while(Result::ok) {
-        let trimmed = line.trim();
-        if trimmed == "---" {
-            if in_frontmatter {
-                break;
-            }
-            in_frontmatter = true;
-            continue;
-        }
-        if in_frontmatter {
-            if let Some(val) = trimmed.strip_prefix("topic:") {
-                title = val.trim().to_string();
-            } else if let Some(val) = trimmed.strip_prefix("project:") {
-                project = val.trim().to_string();
-            }
-        } else if let Some(t) = trimmed.strip_prefix("# ") {
-            title = t.to_string();
-            break;
-        }
-    }
-    (title, project)
+fn extract_frontmatter_from_str(content: &str) -> (String, String, Lifecycle) {
+    let doc = Document::parse(content);
+    let fm = doc.meta();
+    let status = Lifecycle::from_frontmatter(&fm);
+    let title = if fm.title.is_empty() {
+        doc.body
+            .lines()
+            .find_map(|l| l.trim().strip_prefix("# "))
+            .unwrap_or_default()
+            .to_string()
+    } else {
+        fm.title
+    };
+    (title, fm.project, status)
 }
@@ -3,9 +3,11 @@ use std::fs;
 use std::io::{self, IsTerminal, Read};
 use std::path::{Path, PathBuf};
 use std::process;
-use std::time::SystemTime;
 
 use crate::artifact;
+use crate::frontmatter::{Document, Frontmatter};
+use crate::notes;
+use crate::revisions;
 
 fn fatal(msg: &str) -> ! {
     eprintln!("specfile: {msg}");
@@ -22,6 +24,7 @@ pub fn cmd_create(args: &[String]) {
     let mut slug_flag = String::new();
     let mut prefix = String::new();
     let mut body = String::new();
+    let mut template: Option<String> = None;
 
     let mut i = 0;
     while i < args.len() {
@@ -46,6 +49,10 @@ pub fn cmd_create(args: &[String]) {
                 i += 1;
                 body = args.get(i).cloned().unwrap_or_default();
             }
+            "--template" => {
+                i += 1;
+                template = args.get(i).cloned();
+            }
             _ => {}
         }
         i += 1;
@@ -86,19 +93,13 @@ pub fn cmd_create(args: &[String]) {
 
     let now = artifact::chrono_rfc3339();
 
-    let mut buf = String::new();
-    buf.push_str("---\n");
-    buf.push_str(&format!("topic: {}\n", artifact::yaml_quote(&topic)));
-    buf.push_str(&format!("project: {}\n", artifact::yaml_quote(&project)));
-    buf.push_str(&format!("created: {now}\n"));
-    buf.push_str("---\n");
-    if !body.is_empty() {
-        buf.push_str(&body);
-        if !body.ends_with('\n') {
-            buf.push('\n');
-        }
-    }
+    let mut fm = Frontmatter::new(&topic);
+    fm.project = project;
+    fm.created = now;
+    let buf = crate::template::compose("spec", template.as_deref(), fm, &s, &body)
+        .unwrap_or_else(|e| fatal(&e));
 
+    revisions::snapshot(&full_path).unwrap_or_else(|e| fatal(&format!("saving revision: {e}")));
     fs::write(&full_path, &buf).unwrap_or_else(|e| fatal(&format!("writing file: {e}")));
     println!("{}", full_path.display());
 }
@@ -122,27 +123,12 @@ pub fn cmd_read(args: &[String]) {
     let content =
         fs::read_to_string(&file_path).unwrap_or_else(|e| fatal(&format!("reading file: {e}")));
 
-    let (yaml, body) = artifact::parse_frontmatter(&content);
+    let doc = Document::parse(&content);
 
     if frontmatter_mode {
-        match yaml {
-            None => println!("{{}}"),
-            Some(y) => {
-                let pairs = artifact::parse_yaml_map(y);
-                print!("{{");
-                for (i, (k, v)) in pairs.iter().enumerate() {
-                    if i > 0 {
-                        print!(",");
-                    }
-                    let k_escaped = k.replace('\\', "\\\\").replace('"', "\\\"");
-                    let v_escaped = v.replace('\\', "\\\\").replace('"', "\\\"");
-                    print!("\"{k_escaped}\":\"{v_escaped}\"");
-                }
-                println!("}}");
-            }
-        }
+        println!("{}", doc.meta().to_json());
     } else {
-        print!("{body}");
+        print!("{}", doc.body);
     }
 }
 
@@ -156,27 +142,10 @@ pub fn latest_spec(task_file: Option<&str>, project: &str) -> Result<PathBuf, St
     }
 
     let dir = specs_dir(project);
-    let entries = fs::read_dir(&dir)
+    let latest = artifact::latest_in(&dir)
         .map_err(|e| format!("cannot read specs directory {}: {e}", dir.display()))?;
 
-    let mut latest_path: Option<PathBuf> = None;
-    let mut latest_time = SystemTime::UNIX_EPOCH;
-
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.is_dir() || path.extension().and_then(|e| e.to_str()) != Some("md") {
-            continue;
-        }
-        if let Ok(meta) = entry.metadata()
-            && let Ok(modified) = meta.modified()
-            && modified > latest_time
-        {
-            latest_time = modified;
-            latest_path = Some(path);
-        }
-    }
-
-    latest_path.ok_or_else(|| format!("no spec files found in {}", dir.display()))
+    latest.ok_or_else(|| format!("no spec files found in {}", dir.display()))
 }
 
 pub fn cmd_latest(args: &[String]) {
@@ -234,39 +203,18 @@ pub fn cmd_archive(args: &[String]) {
 
     let content = fs::read_to_string(path).unwrap_or_else(|e| fatal(&format!("reading file: {e}")));
 
-    let (yaml, _) = artifact::parse_frontmatter(&content);
-    let project = yaml
-        .map(|y| {
-            artifact::parse_yaml_map(y)
-                .into_iter()
-                .find(|(k, _)| k == "project")
-                .map(|(_, v)| v)
-                .unwrap_or_default()
-        })
-        .unwrap_or_default();
+    let project = Document::parse(&content).meta().project;
 
     if project.is_empty() {
         fatal("spec has no project field — cannot determine git repo");
     }
 
-    let git_dir = process::Command::new("git")
-        .args(["-C", &project, "rev-parse", "--show-toplevel"])
-        .output()
-        .ok()
-        .filter(|o| o.status.success())
-        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
-        .unwrap_or_else(|| fatal(&format!("not a git repository: {project}")));
-
-    let note_status = process::Command::new("git")
-        .args(["-C", &git_dir, "notes", "--ref=specs", "append", "-F"])
-        .arg(path)
-        .arg("HEAD")
-        .status()
-        .unwrap_or_else(|e| fatal(&format!("running git notes: {e}")));
-
-    if !note_status.success() {
-        fatal("git notes append failed — spec file preserved");
-    }
+    let git_dir = notes::repo_root(&project).unwrap_or_else(|e| fatal(&e));
+    let head = notes::resolve_commit(&git_dir, "HEAD").unwrap_or_else(|e| fatal(&e));
+
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    notes::add(&git_dir, "specs", &head, &content, &stem)
+        .unwrap_or_else(|e| fatal(&format!("git notes failed — spec file preserved: {e}")));
 
     let parent = path
         .parent()
@@ -279,5 +227,11 @@ pub fn cmd_archive(args: &[String]) {
         .unwrap_or_else(|| fatal("cannot determine file name"));
     let dest = archive_dir.join(file_name);
     fs::rename(path, &dest).unwrap_or_else(|e| fatal(&format!("archiving file: {e}")));
+    if let Err(e) = revisions::move_history(path, &dest) {
+        eprintln!(
+            "Warning: revisions left in {}: {e}",
+            revisions::history_dir(path).display()
+        );
+    }
     eprintln!("Archived: {file_path} → git notes + {}", dest.display());
 }
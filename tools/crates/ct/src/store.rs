@@ -5,6 +5,13 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
+use crate::history;
+use crate::query::Query;
+
+/// Deleted tasks are moved here rather than removed, so undo can restore
+/// them; `ct task prune` empties out old entries.
+pub const TRASH_DIR: &str = ".trash";
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Status {
     Pending,
@@ -233,6 +240,8 @@ pub struct TaskList {
 
 pub struct Store {
     base: PathBuf,
+    /// Session making changes, recorded as the actor in the journal.
+    session: String,
 }
 
 impl Store {
@@ -240,6 +249,7 @@ impl Store {
         let home = dirs::home_dir().expect("no home directory");
         Self {
             base: home.join(".claude").join("tasks"),
+            session: history::current_session(),
         }
     }
 
@@ -268,25 +278,20 @@ impl Store {
     }
 
     pub fn list_tasks(&self, list_id: &str) -> Vec<Task> {
-        let dir = self.base.join(list_id);
-        let Ok(entries) = fs::read_dir(&dir) else {
-            return Vec::new();
-        };
-        let mut tasks: Vec<Task> = entries
-            .flatten()
-            .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
-            .filter_map(|e| {
-                let data = fs::read_to_string(e.path()).ok()?;
-                let val: Value = serde_json::from_str(&data).ok()?;
-                Some(Task::from_raw(val))
-            })
-            .collect();
-        tasks.sort_by(|a, b| {
-            let a_num: u64 = a.id.parse().unwrap_or(u64::MAX);
-            let b_num: u64 = b.id.parse().unwrap_or(u64::MAX);
-            a_num.cmp(&b_num)
-        });
-        tasks
+        read_task_dir(&self.base.join(list_id))
+    }
+
+    pub fn list_archived_tasks(&self, list_id: &str) -> Vec<Task> {
+        read_task_dir(&self.base.join(list_id).join("archive"))
+    }
+
+    pub fn load_archived_task(&self, list_id: &str, task_id: &str) -> Option<Task> {
+        let path = self
+            .base
+            .join(list_id)
+            .join("archive")
+            .join(format!("{task_id}.json"));
+        read_value(&path).map(Task::from_raw)
     }
 
     pub fn load_task(&self, list_id: &str, task_id: &str) -> Option<Task> {
@@ -297,21 +302,47 @@ impl Store {
     }
 
     pub fn save_task(&self, list_id: &str, task: &Task) -> Result<(), String> {
+        let _lock = self.lock_list(list_id)?;
+        self.write_task(list_id, task)
+    }
+
+    /// `save_task` without taking the lock; callers must hold it.
+    pub fn write_task(&self, list_id: &str, task: &Task) -> Result<(), String> {
         let path = self.base.join(list_id).join(format!("{}.json", task.id));
-        let json =
-            serde_json::to_string_pretty(&task.to_json()).map_err(|e| format!("serialize: {e}"))?;
-        atomic_write(&path, &json)
+        let previous = read_value(&path);
+        let new_val = task.to_json();
+        let json = serde_json::to_string_pretty(&new_val).map_err(|e| format!("serialize: {e}"))?;
+        atomic_write(&path, &json)?;
+
+        let changes = history::diff(previous.as_ref().unwrap_or(&Value::Null), &new_val);
+        if !changes.is_empty() {
+            let op = if previous.is_some() {
+                history::Op::Update
+            } else {
+                history::Op::Create
+            };
+            self.record(list_id, op, &task.id, &new_val, changes);
+        }
+        Ok(())
     }
 
     pub fn create_task(&self, list_id: &str, task: &Task) -> Result<Task, String> {
+        // Held until the task file is written so another process can't
+        // allocate the same ID in between.
+        let _lock = self.lock_list(list_id)?;
+        self.insert_task(list_id, task)
+    }
+
+    /// `create_task` without taking the lock; callers must hold it.
+    pub fn insert_task(&self, list_id: &str, task: &Task) -> Result<Task, String> {
         let dir = self.base.join(list_id);
         let next_id = self.next_id(list_id);
 
         let mut new_task = task.clone();
         new_task.id = next_id.to_string();
 
-        // Build raw JSON
-        let mut raw = serde_json::Map::new();
+        // Build raw JSON, keeping any extra keys the caller set
+        let mut raw = task.raw.as_object().cloned().unwrap_or_default();
         raw.insert("id".into(), Value::String(new_task.id.clone()));
         raw.insert("subject".into(), Value::String(new_task.subject.clone()));
         raw.insert(
@@ -323,7 +354,11 @@ impl Store {
             Value::String(new_task.status.as_str().to_string()),
         );
 
-        let mut meta = serde_json::Map::new();
+        let mut meta = raw
+            .get("metadata")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
         set_or_delete(&mut meta, "priority", new_task.priority.as_str());
         set_or_delete(&mut meta, "type", &new_task.task_type);
         set_or_delete(&mut meta, "parent_id", &new_task.parent_id);
@@ -334,26 +369,286 @@ impl Store {
         new_task.raw = Value::Object(raw);
 
         let path = dir.join(format!("{next_id}.json"));
-        let json = serde_json::to_string_pretty(&new_task.to_json())
-            .map_err(|e| format!("serialize: {e}"))?;
+        let new_val = new_task.to_json();
+        let json = serde_json::to_string_pretty(&new_val).map_err(|e| format!("serialize: {e}"))?;
         atomic_write(&path, &json)?;
+        let changes = history::diff(&Value::Null, &new_val);
+        self.record(
+            list_id,
+            history::Op::Create,
+            &new_task.id,
+            &new_val,
+            changes,
+        );
         Ok(new_task)
     }
 
     pub fn delete_task(&self, list_id: &str, task_id: &str) -> Result<(), String> {
+        let _lock = self.lock_list(list_id)?;
+        self.remove_task(list_id, task_id)
+    }
+
+    /// `delete_task` without taking the lock; callers must hold it.
+    fn remove_task(&self, list_id: &str, task_id: &str) -> Result<(), String> {
         let path = self.base.join(list_id).join(format!("{task_id}.json"));
-        fs::remove_file(path).map_err(|e| format!("delete: {e}"))
+        let previous = read_value(&path).unwrap_or(Value::Null);
+        fs::remove_file(path).map_err(|e| format!("delete: {e}"))?;
+        let changes = history::diff(&previous, &Value::Null);
+        self.record(list_id, history::Op::Delete, task_id, &previous, changes);
+        Ok(())
+    }
+
+    /// Delete a task by moving its JSON into `<list>/.trash/`, replacing any
+    /// earlier trashed copy with the same ID.
+    pub fn trash_task(&self, list_id: &str, task_id: &str) -> Result<(), String> {
+        let _lock = self.lock_list(list_id)?;
+        let src = self.base.join(list_id).join(format!("{task_id}.json"));
+        let trash_dir = self.base.join(list_id).join(TRASH_DIR);
+        fs::create_dir_all(&trash_dir).map_err(|e| format!("create trash dir: {e}"))?;
+        let previous = read_value(&src).unwrap_or(Value::Null);
+        let dest = trash_dir.join(format!("{task_id}.json"));
+        fs::rename(&src, &dest).map_err(|e| format!("delete: {e}"))?;
+        // Expiry goes by when it was trashed, not last edited
+        let _ = fs::File::options()
+            .write(true)
+            .open(&dest)
+            .and_then(|f| f.set_modified(SystemTime::now()));
+        let changes = history::diff(&previous, &Value::Null);
+        self.record(list_id, history::Op::Delete, task_id, &previous, changes);
+        Ok(())
+    }
+
+    /// Move a trashed task back into the list under its own ID.
+    pub fn untrash_task(&self, list_id: &str, task_id: &str) -> Result<(), String> {
+        let _lock = self.lock_list(list_id)?;
+        let src = self
+            .base
+            .join(list_id)
+            .join(TRASH_DIR)
+            .join(format!("{task_id}.json"));
+        let val = read_value(&src).ok_or(format!("#{task_id} is not in the trash"))?;
+        let dest = self.base.join(list_id).join(format!("{task_id}.json"));
+        if dest.exists() {
+            return Err(format!("#{task_id} already exists"));
+        }
+        fs::rename(&src, &dest).map_err(|e| format!("restore: {e}"))?;
+        let changes = history::diff(&Value::Null, &val);
+        self.record(list_id, history::Op::Create, task_id, &val, changes);
+        Ok(())
+    }
+
+    /// Remove trashed tasks older than `max_age`, and the trash dir once it is
+    /// empty. Returns how many were removed.
+    pub fn expire_trash(&self, list_id: &str, max_age: Duration) -> Result<usize, String> {
+        let trash_dir = self.base.join(list_id).join(TRASH_DIR);
+        if !trash_dir.is_dir() {
+            return Ok(0);
+        }
+        let _lock = self.lock_list(list_id)?;
+        let now = SystemTime::now();
+        let mut removed = 0;
+        for entry in fs::read_dir(&trash_dir).into_iter().flatten().flatten() {
+            let expired = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| now.duration_since(t).ok())
+                .is_some_and(|age| age >= max_age);
+            if expired && fs::remove_file(entry.path()).is_ok() {
+                removed += 1;
+            }
+        }
+        // Fails while entries remain, which is fine
+        let _ = fs::remove_dir(&trash_dir);
+        Ok(removed)
+    }
+
+    /// Move (or copy) a task and its subtree into another list. Every task gets
+    /// a fresh ID from the destination's `next_id`. `parent_id`, `blocks` and
+    /// `blocked_by` are rewritten inside the subtree; links that would cross
+    /// lists are dropped on both sides. Returns `(old, new)` ID pairs, root
+    /// first.
+    pub fn move_task(
+        &self,
+        from: &str,
+        task_id: &str,
+        to: &str,
+        copy: bool,
+    ) -> Result<Vec<(String, String)>, String> {
+        if from == to {
+            return Err("source and destination are the same list".to_string());
+        }
+        if !self.base.join(to).is_dir() {
+            return Err(format!("task list not found: {to}"));
+        }
+        // Fixed lock order so two opposite moves can't deadlock
+        let (first, second) = if from < to { (from, to) } else { (to, from) };
+        let _first = self.lock_list(first)?;
+        let _second = self.lock_list(second)?;
+
+        let tasks = self.list_tasks(from);
+        if !tasks.iter().any(|t| t.id == task_id) {
+            return Err(format!("task not found: #{task_id}"));
+        }
+        let subtree = subtree_ids(&tasks, task_id);
+        let pairs: Vec<(String, String)> = subtree
+            .iter()
+            .map(|id| (id.clone(), self.next_id(to).to_string()))
+            .collect();
+        let mapping: HashMap<&str, &str> = pairs
+            .iter()
+            .map(|(old, new)| (old.as_str(), new.as_str()))
+            .collect();
+        let remap = |ids: &[String]| -> Vec<String> {
+            ids.iter()
+                .filter_map(|id| mapping.get(id.as_str()).map(|n| n.to_string()))
+                .collect()
+        };
+
+        // Write the destination first: a crash leaves duplicates, not losses
+        for (old, new) in &pairs {
+            let Some(mut t) = tasks.iter().find(|t| &t.id == old).cloned() else {
+                continue;
+            };
+            t.id = new.clone();
+            t.parent_id = mapping
+                .get(t.parent_id.as_str())
+                .map(|p| p.to_string())
+                .unwrap_or_default();
+            let blocks = remap(&t.blocks);
+            let blocked_by = remap(&t.blocked_by);
+            set_links(&mut t, blocks, blocked_by);
+            self.write_task(to, &t)?;
+        }
+
+        if !copy {
+            for t in tasks
+                .iter()
+                .filter(|t| !mapping.contains_key(t.id.as_str()))
+            {
+                let keep = |ids: &[String]| -> Vec<String> {
+                    ids.iter()
+                        .filter(|id| !mapping.contains_key(id.as_str()))
+                        .cloned()
+                        .collect()
+                };
+                let (blocks, blocked_by) = (keep(&t.blocks), keep(&t.blocked_by));
+                if blocks.len() != t.blocks.len() || blocked_by.len() != t.blocked_by.len() {
+                    let mut t = t.clone();
+                    set_links(&mut t, blocks, blocked_by);
+                    self.write_task(from, &t)?;
+                }
+            }
+            for old in &subtree {
+                self.remove_task(from, old)?;
+            }
+        }
+        Ok(pairs)
     }
 
     pub fn archive_task(&self, list_id: &str, task_id: &str) -> Result<(), String> {
+        let _lock = self.lock_list(list_id)?;
         let src = self.base.join(list_id).join(format!("{task_id}.json"));
         let archive_dir = self.base.join(list_id).join("archive");
         fs::create_dir_all(&archive_dir).map_err(|e| format!("create archive dir: {e}"))?;
         let dest = archive_dir.join(format!("{task_id}.json"));
-        fs::rename(&src, &dest).map_err(|e| format!("archive: {e}"))
+        let previous = read_value(&src).unwrap_or(Value::Null);
+        fs::rename(&src, &dest).map_err(|e| format!("archive: {e}"))?;
+        self.record(
+            list_id,
+            history::Op::Archive,
+            task_id,
+            &previous,
+            Vec::new(),
+        );
+        Ok(())
+    }
+
+    /// Move an archived task back into the active list and return its ID. If
+    /// an active task already holds that ID, the restored task gets a fresh
+    /// one from `next_id`, and `parent_id`, `blocks` and `blocked_by` in the
+    /// rest of the archive follow it; active tasks keep pointing at the
+    /// active holder.
+    pub fn unarchive_task(&self, list_id: &str, task_id: &str) -> Result<String, String> {
+        let _lock = self.lock_list(list_id)?;
+        let src = self
+            .base
+            .join(list_id)
+            .join("archive")
+            .join(format!("{task_id}.json"));
+        let mut val = read_value(&src).ok_or(format!("#{task_id} is not archived"))?;
+        let dest = self.base.join(list_id).join(format!("{task_id}.json"));
+        if !dest.exists() {
+            fs::rename(&src, &dest).map_err(|e| format!("unarchive: {e}"))?;
+            self.record(list_id, history::Op::Unarchive, task_id, &val, Vec::new());
+            return Ok(task_id.to_string());
+        }
+
+        let new_id = self.next_id(list_id).to_string();
+        let before = val.clone();
+        if let Some(obj) = val.as_object_mut() {
+            obj.insert("id".into(), Value::String(new_id.clone()));
+        }
+        let dest = self.base.join(list_id).join(format!("{new_id}.json"));
+        let json = serde_json::to_string_pretty(&val).map_err(|e| format!("serialize: {e}"))?;
+        atomic_write(&dest, &json)?;
+        fs::remove_file(&src).map_err(|e| format!("remove archived copy: {e}"))?;
+        let follow = |ids: &[String]| -> Vec<String> {
+            ids.iter()
+                .map(|id| if id == task_id { &new_id } else { id })
+                .cloned()
+                .collect()
+        };
+        for mut t in self.list_archived_tasks(list_id) {
+            let refers = t.parent_id == task_id
+                || t.blocks.iter().chain(&t.blocked_by).any(|id| id == task_id);
+            if !refers {
+                continue;
+            }
+            if t.parent_id == task_id {
+                t.parent_id = new_id.clone();
+            }
+            let (blocks, blocked_by) = (follow(&t.blocks), follow(&t.blocked_by));
+            set_links(&mut t, blocks, blocked_by);
+            let json = serde_json::to_string_pretty(&t.to_json())
+                .map_err(|e| format!("serialize: {e}"))?;
+            atomic_write(&src.with_file_name(format!("{}.json", t.id)), &json)?;
+        }
+        let changes = history::diff(&before, &val);
+        self.record(list_id, history::Op::Unarchive, &new_id, &val, changes);
+        Ok(new_id)
+    }
+
+    /// Journal entries for a task, oldest first.
+    pub fn task_history(&self, list_id: &str, task_id: &str) -> Vec<history::Entry> {
+        history::read(&self.base.join(list_id), task_id)
+    }
+
+    /// Append to the list's history journal. Best-effort: the task write has
+    /// already succeeded, so a journal failure must not report the mutation
+    /// as failed.
+    fn record(
+        &self,
+        list_id: &str,
+        op: history::Op,
+        task_id: &str,
+        task: &Value,
+        changes: Vec<history::Change>,
+    ) {
+        let entry = history::Entry::new(
+            op,
+            task_id,
+            history::actor_for(&self.session, task),
+            changes,
+        );
+        let _ = history::append(&self.base.join(list_id), &entry);
     }
 
-    pub fn prune_empty_lists(&self) -> Vec<String> {
+    /// Remove UUID lists with no tasks, archive or quarantine. A history
+    /// journal written to within `retention` keeps the list, so recent
+    /// deletes stay auditable; trashed tasks never do.
+    pub fn prune_empty_lists(&self, retention: Duration) -> Vec<String> {
+        let now = SystemTime::now();
         let mut removed = Vec::new();
         for list in self.list_task_lists() {
             if !is_uuid(&list.id) {
@@ -369,7 +664,18 @@ impl Store {
                 })
                 .unwrap_or(true);
             let has_archive = dir.join("archive").is_dir();
-            if !has_json && !has_archive && fs::remove_dir_all(&dir).is_ok() {
+            let has_quarantine = dir.join(crate::doctor::QUARANTINE_DIR).is_dir();
+            let recent_history = fs::metadata(dir.join(history::HISTORY_FILE))
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| now.duration_since(t).ok())
+                .is_some_and(|age| age < retention);
+            if !has_json
+                && !has_archive
+                && !has_quarantine
+                && !recent_history
+                && fs::remove_dir_all(&dir).is_ok()
+            {
                 removed.push(list.id);
             }
         }
@@ -378,9 +684,27 @@ impl Store {
 
     #[cfg(test)]
     pub fn with_base(base: PathBuf) -> Self {
-        Self { base }
+        Self {
+            base,
+            session: String::new(),
+        }
+    }
+
+    /// Take an exclusive advisory lock on `<list>/.lock`, blocking until it is
+    /// free. Released when the returned handle is dropped.
+    pub fn lock_list(&self, list_id: &str) -> Result<fs::File, String> {
+        let path = self.base.join(list_id).join(".lock");
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(|e| format!("open lock: {e}"))?;
+        file.lock().map_err(|e| format!("lock: {e}"))?;
+        Ok(file)
     }
 
+    /// Callers must hold the list lock.
     fn next_id(&self, list_id: &str) -> u64 {
         // Check .highwatermark first
         let hwm_path = self.base.join(list_id).join(".highwatermark");
@@ -389,10 +713,12 @@ impl Store {
             .and_then(|s| s.trim().parse::<u64>().ok())
             .unwrap_or(0);
 
-        // Also scan existing files
+        // Also scan existing files, including archived ones so a restored
+        // task can never collide with a fresh ID
         let max_file = self
             .list_tasks(list_id)
             .iter()
+            .chain(self.list_archived_tasks(list_id).iter())
             .filter_map(|t| t.id.parse::<u64>().ok())
             .max()
             .unwrap_or(0);
@@ -454,7 +780,59 @@ fn is_uuid(s: &str) -> bool {
     s.len() > 30 && s.contains('-')
 }
 
-fn atomic_write(path: &Path, content: &str) -> Result<(), String> {
+/// `root` followed by all its descendants via `parent_id`, breadth-first.
+fn subtree_ids(tasks: &[Task], root: &str) -> Vec<String> {
+    let mut ids = vec![root.to_string()];
+    let mut i = 0;
+    while i < ids.len() {
+        let parent = ids[i].clone();
+        for t in tasks {
+            // The contains check also stops parent cycles from looping forever
+            if t.parent_id == parent && !ids.contains(&t.id) {
+                ids.push(t.id.clone());
+            }
+        }
+        i += 1;
+    }
+    ids
+}
+
+/// Replace a task's ID and dependency links in both the typed fields and
+/// `raw`, since `to_json` only merges metadata-backed fields.
+pub fn set_links(task: &mut Task, blocks: Vec<String>, blocked_by: Vec<String>) {
+    if let Some(obj) = task.raw.as_object_mut() {
+        obj.insert("id".into(), Value::String(task.id.clone()));
+        obj.insert("blocks".into(), serde_json::json!(blocks));
+        obj.insert("blockedBy".into(), serde_json::json!(blocked_by));
+    }
+    task.blocks = blocks;
+    task.blocked_by = blocked_by;
+}
+
+fn read_task_dir(dir: &Path) -> Vec<Task> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut tasks: Vec<Task> = entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| read_value(&e.path()).map(Task::from_raw))
+        .collect();
+    tasks.sort_by(|a, b| {
+        let a_num: u64 = a.id.parse().unwrap_or(u64::MAX);
+        let b_num: u64 = b.id.parse().unwrap_or(u64::MAX);
+        a_num.cmp(&b_num)
+    });
+    tasks
+}
+
+fn read_value(path: &Path) -> Option<Value> {
+    let data = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+/// Write via a temp file and rename, so readers never see a partial file.
+pub fn atomic_write(path: &Path, content: &str) -> Result<(), String> {
     let dir = path.parent().ok_or("no parent directory")?;
     let nanos = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -474,13 +852,15 @@ pub enum StatusFilter {
     Pending,
     InProgress,
     Completed,
+    Ready,
 }
 
 impl StatusFilter {
     pub fn next(self) -> Self {
         match self {
             Self::All => Self::Active,
-            Self::Active => Self::Pending,
+            Self::Active => Self::Ready,
+            Self::Ready => Self::Pending,
             Self::Pending => Self::InProgress,
             Self::InProgress => Self::Completed,
             Self::Completed => Self::All,
@@ -494,14 +874,29 @@ impl StatusFilter {
             Self::Pending => "pending",
             Self::InProgress => "in_progress",
             Self::Completed => "completed",
+            Self::Ready => "ready",
         }
     }
 
+    pub fn from_label(s: &str) -> Option<Self> {
+        match s {
+            "all" => Some(Self::All),
+            "active" => Some(Self::Active),
+            "pending" => Some(Self::Pending),
+            "in_progress" => Some(Self::InProgress),
+            "completed" => Some(Self::Completed),
+            "ready" => Some(Self::Ready),
+            _ => None,
+        }
+    }
+
+    /// Status-only check. `Ready` also needs blocker state, which
+    /// `filter_and_sort` applies on top of this.
     pub fn matches(self, status: &Status) -> bool {
         match self {
             Self::All => true,
             Self::Active => *status != Status::Completed,
-            Self::Pending => *status == Status::Pending,
+            Self::Pending | Self::Ready => *status == Status::Pending,
             Self::InProgress => *status == Status::InProgress,
             Self::Completed => *status == Status::Completed,
         }
@@ -531,6 +926,15 @@ impl SortOrder {
             Self::Subject => "subject",
         }
     }
+
+    pub fn from_label(s: &str) -> Option<Self> {
+        match s {
+            "id" => Some(Self::Id),
+            "priority" => Some(Self::Priority),
+            "subject" => Some(Self::Subject),
+            _ => None,
+        }
+    }
 }
 
 pub fn filter_and_sort(
@@ -540,9 +944,16 @@ pub fn filter_and_sort(
     show_closed: bool,
     query: &str,
 ) -> Vec<Task> {
+    let completed = completed_ids(tasks);
+    let query = Query::parse(query);
+    // An explicit status term in the query overrides hiding closed tasks
+    let show_closed = show_closed || query.mentions_status();
     let mut result: Vec<Task> = tasks
         .iter()
         .filter(|t| {
+            if status_filter == StatusFilter::Ready && !is_ready(t, &completed) {
+                return false;
+            }
             if !show_closed
                 && t.status == Status::Completed
                 && status_filter != StatusFilter::Completed
@@ -552,13 +963,7 @@ pub fn filter_and_sort(
             if !status_filter.matches(&t.status) {
                 return false;
             }
-            if !query.is_empty() {
-                let q = query.to_lowercase();
-                if !t.subject.to_lowercase().contains(&q) {
-                    return false;
-                }
-            }
-            true
+            query.matches(t, &completed)
         })
         .cloned()
         .collect();
@@ -762,6 +1167,50 @@ pub fn child_counts(tasks: &[Task]) -> HashMap<String, (usize, usize)> {
     counts
 }
 
+/// IDs of completed tasks, the set blockers are checked against.
+pub fn completed_ids(tasks: &[Task]) -> HashSet<&str> {
+    tasks
+        .iter()
+        .filter(|t| t.status == Status::Completed)
+        .map(|t| t.id.as_str())
+        .collect()
+}
+
+/// A task is ready when it is pending and every `blocked_by` entry refers to
+/// a completed task in the same list. Unknown blockers count as unresolved,
+/// matching how the list views flag blocked rows.
+pub fn is_ready(task: &Task, completed: &HashSet<&str>) -> bool {
+    task.status == Status::Pending
+        && task
+            .blocked_by
+            .iter()
+            .all(|dep| completed.contains(dep.as_str()))
+}
+
+/// Pending tasks with no outstanding blockers, ordered by priority and then
+/// by position in the parent/child tree.
+pub fn ready_tasks(tasks: &[Task]) -> Vec<Task> {
+    let completed = completed_ids(tasks);
+    let position: HashMap<String, usize> = tree_order(tasks)
+        .into_iter()
+        .enumerate()
+        .map(|(i, row)| (row.task.id, i))
+        .collect();
+
+    let mut ready: Vec<Task> = tasks
+        .iter()
+        .filter(|t| is_ready(t, &completed))
+        .cloned()
+        .collect();
+    ready.sort_by_key(|t| {
+        (
+            t.priority.sort_key(),
+            position.get(&t.id).copied().unwrap_or(usize::MAX),
+        )
+    });
+    ready
+}
+
 fn link_counts_by(tasks: &[Task], field: fn(&Task) -> &str) -> HashMap<String, usize> {
     let mut counts = HashMap::new();
     for t in tasks {
@@ -805,6 +1254,8 @@ pub fn vibe_stage_index(stage: &str) -> usize {
 mod tests {
     use super::*;
 
+    const RETENTION: Duration = Duration::from_secs(7 * 86400);
+
     #[test]
     fn archive_task_moves_file_to_archive_subdir() {
         let dir = tempfile::tempdir().unwrap();
@@ -841,7 +1292,7 @@ mod tests {
         let uuid_id = "a1b2c3d4-e5f6-7890-abcd-ef1234567890";
         fs::create_dir_all(dir.path().join(uuid_id)).unwrap();
 
-        let removed = store.prune_empty_lists();
+        let removed = store.prune_empty_lists(RETENTION);
 
         assert_eq!(removed, vec![uuid_id]);
         assert!(!dir.path().join(uuid_id).exists());
@@ -856,7 +1307,7 @@ mod tests {
         fs::create_dir_all(&list_dir).unwrap();
         fs::write(list_dir.join("1.json"), r#"{"id":"1"}"#).unwrap();
 
-        let removed = store.prune_empty_lists();
+        let removed = store.prune_empty_lists(RETENTION);
 
         assert!(removed.is_empty());
         assert!(list_dir.exists());
@@ -876,7 +1327,7 @@ mod tests {
         )
         .unwrap();
 
-        let removed = store.prune_empty_lists();
+        let removed = store.prune_empty_lists(RETENTION);
 
         assert!(
             removed.is_empty(),
@@ -885,6 +1336,303 @@ mod tests {
         assert!(list_dir.exists());
     }
 
+    #[test]
+    fn prune_empty_lists_keeps_uuid_dirs_with_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::with_base(dir.path().to_path_buf());
+        let uuid_id = "a1b2c3d4-e5f6-7890-abcd-ef1234567890";
+        let list_dir = dir.path().join(uuid_id);
+        // Every task was deleted, but the journal of those deletes remains
+        fs::create_dir_all(&list_dir).unwrap();
+        fs::write(
+            list_dir.join(crate::history::HISTORY_FILE),
+            "{\"op\":\"delete\",\"task_id\":\"1\"}\n",
+        )
+        .unwrap();
+
+        let removed = store.prune_empty_lists(RETENTION);
+
+        assert!(
+            removed.is_empty(),
+            "list with a recent history journal should not be removed"
+        );
+        assert!(list_dir.exists());
+
+        // Once the journal is past retention it no longer keeps the list
+        let removed = store.prune_empty_lists(Duration::ZERO);
+        assert_eq!(removed, vec![uuid_id]);
+        assert!(!list_dir.exists());
+    }
+
+    #[test]
+    fn expired_trash_does_not_keep_a_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::with_base(dir.path().to_path_buf());
+        let uuid_id = "a1b2c3d4-e5f6-7890-abcd-ef1234567890";
+        let list_dir = dir.path().join(uuid_id);
+        fs::create_dir_all(&list_dir).unwrap();
+        fs::write(list_dir.join("1.json"), r#"{"id":"1"}"#).unwrap();
+        store.trash_task(uuid_id, "1").unwrap();
+
+        assert_eq!(store.expire_trash(uuid_id, RETENTION).unwrap(), 0);
+        assert!(list_dir.join(TRASH_DIR).join("1.json").exists());
+        assert_eq!(store.expire_trash(uuid_id, Duration::ZERO).unwrap(), 1);
+        assert!(!list_dir.join(TRASH_DIR).exists());
+
+        let removed = store.prune_empty_lists(Duration::ZERO);
+        assert_eq!(removed, vec![uuid_id]);
+    }
+
+    #[test]
+    fn concurrent_creators_get_unique_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("proj")).unwrap();
+
+        let handles: Vec<_> = (0..8)
+            .map(|worker| {
+                let base = dir.path().to_path_buf();
+                std::thread::spawn(move || {
+                    let store = Store::with_base(base);
+                    (0..10)
+                        .map(|n| {
+                            let task = Task::from_raw(serde_json::json!({
+                                "subject": format!("w{worker}-{n}")
+                            }));
+                            store.create_task("proj", &task).unwrap().id
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        let mut ids: Vec<u64> = handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .map(|id| id.parse().unwrap())
+            .collect();
+        ids.sort_unstable();
+        assert_eq!(ids, (1..=80).collect::<Vec<u64>>());
+
+        let store = Store::with_base(dir.path().to_path_buf());
+        assert_eq!(store.list_tasks("proj").len(), 80);
+    }
+
+    #[test]
+    fn create_after_archive_does_not_reuse_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::with_base(dir.path().to_path_buf());
+        fs::create_dir_all(dir.path().join("proj")).unwrap();
+        let task = Task::from_raw(serde_json::json!({"subject": "a"}));
+
+        let first = store.create_task("proj", &task).unwrap();
+        store.archive_task("proj", &first.id).unwrap();
+        let second = store.create_task("proj", &task).unwrap();
+
+        assert_ne!(first.id, second.id);
+    }
+
+    #[test]
+    fn mutations_are_journaled() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::with_base(dir.path().to_path_buf());
+        fs::create_dir_all(dir.path().join("proj")).unwrap();
+        let task = Task::from_raw(serde_json::json!({"subject": "a", "description": "old"}));
+
+        let mut created = store.create_task("proj", &task).unwrap();
+        created.description = "new".into();
+        store.save_task("proj", &created).unwrap();
+        // Saving unchanged content adds nothing
+        store.save_task("proj", &created).unwrap();
+        store.delete_task("proj", &created.id).unwrap();
+
+        let entries = store.task_history("proj", &created.id);
+        let ops: Vec<&str> = entries.iter().map(|e| e.op.as_str()).collect();
+        assert_eq!(ops, vec!["create", "update", "delete"]);
+        assert_eq!(entries[1].fields_summary(), "description");
+        assert_eq!(entries[1].changes[0].old, Value::String("old".into()));
+        assert!(
+            entries[2]
+                .changes
+                .iter()
+                .any(|c| c.field == "description" && c.new.is_null())
+        );
+    }
+
+    #[test]
+    fn journal_records_the_session_making_the_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = Store::with_base(dir.path().to_path_buf());
+        fs::create_dir_all(dir.path().join("proj")).unwrap();
+        store.session = "s1".into();
+        let task = Task::from_raw(serde_json::json!({
+            "subject": "a",
+            "owner": "bob",
+            "metadata": {"session_id": "s1"}
+        }));
+        let mut created = store.create_task("proj", &task).unwrap();
+
+        store.session = "s2".into();
+        created.subject = "b".into();
+        store.save_task("proj", &created).unwrap();
+        store.session.clear();
+        store.delete_task("proj", &created.id).unwrap();
+
+        let actors: Vec<String> = store
+            .task_history("proj", &created.id)
+            .into_iter()
+            .map(|e| e.actor)
+            .collect();
+        assert_eq!(actors, ["s1", "s2", "bob"]);
+    }
+
+    #[test]
+    fn unarchive_reassigns_id_on_conflict() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::with_base(dir.path().to_path_buf());
+        let list_dir = dir.path().join("proj");
+        fs::create_dir_all(list_dir.join("archive")).unwrap();
+        fs::write(list_dir.join("2.json"), r#"{"id":"2","subject":"live"}"#).unwrap();
+        fs::write(
+            list_dir.join("archive").join("2.json"),
+            r#"{"id":"2","subject":"old"}"#,
+        )
+        .unwrap();
+        fs::write(
+            list_dir.join("archive").join("5.json"),
+            r#"{"id":"5","subject":"other"}"#,
+        )
+        .unwrap();
+
+        let restored = store.unarchive_task("proj", "2").unwrap();
+
+        assert_eq!(restored, "6");
+        assert_eq!(store.load_task("proj", "2").unwrap().subject, "live");
+        assert_eq!(store.load_task("proj", "6").unwrap().subject, "old");
+        assert_eq!(store.load_task("proj", "6").unwrap().id, "6");
+        let archived: Vec<String> = store
+            .list_archived_tasks("proj")
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(archived, vec!["5"]);
+    }
+
+    #[test]
+    fn unarchive_conflict_carries_archived_references_to_the_new_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::with_base(dir.path().to_path_buf());
+        fs::create_dir_all(dir.path().join("proj").join("archive")).unwrap();
+        write_raw(
+            dir.path(),
+            "proj",
+            serde_json::json!({"id": "2", "subject": "live"}),
+        );
+        write_raw(
+            dir.path(),
+            "proj",
+            serde_json::json!({"id": "4", "subject": "uses live", "metadata": {"parent_id": "2"}}),
+        );
+        let archive = "proj/archive";
+        write_raw(
+            dir.path(),
+            archive,
+            serde_json::json!({"id": "2", "subject": "old", "blocks": ["3"]}),
+        );
+        write_raw(
+            dir.path(),
+            archive,
+            serde_json::json!({"id": "3", "subject": "child", "blockedBy": ["2"], "metadata": {"parent_id": "2"}}),
+        );
+
+        let restored = store.unarchive_task("proj", "2").unwrap();
+
+        assert_eq!(restored, "5");
+        let child = store.load_archived_task("proj", "3").unwrap();
+        assert_eq!(child.parent_id, "5");
+        assert_eq!(child.blocked_by, vec!["5"]);
+        assert_eq!(store.load_task("proj", "4").unwrap().parent_id, "2");
+    }
+
+    fn write_raw(dir: &Path, list: &str, val: Value) {
+        let id = val["id"].as_str().unwrap().to_string();
+        fs::write(dir.join(list).join(format!("{id}.json")), val.to_string()).unwrap();
+    }
+
+    #[test]
+    fn move_task_brings_subtree_and_rewrites_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::with_base(dir.path().to_path_buf());
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::create_dir_all(dir.path().join("dst")).unwrap();
+        fs::write(dir.path().join("dst").join(".highwatermark"), "10").unwrap();
+        write_raw(
+            dir.path(),
+            "src",
+            serde_json::json!({"id": "1", "subject": "epic", "blocks": ["4"]}),
+        );
+        write_raw(
+            dir.path(),
+            "src",
+            serde_json::json!({"id": "2", "subject": "child", "blocks": ["3"], "metadata": {"parent_id": "1"}}),
+        );
+        write_raw(
+            dir.path(),
+            "src",
+            serde_json::json!({"id": "3", "subject": "grandchild", "blockedBy": ["2"], "metadata": {"parent_id": "2"}}),
+        );
+        write_raw(
+            dir.path(),
+            "src",
+            serde_json::json!({"id": "4", "subject": "stays", "blockedBy": ["1"]}),
+        );
+
+        let pairs = store.move_task("src", "1", "dst", false).unwrap();
+
+        assert_eq!(
+            pairs,
+            vec![
+                ("1".to_string(), "11".to_string()),
+                ("2".to_string(), "12".to_string()),
+                ("3".to_string(), "13".to_string()),
+            ]
+        );
+        let moved = store.list_tasks("dst");
+        assert_eq!(moved.len(), 3);
+        assert!(moved[0].blocks.is_empty());
+        assert_eq!(moved[1].parent_id, "11");
+        assert_eq!(moved[1].blocks, vec!["13"]);
+        assert_eq!(moved[2].parent_id, "12");
+        assert_eq!(moved[2].blocked_by, vec!["12"]);
+        assert_eq!(moved[2].raw["id"], "13");
+
+        let left = store.list_tasks("src");
+        assert_eq!(left.len(), 1);
+        assert!(left[0].blocked_by.is_empty());
+    }
+
+    #[test]
+    fn copy_task_leaves_source_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::with_base(dir.path().to_path_buf());
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::create_dir_all(dir.path().join("dst")).unwrap();
+        write_raw(
+            dir.path(),
+            "src",
+            serde_json::json!({"id": "5", "subject": "a", "metadata": {"parent_id": "9"}}),
+        );
+
+        let pairs = store.move_task("src", "5", "dst", true).unwrap();
+
+        assert_eq!(pairs, vec![("5".to_string(), "1".to_string())]);
+        assert!(store.load_task("src", "5").is_some());
+        let copy = store.load_task("dst", "1").unwrap();
+        assert_eq!(copy.subject, "a");
+        assert!(copy.parent_id.is_empty());
+        assert!(store.move_task("src", "5", "src", false).is_err());
+        assert!(store.move_task("src", "5", "nope", false).is_err());
+    }
+
     #[test]
     fn prune_empty_lists_never_removes_named_dirs() {
         let dir = tempfile::tempdir().unwrap();
@@ -892,7 +1640,7 @@ mod tests {
         let named_id = "my-project";
         fs::create_dir_all(dir.path().join(named_id)).unwrap();
 
-        let removed = store.prune_empty_lists();
+        let removed = store.prune_empty_lists(RETENTION);
 
         assert!(removed.is_empty());
         assert!(dir.path().join(named_id).exists());
@@ -1127,6 +1875,47 @@ mod tests {
         assert_eq!(counts.get("10"), Some(&(0, 1)));
     }
 
+    #[test]
+    fn ready_tasks_requires_completed_blockers() {
+        let tasks = vec![
+            Task::from_raw(serde_json::json!({"id": "1", "status": "completed"})),
+            Task::from_raw(serde_json::json!({"id": "2", "status": "in_progress"})),
+            Task::from_raw(serde_json::json!({"id": "3", "blockedBy": ["1"]})),
+            Task::from_raw(serde_json::json!({"id": "4", "blockedBy": ["1", "2"]})),
+            Task::from_raw(serde_json::json!({"id": "5", "blockedBy": ["99"]})),
+            Task::from_raw(serde_json::json!({"id": "6"})),
+        ];
+        let ids: Vec<String> = ready_tasks(&tasks).into_iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec!["3", "6"]);
+    }
+
+    #[test]
+    fn ready_tasks_orders_by_priority_then_tree_position() {
+        let tasks = vec![
+            Task::from_raw(serde_json::json!({"id": "1", "metadata": {"priority": "P2"}})),
+            Task::from_raw(serde_json::json!({"id": "2", "metadata": {"priority": "P1"}})),
+            Task::from_raw(
+                serde_json::json!({"id": "3", "metadata": {"priority": "P2", "parent_id": "1"}}),
+            ),
+            Task::from_raw(serde_json::json!({"id": "4", "metadata": {"priority": "P2"}})),
+            Task::from_raw(serde_json::json!({"id": "5"})),
+        ];
+        let ids: Vec<String> = ready_tasks(&tasks).into_iter().map(|t| t.id).collect();
+        // P1 first; among P2s the child of 1 sorts before root 4
+        assert_eq!(ids, vec!["2", "1", "3", "4", "5"]);
+    }
+
+    #[test]
+    fn ready_filter_excludes_blocked_tasks() {
+        let tasks = vec![
+            Task::from_raw(serde_json::json!({"id": "1"})),
+            Task::from_raw(serde_json::json!({"id": "2", "blockedBy": ["1"]})),
+        ];
+        let filtered = filter_and_sort(&tasks, StatusFilter::Ready, SortOrder::Id, false, "");
+        let ids: Vec<&str> = filtered.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["1"]);
+    }
+
     #[test]
     fn plan_link_counts_groups_by_plan_file() {
         let tasks = vec![
@@ -0,0 +1,322 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::artifact;
+use crate::frontmatter::{Document, Frontmatter, Value};
+
+/// Template used when `--template` is not given.
+const DEFAULT: &str = "default";
+
+/// `--template` value that skips templates, even a default one.
+const NONE: &str = "none";
+
+/// Values substituted for `{{name}}` placeholders.
+pub struct Vars<'a> {
+    pub topic: &'a str,
+    pub project: &'a str,
+    pub slug: &'a str,
+    pub branch: &'a str,
+    pub date: &'a str,
+    pub spec: &'a str,
+    pub body: &'a str,
+}
+
+impl Vars<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        Some(match key {
+            "topic" => self.topic,
+            "project" => self.project,
+            "slug" => self.slug,
+            "branch" => self.branch,
+            "date" => self.date,
+            "spec" => self.spec,
+            "body" => self.body,
+            _ => return None,
+        })
+    }
+}
+
+/// Template directories for `kind` (`plan` or `spec`), most specific first:
+/// the project's own under `~/.claude/templates/<kind>/<project>/`, then
+/// the shared ones.
+fn dirs(base: &Path, kind: &str, project: &str) -> [PathBuf; 2] {
+    let shared = base.join(".claude").join("templates").join(kind);
+    [shared.join(artifact::project_name(project)), shared]
+}
+
+/// Template names available to a project, sorted.
+pub fn names_in(base: &Path, kind: &str, project: &str) -> Vec<String> {
+    let mut names: Vec<String> = dirs(base, kind, project)
+        .iter()
+        .filter_map(|d| fs::read_dir(d).ok())
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "md"))
+        .filter_map(|p| Some(p.file_stem()?.to_string_lossy().to_string()))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Resolve `name`, or the project's default when `None`. A missing default
+/// (or `none`) means no template; a missing named template is an error.
+pub fn find_in(
+    base: &Path,
+    kind: &str,
+    project: &str,
+    name: Option<&str>,
+) -> Result<Option<PathBuf>, String> {
+    if name == Some(NONE) {
+        return Ok(None);
+    }
+    let file = format!("{}.md", name.unwrap_or(DEFAULT));
+    let found = dirs(base, kind, project)
+        .into_iter()
+        .map(|d| d.join(&file))
+        .find(|p| p.is_file());
+    match (found, name) {
+        (Some(path), _) => Ok(Some(path)),
+        (None, None) => Ok(None),
+        (None, Some(name)) => {
+            let names = names_in(base, kind, project);
+            Err(if names.is_empty() {
+                format!("template not found: {name} (no templates in ~/.claude/templates/{kind}/)")
+            } else {
+                format!(
+                    "template not found: {name} (available: {})",
+                    names.join(", ")
+                )
+            })
+        }
+    }
+}
+
+/// Marks a placeholder while the template's frontmatter is parsed, so a
+/// value like `{{topic}}` is read as a plain scalar, not a flow mapping.
+const MARK: char = '\u{E000}';
+
+/// Replace each known `{{name}}` placeholder with `fill(name)`; unknown ones
+/// are left as written.
+fn replace_placeholders(text: &str, fill: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after
+            .find("}}")
+            .and_then(|end| Some((end, fill(after[..end].trim())?)))
+        {
+            Some((end, value)) => {
+                out.push_str(&value);
+                rest = &after[end + 2..];
+            }
+            None => {
+                out.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Swap `MARK`ed placeholder names for their values.
+fn unmark(text: &str, vars: &Vars) -> String {
+    text.split(MARK)
+        .enumerate()
+        .map(|(i, part)| match i % 2 {
+            1 => vars.get(part).unwrap_or(part),
+            _ => part,
+        })
+        .collect()
+}
+
+/// `unmark` applied to every scalar in a parsed frontmatter value, so
+/// substituted text can never change the YAML's structure.
+fn unmark_value(value: &Value, vars: &Vars) -> Value {
+    match value {
+        Value::Null => Value::Null,
+        Value::Scalar(s) => Value::Scalar(unmark(s, vars)),
+        Value::List(items) => Value::List(items.iter().map(|v| unmark_value(v, vars)).collect()),
+        Value::Map(entries) => Value::Map(
+            entries
+                .iter()
+                .map(|(k, v)| (k.clone(), unmark_value(v, vars)))
+                .collect(),
+        ),
+    }
+}
+
+/// Current branch of the project's repo, empty outside git.
+pub fn branch(project: &str) -> String {
+    Command::new("git")
+        .args(["-C", project, "branch", "--show-current"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_default()
+}
+
+/// Fill in a template. Its frontmatter (tags, status, extra keys) is kept
+/// under the generated fields, with placeholders filled in after parsing;
+/// the body goes at `{{body}}` or, without one, after the template.
+pub fn render(template: &str, mut fm: Frontmatter, vars: &Vars) -> String {
+    let marked = replace_placeholders(template, |key| {
+        vars.get(key).map(|_| format!("{MARK}{key}{MARK}"))
+    });
+    let doc = Document::parse(&marked);
+    if let Some(t) = doc.frontmatter {
+        fm.status = unmark(&t.status, vars);
+        fm.tags = t.tags.iter().map(|s| unmark(s, vars)).collect();
+        fm.links = t.links.iter().map(|s| unmark(s, vars)).collect();
+        fm.extras = t
+            .extras
+            .iter()
+            .map(|(k, v)| (k.clone(), unmark_value(v, vars)))
+            .collect();
+    }
+    let has_body = doc.body.contains(&format!("{MARK}body{MARK}"));
+    let mut body = unmark(&doc.body, vars);
+    if !has_body && !vars.body.is_empty() {
+        if !body.is_empty() && !body.ends_with('\n') {
+            body.push('\n');
+        }
+        body.push_str(vars.body);
+    }
+    if !body.is_empty() && !body.ends_with('\n') {
+        body.push('\n');
+    }
+    Document::new(fm, &body).render()
+}
+
+/// Content of a new plan or spec: the chosen (or default) template filled
+/// in, or just the frontmatter and body when there is none.
+pub fn compose(
+    kind: &str,
+    name: Option<&str>,
+    fm: Frontmatter,
+    slug: &str,
+    body: &str,
+) -> Result<String, String> {
+    let home = std::env::var("HOME").map_err(|_| "cannot determine home directory")?;
+    let template = match find_in(Path::new(&home), kind, &fm.project, name)? {
+        Some(path) => {
+            Some(fs::read_to_string(&path).map_err(|e| format!("{}: {e}", path.display()))?)
+        }
+        None => None,
+    };
+    let Some(template) = template else {
+        let mut buf = Document::new(fm, "").render();
+        if !body.is_empty() {
+            buf.push_str(body);
+            if !body.ends_with('\n') {
+                buf.push('\n');
+            }
+        }
+        return Ok(buf);
+    };
+    let branch = branch(&fm.project);
+    let date = fm.created.get(..10).unwrap_or_default().to_string();
+    let (topic, project, spec) = (fm.title.clone(), fm.project.clone(), fm.spec.clone());
+    let vars = Vars {
+        topic: &topic,
+        project: &project,
+        slug,
+        branch: &branch,
+        date: &date,
+        spec: &spec,
+        body,
+    };
+    Ok(render(&template, fm, &vars))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(body: &str) -> Vars<'_> {
+        Vars {
+            topic: "Auth login",
+            project: "/src/app",
+            slug: "auth-login",
+            branch: "main",
+            date: "2026-03-04",
+            spec: "/specs/auth.md",
+            body,
+        }
+    }
+
+    #[test]
+    fn project_templates_shadow_shared_ones() {
+        let base = tempfile::tempdir().unwrap();
+        let shared = base.path().join(".claude/templates/plan");
+        fs::create_dir_all(shared.join("app")).unwrap();
+        fs::write(shared.join("default.md"), "shared").unwrap();
+        fs::write(shared.join("phased.md"), "phased").unwrap();
+        fs::write(shared.join("app/default.md"), "app").unwrap();
+
+        let find = |project: &str, name: Option<&str>| {
+            find_in(base.path(), "plan", project, name)
+                .map(|p| p.map(|p| fs::read_to_string(p).unwrap()))
+        };
+        assert_eq!(find("/src/app", None), Ok(Some("app".into())));
+        assert_eq!(find("/src/other", None), Ok(Some("shared".into())));
+        assert_eq!(find("/src/app", Some("phased")), Ok(Some("phased".into())));
+        assert_eq!(find("/src/app", Some("none")), Ok(None));
+        assert_eq!(find_in(base.path(), "spec", "/src/app", None), Ok(None));
+
+        let err = find("/src/app", Some("missing")).unwrap_err();
+        assert!(err.contains("available: default, phased"), "{err}");
+    }
+
+    #[test]
+    fn substitutes_placeholders_and_merges_frontmatter() {
+        let v = vars("");
+        let fill = |key: &str| v.get(key).map(str::to_string);
+        assert_eq!(
+            replace_placeholders("{{ topic }} on {{branch}}: {{unknown}} {{", fill),
+            "Auth login on main: {{unknown}} {{"
+        );
+
+        let template = "---\ntags: [plan, \"{{slug}}\"]\nreviewer: ana\n---\n# {{topic}}\n\nSpec: {{spec}}\n\n### Phase 1: Setup\n";
+        let mut fm = Frontmatter::new("Auth login");
+        fm.project = "/src/app".into();
+        fm.created = "2026-03-04T05:06:07Z".into();
+        let out = render(template, fm.clone(), &vars("Extra notes"));
+        let doc = Document::parse(&out);
+        let meta = doc.meta();
+        assert_eq!(meta.title, "Auth login");
+        assert_eq!(meta.tags, vec!["plan", "auth-login"]);
+        assert_eq!(meta.get_str("reviewer"), "ana");
+        assert!(out.starts_with("---\ntopic: Auth login\nproject: /src/app\n"));
+        assert_eq!(
+            doc.body,
+            "# Auth login\n\nSpec: /specs/auth.md\n\n### Phase 1: Setup\nExtra notes\n"
+        );
+
+        // Values with YAML syntax in them survive the frontmatter
+        let template = "---\nsummary: {{topic}}\ntags: [plan, \"{{topic}}\"]\n---\n";
+        let hash = Vars {
+            topic: "Fix #123 crash",
+            ..vars("")
+        };
+        let meta = Document::parse(&render(template, fm.clone(), &hash)).meta();
+        assert_eq!(meta.get_str("summary"), "Fix #123 crash");
+        let quoted = Vars {
+            topic: "Say \"hi\"",
+            ..vars("")
+        };
+        let meta = Document::parse(&render(template, fm.clone(), &quoted)).meta();
+        assert_eq!(meta.tags, vec!["plan", "Say \"hi\""]);
+
+        let out = render("Intro\n{{body}}\nOutro", fm.clone(), &vars("Middle"));
+        assert!(out.ends_with("---\nIntro\nMiddle\nOutro\n"));
+        let out = render("Intro\n{{ body }}\nOutro", fm, &vars("Middle"));
+        assert!(out.ends_with("---\nIntro\nMiddle\nOutro\n"), "{out}");
+    }
+}
@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::artifact;
+use crate::plan::Plan;
+use crate::spec::Spec;
+use crate::store::Task;
+
+/// A plan under its spec, with the tasks created from it.
+#[derive(Debug, Clone)]
+pub struct PlanNode {
+    pub plan: Plan,
+    /// No `spec:` link in the plan; a task names both instead.
+    pub inferred: bool,
+    pub tasks: Vec<Task>,
+}
+
+/// Spec → plans → tasks.
+#[derive(Debug, Clone)]
+pub struct SpecTrace {
+    pub spec: Spec,
+    pub plans: Vec<PlanNode>,
+    /// Tasks linked to the spec but to none of its plans.
+    pub tasks: Vec<Task>,
+}
+
+pub struct Report {
+    pub specs: Vec<SpecTrace>,
+    /// Plans without a spec, or whose link matches no spec.
+    pub unlinked: Vec<Plan>,
+}
+
+/// One line of the rendered tree: box-drawing prefix and what it shows.
+pub enum Row<'a> {
+    Plan(String, &'a PlanNode),
+    Task(String, &'a Task),
+    /// Header for the spec's tasks that no plan covers.
+    Unplanned(String),
+}
+
+/// Whether `link` is a path to `spec`: absolute, `~/`, or relative to
+/// `base`.
+fn is_path_to(link: &str, base: &Path, spec: &Spec) -> bool {
+    let path = match link.strip_prefix("~/") {
+        Some(rest) => match dirs::home_dir() {
+            Some(home) => home.join(rest),
+            None => return false,
+        },
+        None => base.join(link),
+    };
+    path == spec.path
+        || fs::canonicalize(&path).is_ok_and(|p| fs::canonicalize(&spec.path).is_ok_and(|s| s == p))
+}
+
+/// The spec `link` names, for a plan in `project`. A path or a listed name
+/// (`proj/auth`) picks one spec anywhere; a bare file name, with or without
+/// `.md`, only matches specs of the same project and must be unambiguous.
+pub fn find_spec<'a>(
+    link: &str,
+    base: &Path,
+    project: &str,
+    specs: &'a [Spec],
+) -> Result<Option<&'a Spec>, String> {
+    let link = link.trim();
+    if link.is_empty() {
+        return Ok(None);
+    }
+    let name = link.strip_suffix(".md").unwrap_or(link);
+    if let Some(s) = specs
+        .iter()
+        .find(|s| s.name == name || is_path_to(link, base, s))
+    {
+        return Ok(Some(s));
+    }
+    let project = artifact::project_name(project);
+    let by_stem: Vec<&Spec> = specs
+        .iter()
+        .filter(|s| s.path.file_stem().is_some_and(|f| f == name))
+        .filter(|s| artifact::project_name(&s.project) == project)
+        .collect();
+    match by_stem[..] {
+        [] => Ok(None),
+        [s] => Ok(Some(s)),
+        _ => Err(format!(
+            "ambiguous spec {link}: {}",
+            by_stem
+                .iter()
+                .map(|s| s.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// The spec a plan's `spec:` link resolves to among `specs`, if exactly one.
+pub fn linked_spec<'a>(plan: &Plan, specs: &'a [Spec]) -> Option<&'a Spec> {
+    find_spec(&plan.spec, plan_dir(plan), &plan.project, specs)
+        .ok()
+        .flatten()
+}
+
+fn plan_dir(plan: &Plan) -> &Path {
+    plan.path.parent().unwrap_or(Path::new(""))
+}
+
+/// Plan path → path of the spec its `spec:` link resolves to, for plans
+/// with a link. Resolving touches the filesystem, so it is done once.
+fn resolve_links(plans: &[Plan], specs: &[Spec]) -> HashMap<PathBuf, Option<PathBuf>> {
+    plans
+        .iter()
+        .filter(|p| !p.spec.is_empty())
+        .map(|p| {
+            (
+                p.path.clone(),
+                linked_spec(p, specs).map(|s| s.path.clone()),
+            )
+        })
+        .collect()
+}
+
+/// Trace `spec`, one of `specs`: plan links are resolved against all of
+/// them, so a link only counts for the spec it unambiguously names.
+pub fn trace(spec: &Spec, specs: &[Spec], plans: &[Plan], tasks: &[Task]) -> SpecTrace {
+    trace_linked(spec, &resolve_links(plans, specs), plans, tasks)
+}
+
+fn trace_linked(
+    spec: &Spec,
+    links: &HashMap<PathBuf, Option<PathBuf>>,
+    plans: &[Plan],
+    tasks: &[Task],
+) -> SpecTrace {
+    let spec_path = spec.path.to_string_lossy();
+    let plans: Vec<PlanNode> = plans
+        .iter()
+        .filter_map(|p| {
+            let plan_path = p.path.to_string_lossy();
+            let inferred = p.spec.is_empty();
+            let linked = if inferred {
+                tasks
+                    .iter()
+                    .any(|t| t.plan_file == plan_path && t.spec_file == spec_path)
+            } else {
+                links
+                    .get(&p.path)
+                    .is_some_and(|s| s.as_ref() == Some(&spec.path))
+            };
+            linked.then(|| PlanNode {
+                plan: p.clone(),
+                inferred,
+                tasks: tasks
+                    .iter()
+                    .filter(|t| t.plan_file == plan_path)
+                    .cloned()
+                    .collect(),
+            })
+        })
+        .collect();
+    let tasks = tasks
+        .iter()
+        .filter(|t| {
+            t.spec_file == spec_path
+                && !plans
+                    .iter()
+                    .any(|n| n.plan.path.to_string_lossy() == t.plan_file)
+        })
+        .cloned()
+        .collect();
+    SpecTrace {
+        spec: spec.clone(),
+        plans,
+        tasks,
+    }
+}
+
+/// Trace the specs `shown` picks, plus any spec one of `plans` links to.
+/// Links resolve against all of `specs`, so a plan pointing at another
+/// project's spec is not reported as unlinked.
+pub fn report(
+    specs: &[Spec],
+    shown: impl Fn(&Spec) -> bool,
+    plans: &[Plan],
+    tasks: &[Task],
+) -> Report {
+    let links = resolve_links(plans, specs);
+    let traces: Vec<SpecTrace> = specs
+        .iter()
+        .map(|s| trace_linked(s, &links, plans, tasks))
+        .filter(|t| shown(&t.spec) || !t.plans.is_empty())
+        .collect();
+    let unlinked = plans
+        .iter()
+        .filter(|p| {
+            !traces
+                .iter()
+                .any(|t| t.plans.iter().any(|n| n.plan.path == p.path))
+        })
+        .cloned()
+        .collect();
+    Report {
+        specs: traces,
+        unlinked,
+    }
+}
+
+/// Joint for a child and the indent below it.
+fn branch(last: bool) -> (&'static str, &'static str) {
+    if last {
+        ("└─ ", "   ")
+    } else {
+        ("├─ ", "│  ")
+    }
+}
+
+fn task_rows<'a>(rows: &mut Vec<Row<'a>>, tasks: &'a [Task], indent: &str) {
+    for (i, t) in tasks.iter().enumerate() {
+        let (joint, _) = branch(i + 1 == tasks.len());
+        rows.push(Row::Task(format!("{indent}{joint}"), t));
+    }
+}
+
+impl SpecTrace {
+    /// The tree below the spec, one row per line.
+    pub fn rows(&self) -> Vec<Row<'_>> {
+        let mut rows = Vec::new();
+        let groups = self.plans.len() + usize::from(!self.tasks.is_empty());
+        for (i, node) in self.plans.iter().enumerate() {
+            let (joint, indent) = branch(i + 1 == groups);
+            rows.push(Row::Plan(joint.to_string(), node));
+            task_rows(&mut rows, &node.tasks, indent);
+        }
+        if !self.tasks.is_empty() {
+            rows.push(Row::Unplanned("└─ ".to_string()));
+            task_rows(&mut rows, &self.tasks, "   ");
+        }
+        rows
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "name": self.spec.name,
+            "title": self.spec.title,
+            "path": self.spec.path.to_string_lossy(),
+            "status": self.spec.status.as_str(),
+            "plans": self.plans.iter().map(|n| {
+                let mut plan = plan_json(&n.plan);
+                plan["inferred"] = n.inferred.into();
+                plan["tasks"] = tasks_json(&n.tasks);
+                plan
+            }).collect::<Vec<_>>(),
+            "tasks": tasks_json(&self.tasks),
+        })
+    }
+}
+
+impl Report {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "specs": self.specs.iter().map(SpecTrace::to_json).collect::<Vec<_>>(),
+            "unlinked_plans": self.unlinked.iter().map(plan_json).collect::<Vec<_>>(),
+        })
+    }
+}
+
+fn plan_json(p: &Plan) -> serde_json::Value {
+    serde_json::json!({
+        "name": p.name,
+        "title": p.title,
+        "path": p.path.to_string_lossy(),
+        "status": p.status.as_str(),
+        "spec": p.spec,
+    })
+}
+
+fn tasks_json(tasks: &[Task]) -> serde_json::Value {
+    tasks
+        .iter()
+        .map(|t| {
+            serde_json::json!({
+                "id": t.id,
+                "subject": t.subject,
+                "status": t.status.as_str(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifact::Lifecycle;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    fn spec_in(project: &str, name: &str) -> Spec {
+        Spec {
+            name: format!("{project}/{name}"),
+            path: PathBuf::from(format!("/h/specs/{project}/{name}.md")),
+            title: name.to_string(),
+            project: format!("/src/{project}"),
+            status: Lifecycle::Approved,
+            mod_time: SystemTime::UNIX_EPOCH,
+            size: 0,
+        }
+    }
+
+    fn spec(name: &str) -> Spec {
+        spec_in("proj", name)
+    }
+
+    fn plan(name: &str, link: &str) -> Plan {
+        Plan {
+            name: format!("proj/{name}"),
+            path: PathBuf::from(format!("/h/plans/proj/{name}.md")),
+            title: name.to_string(),
+            project: "/src/proj".to_string(),
+            spec: link.to_string(),
+            status: Lifecycle::Draft,
+            mod_time: SystemTime::UNIX_EPOCH,
+            size: 0,
+        }
+    }
+
+    fn task(id: &str, plan_file: &str, spec_file: &str) -> Task {
+        Task::from_raw(serde_json::json!({
+            "id": id,
+            "subject": format!("task {id}"),
+            "metadata": {"plan_file": plan_file, "spec_file": spec_file},
+        }))
+    }
+
+    #[test]
+    fn resolves_links_by_path_name_and_file_within_the_project() {
+        let specs = [spec("auth"), spec_in("other", "auth"), spec("billing")];
+        let base = Path::new("/h/plans/proj");
+        let find = |link: &str, project: &str| {
+            find_spec(link, base, project, &specs).map(|s| s.map(|s| s.name.as_str()))
+        };
+        assert_eq!(
+            find("/h/specs/other/auth.md", "/src/proj"),
+            Ok(Some("other/auth"))
+        );
+        assert_eq!(find("other/auth", "/src/proj"), Ok(Some("other/auth")));
+        assert_eq!(find("auth.md", "/src/proj"), Ok(Some("proj/auth")));
+        assert_eq!(find("auth", "/src/other"), Ok(Some("other/auth")));
+        assert_eq!(find("billing", "/src/other"), Ok(None));
+        assert_eq!(find("", "/src/proj"), Ok(None));
+
+        let nested = Spec {
+            name: "proj/old/auth".into(),
+            path: PathBuf::from("/h/specs/proj/old/auth.md"),
+            ..spec("auth")
+        };
+        let dup = [spec("auth"), nested];
+        let err = find_spec("auth", base, "/src/proj", &dup).unwrap_err();
+        assert_eq!(err, "ambiguous spec auth: proj/auth, proj/old/auth");
+    }
+
+    #[test]
+    fn builds_tree_and_flags_orphans() {
+        let specs = [spec("auth"), spec("billing"), spec_in("other", "auth")];
+        let plans = [
+            plan("rollout", "auth"),
+            plan("sessions", ""),
+            plan("cleanup", ""),
+            plan("stale", "gone"),
+        ];
+        let tasks = [
+            task("1", "/h/plans/proj/rollout.md", ""),
+            task("2", "/h/plans/proj/sessions.md", "/h/specs/proj/auth.md"),
+            task("3", "", "/h/specs/proj/auth.md"),
+            task("4", "/h/plans/proj/cleanup.md", ""),
+        ];
+        let r = report(&specs, |_| true, &plans, &tasks);
+
+        let auth = &r.specs[0];
+        let names: Vec<_> = auth.plans.iter().map(|n| n.plan.title.as_str()).collect();
+        assert_eq!(names, ["rollout", "sessions"]);
+        assert!(!auth.plans[0].inferred && auth.plans[1].inferred);
+        assert_eq!(auth.plans[0].tasks[0].id, "1");
+        assert_eq!(auth.tasks.len(), 1);
+        assert_eq!(auth.tasks[0].id, "3");
+        assert!(r.specs[1].plans.is_empty());
+        // Same file name in another project doesn't pick up the plan
+        assert!(r.specs[2].plans.is_empty());
+
+        let unlinked: Vec<_> = r.unlinked.iter().map(|p| p.title.as_str()).collect();
+        assert_eq!(unlinked, ["cleanup", "stale"]);
+
+        let prefixes: Vec<String> = auth
+            .rows()
+            .iter()
+            .map(|r| match r {
+                Row::Plan(p, _) | Row::Task(p, _) | Row::Unplanned(p) => p.clone(),
+            })
+            .collect();
+        assert_eq!(
+            prefixes,
+            ["├─ ", "│  └─ ", "├─ ", "│  └─ ", "└─ ", "   └─ "]
+        );
+    }
+
+    #[test]
+    fn report_resolves_links_to_specs_it_does_not_show() {
+        let specs = [
+            spec("billing"),
+            spec_in("other", "auth"),
+            spec_in("other", "sso"),
+        ];
+        let plans = [plan("rollout", "other/auth")];
+        let r = report(&specs, |s| s.project == "/src/proj", &plans, &[]);
+
+        let shown: Vec<_> = r.specs.iter().map(|t| t.spec.name.as_str()).collect();
+        assert_eq!(shown, ["proj/billing", "other/auth"]);
+        assert_eq!(r.specs[1].plans[0].plan.title, "rollout");
+        assert!(r.unlinked.is_empty());
+    }
+}
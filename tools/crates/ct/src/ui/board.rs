@@ -0,0 +1,245 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use crate::store::{SortOrder, Status, StatusFilter, Task, filter_and_sort};
+use crate::ui::theme;
+
+/// Lines per card: header (id, priority, type) and subject.
+const CARD_HEIGHT: u16 = 2;
+
+pub struct Column {
+    pub status: Status,
+    pub tasks: Vec<Task>,
+    pub cursor: usize,
+}
+
+pub struct BoardState {
+    pub columns: Vec<Column>,
+    pub col: usize,
+}
+
+impl BoardState {
+    pub fn new(tasks: &[Task]) -> Self {
+        let mut state = Self {
+            columns: Vec::new(),
+            col: 0,
+        };
+        state.set_tasks(tasks);
+        state
+    }
+
+    /// Rebuild columns from `tasks`, keeping the selected card (or at least
+    /// the selected column) where possible.
+    pub fn set_tasks(&mut self, tasks: &[Task]) {
+        let selected = self.selected_id();
+        let status = self.columns.get(self.col).map(|c| c.status.clone());
+        let sorted = filter_and_sort(tasks, StatusFilter::All, SortOrder::Priority, true, "");
+
+        // Fixed columns first, then any custom statuses alphabetically, with
+        // completed last so the board reads left to right
+        let mut statuses = vec![Status::Pending, Status::InProgress];
+        let mut others: Vec<Status> = sorted
+            .iter()
+            .filter(|t| matches!(t.status, Status::Other(_)))
+            .map(|t| t.status.clone())
+            .collect();
+        others.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        others.dedup();
+        statuses.extend(others);
+        statuses.push(Status::Completed);
+
+        self.columns = statuses
+            .into_iter()
+            .map(|status| Column {
+                tasks: sorted
+                    .iter()
+                    .filter(|t| t.status == status)
+                    .cloned()
+                    .collect(),
+                status,
+                cursor: 0,
+            })
+            .collect();
+
+        let found = selected.and_then(|id| {
+            self.columns
+                .iter()
+                .enumerate()
+                .find_map(|(ci, c)| c.tasks.iter().position(|t| t.id == id).map(|ti| (ci, ti)))
+        });
+        if let Some((ci, ti)) = found {
+            self.col = ci;
+            self.columns[ci].cursor = ti;
+        } else {
+            self.col = status
+                .and_then(|s| self.columns.iter().position(|c| c.status == s))
+                .unwrap_or(0);
+        }
+    }
+
+    pub fn selected_task(&self) -> Option<&Task> {
+        let column = self.columns.get(self.col)?;
+        column.tasks.get(column.cursor)
+    }
+
+    pub fn selected_id(&self) -> Option<String> {
+        self.selected_task().map(|t| t.id.clone())
+    }
+
+    pub fn next(&mut self) {
+        if let Some(c) = self.columns.get_mut(self.col)
+            && c.cursor + 1 < c.tasks.len()
+        {
+            c.cursor += 1;
+        }
+    }
+
+    pub fn prev(&mut self) {
+        if let Some(c) = self.columns.get_mut(self.col) {
+            c.cursor = c.cursor.saturating_sub(1);
+        }
+    }
+
+    pub fn home(&mut self) {
+        if let Some(c) = self.columns.get_mut(self.col) {
+            c.cursor = 0;
+        }
+    }
+
+    pub fn end(&mut self) {
+        if let Some(c) = self.columns.get_mut(self.col) {
+            c.cursor = c.tasks.len().saturating_sub(1);
+        }
+    }
+
+    pub fn left(&mut self) {
+        self.col = self.col.saturating_sub(1);
+    }
+
+    pub fn right(&mut self) {
+        if self.col + 1 < self.columns.len() {
+            self.col += 1;
+        }
+    }
+
+    /// Status of the column `delta` steps from the current one, if any.
+    pub fn neighbor_status(&self, delta: isize) -> Option<Status> {
+        let idx = self.col.checked_add_signed(delta)?;
+        self.columns.get(idx).map(|c| c.status.clone())
+    }
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        let end: String = s.chars().take(max.saturating_sub(1)).collect();
+        format!("{end}…")
+    }
+}
+
+pub fn render_board(f: &mut Frame, area: Rect, state: &BoardState) {
+    let constraints = vec![Constraint::Fill(1); state.columns.len()];
+    let areas = Layout::horizontal(constraints).split(area);
+
+    for (ci, (column, col_area)) in state.columns.iter().zip(areas.iter()).enumerate() {
+        let focused = ci == state.col;
+        let border_style = if focused {
+            Style::default().fg(theme::ACCENT)
+        } else {
+            Style::default().fg(theme::OVERLAY)
+        };
+        let title = Line::from(vec![
+            Span::styled(
+                format!(" {} ", column.status.as_str()),
+                theme::status_style(&column.status).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(format!("{} ", column.tasks.len()), theme::muted_style()),
+        ]);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style)
+            .title(title);
+        let inner = block.inner(*col_area);
+        f.render_widget(block, *col_area);
+
+        // Scroll so the cursor card stays visible
+        let capacity = (inner.height / CARD_HEIGHT).max(1) as usize;
+        let offset = (column.cursor + 1).saturating_sub(capacity);
+        let width = inner.width.saturating_sub(1) as usize;
+
+        let mut lines = Vec::new();
+        for (ti, task) in column.tasks.iter().enumerate().skip(offset).take(capacity) {
+            let selected = focused && ti == column.cursor;
+            let mut header = vec![
+                Span::styled(format!("#{} ", task.id), theme::muted_style()),
+                Span::styled(
+                    format!("{} ", task.priority.as_str()),
+                    theme::priority_style(&task.priority),
+                ),
+            ];
+            if !task.task_type.is_empty() {
+                header.push(Span::styled(
+                    task.task_type.clone(),
+                    Style::default().fg(theme::type_color(&task.task_type)),
+                ));
+            }
+            let subject_style = if selected {
+                theme::selected_style()
+            } else {
+                theme::value_style()
+            };
+            lines.push(Line::from(header));
+            lines.push(Line::from(Span::styled(
+                truncate(&task.subject, width),
+                subject_style,
+            )));
+        }
+        if column.tasks.is_empty() {
+            lines.push(Line::from(Span::styled("—", theme::muted_style())));
+        }
+
+        f.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str, status: &str) -> Task {
+        Task::from_raw(serde_json::json!({"id": id, "subject": id, "status": status}))
+    }
+
+    #[test]
+    fn columns_include_custom_statuses_before_completed() {
+        let board = BoardState::new(&[
+            task("1", "pending"),
+            task("2", "review"),
+            task("3", "completed"),
+            task("4", "blocked"),
+        ]);
+        let names: Vec<&str> = board.columns.iter().map(|c| c.status.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["pending", "in_progress", "blocked", "review", "completed"]
+        );
+        assert_eq!(board.columns[3].tasks[0].id, "2");
+    }
+
+    #[test]
+    fn selection_follows_task_across_rebuild() {
+        let mut board = BoardState::new(&[task("1", "pending"), task("2", "pending")]);
+        board.next();
+        assert_eq!(board.selected_id().as_deref(), Some("2"));
+        assert_eq!(board.neighbor_status(1), Some(Status::InProgress));
+        assert_eq!(board.neighbor_status(-1), None);
+
+        board.set_tasks(&[task("1", "pending"), task("2", "in_progress")]);
+        assert_eq!(board.col, 1);
+        assert_eq!(board.selected_id().as_deref(), Some("2"));
+    }
+}
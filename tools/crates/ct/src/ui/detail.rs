@@ -1,3 +1,4 @@
+use crate::history;
 use crate::plan::{self, Plan};
 use crate::spec::{self, Spec};
 use crate::store::{Status, Task, meta_str_raw};
@@ -13,6 +14,7 @@ pub struct DetailState {
     pub children: Vec<Task>,
     pub related_plans: Vec<Plan>,
     pub related_specs: Vec<Spec>,
+    pub history: Vec<history::Entry>,
     pub scroll: u16,
 }
 
@@ -57,6 +59,7 @@ impl DetailState {
             children,
             related_plans,
             related_specs,
+            history: Vec::new(),
             scroll: 0,
         }
     }
@@ -269,6 +272,31 @@ pub fn render_detail(f: &mut Frame, area: Rect, state: &DetailState) {
         }
     }
 
+    if !state.history.is_empty() {
+        lines.push(Line::raw(""));
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled(
+                format!("History ({})", state.history.len()),
+                theme::section_style(),
+            ),
+        ]));
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled("─".repeat(60), Style::default().fg(theme::OVERLAY)),
+        ]));
+        lines.push(Line::raw(""));
+        for entry in state.history.iter().rev().take(MAX_HISTORY_ENTRIES) {
+            lines.extend(history_lines(entry));
+        }
+        if state.history.len() > MAX_HISTORY_ENTRIES {
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled("...".to_string(), theme::muted_style()),
+            ]));
+        }
+    }
+
     lines.push(Line::raw(""));
 
     let paragraph = Paragraph::new(lines)
@@ -310,6 +338,36 @@ pub fn build_text_section(label: &str, content: &str) -> Vec<Line<'static>> {
     out
 }
 
+const MAX_HISTORY_ENTRIES: usize = 10;
+
+/// One summary line per entry (newest first in the caller), followed by the
+/// previous value of any changed description so overwritten text stays visible.
+fn history_lines(entry: &history::Entry) -> Vec<Line<'static>> {
+    let mut out = vec![Line::from(vec![
+        Span::raw("  "),
+        Span::styled(entry.ts.clone(), theme::muted_style()),
+        Span::raw("  "),
+        Span::styled(format!("{:<8}", entry.op.as_str()), theme::label_style()),
+        Span::raw("  "),
+        Span::styled(entry.fields_summary(), theme::value_style()),
+        Span::raw("  "),
+        Span::styled(entry.actor.clone(), theme::muted_style()),
+    ])];
+    for change in &entry.changes {
+        if change.field != "description" || change.old.is_null() {
+            continue;
+        }
+        let old = history::display_value(&change.old);
+        for line in old.lines().take(3) {
+            out.push(Line::from(vec![
+                Span::raw("      "),
+                Span::styled(format!("- {line}"), Style::default().fg(theme::RED)),
+            ]));
+        }
+    }
+    out
+}
+
 fn children_header(children: &[Task]) -> String {
     let total = children.len();
     let completed = children
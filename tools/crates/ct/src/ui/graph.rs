@@ -0,0 +1,121 @@
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+
+use crate::graph;
+use crate::store::{Status, Task};
+use crate::ui::theme;
+
+/// Blocker layers shown above and below the selected task.
+const DEPTH: usize = 3;
+const SUBJECT_WIDTH: usize = 24;
+
+fn node_style(task: &Task) -> Style {
+    let style = theme::status_style(&task.status);
+    if task.status == Status::Completed {
+        style.add_modifier(Modifier::DIM)
+    } else {
+        style
+    }
+}
+
+fn node_label(task: &Task) -> String {
+    let subject: String = if task.subject.chars().count() > SUBJECT_WIDTH {
+        let head: String = task.subject.chars().take(SUBJECT_WIDTH - 1).collect();
+        format!("{head}…")
+    } else {
+        task.subject.clone()
+    };
+    format!("[#{} {subject}]", task.id)
+}
+
+fn layer_line(layer: &[&Task]) -> Line<'static> {
+    let mut spans = vec![Span::raw("  ")];
+    for (i, task) in layer.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw(" "));
+        }
+        spans.push(Span::styled(node_label(task), node_style(task)));
+    }
+    Line::from(spans)
+}
+
+fn arrow() -> Line<'static> {
+    Line::from(Span::styled("    ▼", theme::muted_style()))
+}
+
+/// ASCII DAG of what blocks the selected task and what it blocks, with its
+/// parent and children listed around it.
+pub fn render_graph(f: &mut Frame, area: Rect, tasks: &[Task], selected: Option<&str>) {
+    let block = Block::default()
+        .borders(Borders::LEFT)
+        .border_style(Style::default().fg(theme::OVERLAY))
+        .title(Span::styled(" graph ", theme::section_style()));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let Some(n) = selected.and_then(|id| graph::neighborhood(tasks, id, DEPTH)) else {
+        f.render_widget(
+            Paragraph::new(Line::from(Span::styled(
+                "  No task selected",
+                theme::muted_style(),
+            ))),
+            inner,
+        );
+        return;
+    };
+
+    let mut lines = Vec::new();
+    if let Some(parent) = n.parent {
+        lines.push(Line::from(vec![
+            Span::styled("  parent ", theme::label_style()),
+            Span::styled(node_label(parent), node_style(parent)),
+        ]));
+        lines.push(Line::raw(""));
+    }
+
+    if n.upstream.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  (no blockers)",
+            theme::muted_style(),
+        )));
+    }
+    for layer in n.upstream.iter().rev() {
+        lines.push(layer_line(layer));
+        lines.push(arrow());
+    }
+
+    lines.push(Line::from(vec![
+        Span::styled("▶ ", Style::default().fg(theme::ACCENT)),
+        Span::styled(
+            node_label(n.focus),
+            node_style(n.focus).add_modifier(Modifier::BOLD),
+        ),
+    ]));
+
+    for layer in &n.downstream {
+        lines.push(arrow());
+        lines.push(layer_line(layer));
+    }
+    if n.downstream.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  (blocks nothing)",
+            theme::muted_style(),
+        )));
+    }
+
+    if !n.children.is_empty() {
+        lines.push(Line::raw(""));
+        lines.push(Line::from(Span::styled("  children", theme::label_style())));
+        for child in &n.children {
+            lines.push(Line::from(vec![
+                Span::raw("    "),
+                Span::styled(node_label(child), node_style(child)),
+            ]));
+        }
+    }
+
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+}
@@ -27,6 +27,7 @@ const TASK_SECTIONS: &[Section] = &[
             ("tab / 2", "switch to Plans tab"),
             ("1", "switch to Tasks tab"),
             ("3", "switch to Specs tab"),
+            ("5", "switch to Board tab"),
         ],
     },
     Section {
@@ -37,6 +38,9 @@ const TASK_SECTIONS: &[Section] = &[
             ("e", "edit in $EDITOR"),
             ("n", "new task"),
             ("D", "delete task"),
+            ("X", "archive task (restore in archived view)"),
+            ("m", "move / copy task tree to another list"),
+            ("u / ctrl-r", "undo / redo"),
             ("x", "expand / collapse description"),
         ],
     },
@@ -51,12 +55,16 @@ const TASK_SECTIONS: &[Section] = &[
     Section {
         title: "Filters",
         keys: &[
-            ("f", "cycle status filter"),
+            ("f", "cycle status filter (incl. ready)"),
             ("A", "toggle completed"),
+            ("S", "toggle archived tasks"),
             ("o", "cycle sort order"),
             ("T", "toggle tree view"),
+            ("g", "toggle dependency graph pane"),
             ("F", "clear all filters"),
-            ("/", "search by subject"),
+            ("v", "cycle saved views"),
+            ("V", "save filters as a view"),
+            ("/", "query, e.g. type:bug -owner:bot \"text\""),
         ],
     },
     Section {
@@ -74,6 +82,7 @@ const TASK_SECTIONS: &[Section] = &[
         keys: &[
             ("L", "switch task list"),
             ("R", "reload from disk"),
+            ("ctrl-f", "search tasks, plans and specs"),
             ("?", "toggle this help"),
         ],
     },
@@ -105,6 +114,7 @@ const PLAN_SECTIONS: &[Section] = &[
         title: "Filters",
         keys: &[
             ("A", "cycle: active / archived / git notes"),
+            ("s", "cycle status filter"),
             ("/", "search by title"),
         ],
     },
@@ -113,12 +123,21 @@ const PLAN_SECTIONS: &[Section] = &[
         keys: &[
             ("j/k", "scroll"),
             ("space / b", "page down / up"),
+            ("n / N", "next / previous heading"),
+            ("o", "toggle outline sidebar"),
+            ("r", "toggle raw markdown"),
+            ("d", "diff against newest revision"),
+            ("[ / ]", "diff against older / newer revision"),
             ("esc", "back to list"),
         ],
     },
     Section {
         title: "Other",
-        keys: &[("R", "reload from disk"), ("?", "toggle this help")],
+        keys: &[
+            ("R", "reload from disk"),
+            ("ctrl-f", "search tasks, plans and specs"),
+            ("?", "toggle this help"),
+        ],
     },
 ];
 
@@ -135,10 +154,11 @@ const VIBE_SECTIONS: &[Section] = &[
     Section {
         title: "Tabs",
         keys: &[
-            ("tab / 1", "switch to Tasks tab"),
+            ("1", "switch to Tasks tab"),
             ("2", "switch to Plans tab"),
             ("3", "switch to Specs tab"),
             ("4", "switch to Vibe tab"),
+            ("tab / 5", "switch to Board tab"),
         ],
     },
     Section {
@@ -155,7 +175,55 @@ const VIBE_SECTIONS: &[Section] = &[
     },
     Section {
         title: "Other",
-        keys: &[("?", "toggle this help")],
+        keys: &[
+            ("ctrl-f", "search tasks, plans and specs"),
+            ("?", "toggle this help"),
+        ],
+    },
+];
+
+const BOARD_SECTIONS: &[Section] = &[
+    Section {
+        title: "Navigation",
+        keys: &[
+            ("h/l", "previous / next column"),
+            ("j/k", "previous / next card"),
+            ("g / G", "first / last card"),
+            ("enter", "open task detail"),
+            ("q", "quit"),
+        ],
+    },
+    Section {
+        title: "Tabs",
+        keys: &[
+            ("tab / 1", "switch to Tasks tab"),
+            ("2", "switch to Plans tab"),
+            ("3", "switch to Specs tab"),
+            ("4", "switch to Vibe tab"),
+        ],
+    },
+    Section {
+        title: "Actions",
+        keys: &[
+            ("H / L", "move card to previous / next column"),
+            ("shift-←/→", "move card to previous / next column"),
+            ("s", "change status"),
+            ("p / a / d", "pending / active / done"),
+            ("e", "edit in $EDITOR"),
+            ("n", "new task"),
+            ("D", "delete task"),
+            ("X", "archive task"),
+            ("m", "move / copy task tree to another list"),
+            ("u / ctrl-r", "undo / redo"),
+            ("R", "reload"),
+        ],
+    },
+    Section {
+        title: "Other",
+        keys: &[
+            ("ctrl-f", "search tasks, plans and specs"),
+            ("?", "toggle this help"),
+        ],
     },
 ];
 
@@ -183,19 +251,29 @@ const SPEC_SECTIONS: &[Section] = &[
     },
     Section {
         title: "Filters",
-        keys: &[("A", "cycle: active / archived"), ("/", "search by title")],
+        keys: &[
+            ("A", "cycle: active / archived / git notes"),
+            ("s", "cycle status filter"),
+            ("/", "search by title"),
+        ],
     },
     Section {
         title: "Spec detail",
         keys: &[
             ("j/k", "scroll"),
             ("space / b", "page down / up"),
+            ("n / N", "next / previous heading"),
+            ("o", "toggle outline sidebar"),
+            ("r", "toggle raw markdown"),
             ("esc", "back to list"),
         ],
     },
     Section {
         title: "Other",
-        keys: &[("?", "toggle this help")],
+        keys: &[
+            ("ctrl-f", "search tasks, plans and specs"),
+            ("?", "toggle this help"),
+        ],
     },
 ];
 
@@ -286,8 +364,11 @@ pub fn render_help(
     plans_context: bool,
     specs_context: bool,
     vibe_context: bool,
+    board_context: bool,
 ) -> u16 {
-    if vibe_context {
+    if board_context {
+        render_sections(f, area, BOARD_SECTIONS, scroll)
+    } else if vibe_context {
         render_sections(f, area, VIBE_SECTIONS, scroll)
     } else if specs_context {
         render_sections(f, area, SPEC_SECTIONS, scroll)
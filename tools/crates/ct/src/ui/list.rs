@@ -10,18 +10,47 @@ use crate::store::{
     SortOrder, Status, StatusFilter, Task, child_counts, filter_and_sort, tree_order, tree_prefix,
 };
 use crate::ui::theme;
+use crate::views::View;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TaskSource {
+    Active,
+    Archived,
+}
+
+impl TaskSource {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Active => Self::Archived,
+            Self::Archived => Self::Active,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Archived => "archived",
+        }
+    }
+}
 
 pub struct ListState {
     pub tasks: Vec<Task>,
+    pub archived: Vec<Task>,
+    pub source: TaskSource,
     pub filtered: Vec<Task>,
     pub table_state: TableState,
     pub status_filter: StatusFilter,
     pub sort_order: SortOrder,
     pub show_closed: bool,
     pub tree_view: bool,
+    pub show_graph: bool,
     pub searching: bool,
     pub query: String,
     pub search_input: String,
+    pub views: Vec<View>,
+    /// Name being typed for `V` (save view); `None` when not prompting.
+    pub view_input: Option<String>,
     pub collapsed: HashSet<String>,
     pub expanded_ids: HashSet<String>,
     pub pending_z: bool,
@@ -40,15 +69,20 @@ impl ListState {
         }
         Self {
             tasks,
+            archived: Vec::new(),
+            source: TaskSource::Active,
             filtered,
             table_state,
             status_filter: StatusFilter::All,
             sort_order: SortOrder::Id,
             show_closed: false,
             tree_view: false,
+            show_graph: false,
             searching: false,
             query: String::new(),
             search_input: String::new(),
+            views: Vec::new(),
+            view_input: None,
             collapsed: HashSet::new(),
             expanded_ids: HashSet::new(),
             pending_z: false,
@@ -57,13 +91,51 @@ impl ListState {
         }
     }
 
+    /// Tasks the table is built from: the live list or its archive.
+    pub fn pool(&self) -> &[Task] {
+        match self.source {
+            TaskSource::Active => &self.tasks,
+            TaskSource::Archived => &self.archived,
+        }
+    }
+
+    /// The current filter state as a view called `name`.
+    pub fn to_view(&self, name: &str) -> View {
+        View {
+            name: name.to_string(),
+            status: self.status_filter,
+            sort: self.sort_order,
+            tree: self.tree_view,
+            show_closed: self.show_closed,
+            query: self.query.clone(),
+        }
+    }
+
+    pub fn apply_view(&mut self, view: &View) {
+        self.status_filter = view.status;
+        self.sort_order = view.sort;
+        self.tree_view = view.tree;
+        self.show_closed = view.show_closed;
+        self.query = view.query.clone();
+        self.search_input = view.query.clone();
+        self.rebuild();
+    }
+
+    /// Index of the first saved view matching the current filter state.
+    pub fn active_view(&self) -> Option<usize> {
+        let current = self.to_view("");
+        self.views.iter().position(|v| v.same_filter(&current))
+    }
+
     pub fn rebuild(&mut self) {
-        self.child_count_map = child_counts(&self.tasks);
+        self.child_count_map = child_counts(self.pool());
+        // Archived tasks are nearly all completed, so never hide closed ones there
+        let show_closed = self.show_closed || self.source == TaskSource::Archived;
         self.filtered = filter_and_sort(
-            &self.tasks,
+            self.pool(),
             self.status_filter,
             self.sort_order,
-            self.show_closed,
+            show_closed,
             &self.query,
         );
         // Clamp selection
@@ -116,8 +188,12 @@ impl ListState {
 }
 
 pub fn render_list(f: &mut Frame, area: Rect, state: &mut ListState) {
-    let completed_ids: HashSet<&str> = state
-        .tasks
+    // Borrow the source field directly so `state` stays assignable below
+    let pool: &[Task] = match state.source {
+        TaskSource::Active => &state.tasks,
+        TaskSource::Archived => &state.archived,
+    };
+    let completed_ids: HashSet<&str> = pool
         .iter()
         .filter(|t| t.status == Status::Completed)
         .map(|t| t.id.as_str())
@@ -125,7 +201,7 @@ pub fn render_list(f: &mut Frame, area: Rect, state: &mut ListState) {
 
     let tree_view = state.tree_view;
     let (display_tasks, dim_ids) = if tree_view {
-        build_tree_display(&state.tasks, &state.filtered, &state.collapsed)
+        build_tree_display(pool, &state.filtered, &state.collapsed)
     } else {
         (state.filtered.clone(), HashSet::new())
     };
@@ -389,6 +465,25 @@ fn build_tree_display(
 pub fn render_filter_bar(f: &mut Frame, area: Rect, state: &ListState) {
     let mut spans = Vec::new();
 
+    if let Some(input) = &state.view_input {
+        spans.push(Span::styled(
+            "save view as: ",
+            Style::default().fg(theme::ACCENT),
+        ));
+        spans.push(Span::raw(input.as_str()));
+        spans.push(Span::styled("█", Style::default().fg(theme::ACCENT)));
+        f.render_widget(Line::from(spans), area);
+        return;
+    }
+
+    if let Some(i) = state.active_view() {
+        spans.push(Span::styled(
+            format!(" view:{} ", state.views[i].name),
+            theme::filter_tag_style(),
+        ));
+        spans.push(Span::raw(" "));
+    }
+
     // Status filter
     if state.status_filter != StatusFilter::All {
         spans.push(Span::styled(
@@ -414,6 +509,13 @@ pub fn render_filter_bar(f: &mut Frame, area: Rect, state: &ListState) {
     }
 
     // Show closed
+    if state.source != TaskSource::Active {
+        spans.push(Span::styled(
+            format!(" {} ", state.source.label()),
+            theme::filter_tag_style(),
+        ));
+        spans.push(Span::raw(" "));
+    }
     if state.show_closed {
         spans.push(Span::styled(" +closed ", theme::filter_tag_style()));
         spans.push(Span::raw(" "));
@@ -0,0 +1,1174 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use crate::phases;
+use crate::ui::theme;
+
+/// Left margin of rendered content, matching the detail views.
+const MARGIN: &str = "  ";
+
+/// Narrowest wrap width, so tiny panes still show something readable.
+const MIN_WIDTH: usize = 20;
+
+/// Widest the outline sidebar gets.
+const OUTLINE_WIDTH: u16 = 32;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heading {
+    pub level: usize,
+    pub title: String,
+    /// Line of the heading in the rendered output.
+    pub row: usize,
+}
+
+pub struct Rendered {
+    pub lines: Vec<Line<'static>>,
+    pub headings: Vec<Heading>,
+}
+
+/// Rendered/raw toggle and outline sidebar shared by the plan and spec
+/// detail panes.
+#[derive(Debug, Default)]
+pub struct MarkdownView {
+    pub raw: bool,
+    pub outline: bool,
+    /// Headings from the last render, with rows in the scrolled pane.
+    pub headings: Vec<Heading>,
+}
+
+impl MarkdownView {
+    pub fn toggle_raw(&mut self) {
+        self.raw = !self.raw;
+    }
+
+    pub fn toggle_outline(&mut self) {
+        self.outline = !self.outline;
+    }
+
+    /// The content pane and, when the outline is shown, its sidebar.
+    pub fn layout(&self, area: Rect) -> (Rect, Option<Rect>) {
+        if self.raw || !self.outline {
+            return (area, None);
+        }
+        let side = OUTLINE_WIDTH.min(area.width / 3);
+        let [body, side] =
+            Layout::horizontal([Constraint::Min(0), Constraint::Length(side)]).areas(area);
+        (body, Some(side))
+    }
+
+    /// Lines for `content` in a pane `width` columns wide, below `offset`
+    /// lines of header: rendered markdown without the frontmatter, or the
+    /// file as written in raw mode. Records the headings for navigation.
+    pub fn lines(&mut self, content: &str, width: u16, offset: usize) -> Vec<Line<'static>> {
+        if self.raw {
+            self.headings.clear();
+            return content
+                .lines()
+                .map(|l| Line::raw(format!("{MARGIN}{l}")))
+                .collect();
+        }
+        let r = render(phases::strip_frontmatter(content), width as usize);
+        self.headings = r
+            .headings
+            .into_iter()
+            .map(|h| Heading {
+                row: h.row + offset,
+                ..h
+            })
+            .collect();
+        r.lines
+    }
+
+    /// Heading whose section `scroll` is in.
+    pub fn current(&self, scroll: u16) -> Option<usize> {
+        self.headings.iter().rposition(|h| h.row <= scroll as usize)
+    }
+
+    /// Scroll offset of the first heading below `scroll`.
+    pub fn next(&self, scroll: u16) -> Option<u16> {
+        self.headings
+            .iter()
+            .find(|h| h.row > scroll as usize)
+            .map(|h| h.row as u16)
+    }
+
+    /// Scroll offset of the last heading above `scroll`.
+    pub fn prev(&self, scroll: u16) -> Option<u16> {
+        self.headings
+            .iter()
+            .rev()
+            .find(|h| (h.row as u16) < scroll)
+            .map(|h| h.row as u16)
+    }
+}
+
+// ── Inline ──────────────────────────────────────────────────────────────────
+
+fn code_style() -> Style {
+    Style::default().fg(theme::ORANGE)
+}
+
+fn link_style() -> Style {
+    Style::default()
+        .fg(theme::ACCENT_DIM)
+        .add_modifier(Modifier::UNDERLINED)
+}
+
+/// Closing `delim` in `rest`, for emphasis that wraps non-blank text.
+fn closing(rest: &str, delim: &str) -> Option<usize> {
+    if rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    rest.find(delim).filter(|&end| end > 0)
+}
+
+/// Spans for `code`, **bold**, *italic*, ~~strike~~, [links](url) and
+/// <autolinks> in `text`.
+fn inline(text: &str, base: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    let flush = |plain: &mut String, spans: &mut Vec<Span<'static>>| {
+        if !plain.is_empty() {
+            spans.push(Span::styled(std::mem::take(plain), base));
+        }
+    };
+
+    while let Some(c) = rest.chars().next() {
+        let prev_word = plain.chars().last().is_some_and(char::is_alphanumeric);
+
+        if c == '`'
+            && let Some(end) = rest[1..].find('`')
+        {
+            flush(&mut plain, &mut spans);
+            spans.push(Span::styled(rest[1..1 + end].to_string(), code_style()));
+            rest = &rest[end + 2..];
+            continue;
+        }
+
+        let double = ["**", "__", "~~"].into_iter().find(|d| rest.starts_with(d));
+        if let Some(d) = double
+            && !(d == "__" && prev_word)
+            && let Some(end) = closing(&rest[2..], d)
+        {
+            flush(&mut plain, &mut spans);
+            let style = if d == "~~" {
+                base.add_modifier(Modifier::CROSSED_OUT)
+            } else {
+                base.add_modifier(Modifier::BOLD)
+            };
+            spans.extend(inline(&rest[2..2 + end], style));
+            rest = &rest[end + 4..];
+            continue;
+        }
+
+        if (c == '*' || (c == '_' && !prev_word))
+            && double.is_none()
+            && let Some(end) = closing(&rest[1..], &c.to_string())
+        {
+            flush(&mut plain, &mut spans);
+            spans.extend(inline(
+                &rest[1..1 + end],
+                base.add_modifier(Modifier::ITALIC),
+            ));
+            rest = &rest[end + 2..];
+            continue;
+        }
+
+        if c == '['
+            && let Some(mid) = rest.find("](")
+            && let Some(end) = rest[mid..].find(')').map(|e| mid + e)
+        {
+            flush(&mut plain, &mut spans);
+            let label = &rest[1..mid];
+            let url = rest[mid + 2..end].split_whitespace().next().unwrap_or("");
+            spans.extend(inline(label, link_style()));
+            if !url.is_empty() && url != label {
+                spans.push(Span::styled(format!(" ({url})"), theme::muted_style()));
+            }
+            rest = &rest[end + 1..];
+            continue;
+        }
+
+        if c == '<'
+            && let Some(end) = rest.find('>')
+            && rest[1..end].contains("://")
+        {
+            flush(&mut plain, &mut spans);
+            spans.push(Span::styled(rest[1..end].to_string(), link_style()));
+            rest = &rest[end + 1..];
+            continue;
+        }
+
+        plain.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    flush(&mut plain, &mut spans);
+    spans
+}
+
+fn plain_text(spans: &[Span]) -> String {
+    spans.iter().map(|s| s.content.as_ref()).collect()
+}
+
+fn spans_width(spans: &[Span]) -> usize {
+    spans.iter().map(Span::width).sum()
+}
+
+/// Word-wrap `spans` into lines of at most `width` columns. The first line
+/// starts with `first`, later ones with `rest` (a hanging indent).
+fn wrap(
+    spans: Vec<Span<'static>>,
+    width: usize,
+    first: Vec<Span<'static>>,
+    rest: Vec<Span<'static>>,
+) -> Vec<Line<'static>> {
+    // Words keep their styled pieces; the style of the gap before each word
+    // is kept so underlined links stay continuous
+    let mut words: Vec<(Option<Style>, Vec<Span<'static>>)> = Vec::new();
+    let mut gap: Option<Style> = None;
+    for span in spans {
+        let mut piece = String::new();
+        for ch in span.content.chars() {
+            if ch.is_whitespace() {
+                if !piece.is_empty() {
+                    push_piece(&mut words, &mut gap, std::mem::take(&mut piece), span.style);
+                }
+                gap = Some(span.style);
+            } else {
+                piece.push(ch);
+            }
+        }
+        if !piece.is_empty() {
+            push_piece(&mut words, &mut gap, piece, span.style);
+        }
+    }
+
+    let mut lines = Vec::new();
+    let mut line = first;
+    let mut used = spans_width(&line);
+    let indent = spans_width(&rest);
+    let mut empty = true;
+    for (gap, word) in words {
+        let w = spans_width(&word);
+        let gap_w = usize::from(!empty && gap.is_some());
+        if !empty && used + gap_w + w > width {
+            lines.push(Line::from(std::mem::replace(&mut line, rest.clone())));
+            used = indent;
+            empty = true;
+        }
+        if !empty && let Some(style) = gap {
+            line.push(Span::styled(" ", style));
+            used += 1;
+        }
+        if used + w > width && w > width.saturating_sub(indent) {
+            // Hard-break words longer than a whole line
+            for span in word {
+                for ch in span.content.chars() {
+                    let cw = Span::raw(ch.to_string()).width();
+                    if used + cw > width && !empty {
+                        lines.push(Line::from(std::mem::replace(&mut line, rest.clone())));
+                        used = indent;
+                    }
+                    line.push(Span::styled(ch.to_string(), span.style));
+                    used += cw;
+                    empty = false;
+                }
+            }
+            continue;
+        }
+        line.extend(word);
+        used += w;
+        empty = false;
+    }
+    lines.push(Line::from(line));
+    lines
+}
+
+/// Break `spans` at exactly `width` columns, keeping whitespace as written,
+/// with each row starting with `prefix`. For code, where word-wrapping
+/// would lose indentation.
+fn hard_wrap(
+    spans: Vec<Span<'static>>,
+    width: usize,
+    prefix: Vec<Span<'static>>,
+) -> Vec<Line<'static>> {
+    let indent = spans_width(&prefix);
+    let mut lines = Vec::new();
+    let mut line = prefix.clone();
+    let mut used = indent;
+    for span in spans {
+        let mut piece = String::new();
+        for ch in span.content.chars() {
+            let cw = Span::raw(ch.to_string()).width();
+            if used + cw > width && used > indent {
+                line.push(Span::styled(std::mem::take(&mut piece), span.style));
+                lines.push(Line::from(std::mem::replace(&mut line, prefix.clone())));
+                used = indent;
+            }
+            piece.push(ch);
+            used += cw;
+        }
+        line.push(Span::styled(piece, span.style));
+    }
+    lines.push(Line::from(line));
+    lines
+}
+
+/// `spans` cut to `width` columns, ending in `…` when anything was dropped.
+fn truncate(spans: Vec<Span<'static>>, width: usize) -> Line<'static> {
+    if spans_width(&spans) <= width {
+        return Line::from(spans);
+    }
+    let mut out = Vec::new();
+    let mut used = 0;
+    'spans: for span in spans {
+        let mut piece = String::new();
+        for ch in span.content.chars() {
+            let cw = Span::raw(ch.to_string()).width();
+            if used + cw >= width {
+                out.push(Span::styled(piece, span.style));
+                break 'spans;
+            }
+            piece.push(ch);
+            used += cw;
+        }
+        out.push(Span::styled(piece, span.style));
+    }
+    out.push(Span::styled("…", theme::muted_style()));
+    Line::from(out)
+}
+
+/// `text` word-wrapped to a pane `width` columns wide, after `prefix` (a
+/// label, ID or tree joint) with later rows indented to line up under it.
+/// For the rows the detail panes draw around rendered markdown.
+pub fn hang(prefix: Vec<Span<'_>>, text: Vec<Span<'_>>, width: u16) -> Vec<Line<'static>> {
+    let owned = |spans: Vec<Span<'_>>| -> Vec<Span<'static>> {
+        spans
+            .into_iter()
+            .map(|s| Span::styled(s.content.into_owned(), s.style))
+            .collect()
+    };
+    let prefix = owned(prefix);
+    let indent = vec![Span::raw(" ".repeat(spans_width(&prefix)))];
+    wrap(owned(text), (width as usize).max(MIN_WIDTH), prefix, indent)
+}
+
+fn push_piece(
+    words: &mut Vec<(Option<Style>, Vec<Span<'static>>)>,
+    gap: &mut Option<Style>,
+    text: String,
+    style: Style,
+) {
+    let span = Span::styled(text, style);
+    match (gap.take(), words.last_mut()) {
+        (None, Some((_, word))) => word.push(span),
+        (g, _) => words.push((g, vec![span])),
+    }
+}
+
+// ── Code ────────────────────────────────────────────────────────────────────
+
+struct Lang {
+    comments: &'static [&'static str],
+    keywords: &'static [&'static str],
+    single_quoted: bool,
+    /// Separator after a mapping key (`:` or `=`), styling the key.
+    key_sep: Option<char>,
+}
+
+fn lang(name: &str) -> Option<Lang> {
+    let (comments, keywords, single_quoted, key_sep): (&[&str], &[&str], bool, Option<char>) =
+        match name.to_ascii_lowercase().as_str() {
+            "rust" | "rs" => (
+                &["//"],
+                &[
+                    "as", "async", "await", "break", "const", "continue", "crate", "else", "enum",
+                    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod",
+                    "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct",
+                    "super", "trait", "true", "type", "unsafe", "use", "where", "while",
+                ],
+                false,
+                None,
+            ),
+            "go" => (
+                &["//"],
+                &[
+                    "break",
+                    "case",
+                    "chan",
+                    "const",
+                    "continue",
+                    "default",
+                    "defer",
+                    "else",
+                    "false",
+                    "for",
+                    "func",
+                    "go",
+                    "if",
+                    "import",
+                    "interface",
+                    "map",
+                    "nil",
+                    "package",
+                    "range",
+                    "return",
+                    "select",
+                    "struct",
+                    "switch",
+                    "true",
+                    "type",
+                    "var",
+                ],
+                false,
+                None,
+            ),
+            "python" | "py" => (
+                &["#"],
+                &[
+                    "and", "as", "async", "await", "class", "def", "elif", "else", "except",
+                    "False", "finally", "for", "from", "if", "import", "in", "is", "lambda",
+                    "None", "not", "or", "pass", "raise", "return", "True", "try", "while", "with",
+                    "yield",
+                ],
+                true,
+                None,
+            ),
+            "js" | "javascript" | "jsx" | "ts" | "typescript" | "tsx" => (
+                &["//"],
+                &[
+                    "async",
+                    "await",
+                    "break",
+                    "case",
+                    "class",
+                    "const",
+                    "continue",
+                    "default",
+                    "else",
+                    "export",
+                    "extends",
+                    "false",
+                    "for",
+                    "from",
+                    "function",
+                    "if",
+                    "import",
+                    "in",
+                    "interface",
+                    "let",
+                    "new",
+                    "null",
+                    "of",
+                    "return",
+                    "switch",
+                    "this",
+                    "throw",
+                    "true",
+                    "try",
+                    "type",
+                    "undefined",
+                    "var",
+                    "while",
+                ],
+                true,
+                None,
+            ),
+            "sh" | "bash" | "shell" | "zsh" | "console" => (
+                &["#"],
+                &[
+                    "case", "do", "done", "elif", "else", "esac", "export", "fi", "for",
+                    "function", "if", "in", "local", "then", "while",
+                ],
+                true,
+                None,
+            ),
+            "sql" => (
+                &["--"],
+                &[
+                    "and", "as", "by", "create", "delete", "from", "group", "insert", "into",
+                    "join", "not", "null", "on", "or", "order", "select", "set", "table", "update",
+                    "values", "where", "AND", "AS", "BY", "CREATE", "DELETE", "FROM", "GROUP",
+                    "INSERT", "INTO", "JOIN", "NOT", "NULL", "ON", "OR", "ORDER", "SELECT", "SET",
+                    "TABLE", "UPDATE", "VALUES", "WHERE",
+                ],
+                true,
+                None,
+            ),
+            "json" | "jsonc" => (&["//"], &["true", "false", "null"], false, Some(':')),
+            "yaml" | "yml" => (&["#"], &["true", "false", "null"], true, Some(':')),
+            "toml" => (&["#"], &["true", "false"], true, Some('=')),
+            _ => return None,
+        };
+    Some(Lang {
+        comments,
+        keywords,
+        single_quoted,
+        key_sep,
+    })
+}
+
+/// Token-level highlighting of one line of code: comments, strings,
+/// numbers, keywords, calls, type names and mapping keys.
+fn highlight(line: &str, lang: Option<&Lang>) -> Vec<Span<'static>> {
+    let text = Style::default().fg(theme::TEXT);
+    let Some(lang) = lang else {
+        return vec![Span::styled(line.to_string(), text)];
+    };
+    let key = Style::default().fg(theme::ACCENT);
+    let is_key = |after: &str| {
+        lang.key_sep
+            .is_some_and(|sep| after.trim_start().starts_with(sep))
+    };
+
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut push = |s: &str, style: Style| match spans.last_mut() {
+        Some(last) if last.style == style => last.content.to_mut().push_str(s),
+        _ => spans.push(Span::styled(s.to_string(), style)),
+    };
+    let mut i = 0;
+    while i < line.len() {
+        let rest = &line[i..];
+        let c = rest.chars().next().unwrap_or(' ');
+        let prev_ident = line[..i]
+            .chars()
+            .last()
+            .is_some_and(|p| p.is_alphanumeric() || p == '_');
+
+        if lang
+            .comments
+            .iter()
+            .any(|m| rest.starts_with(m) && (*m != "#" || !prev_ident))
+        {
+            push(rest, theme::muted_style().add_modifier(Modifier::ITALIC));
+            break;
+        }
+        if c == '"' || (c == '\'' && lang.single_quoted) {
+            let mut end = rest.len();
+            let mut escaped = false;
+            for (j, ch) in rest.char_indices().skip(1) {
+                if ch == c && !escaped {
+                    end = j + 1;
+                    break;
+                }
+                escaped = ch == '\\' && !escaped;
+            }
+            let style = if is_key(&rest[end..]) {
+                key
+            } else {
+                Style::default().fg(theme::GREEN)
+            };
+            push(&rest[..end], style);
+            i += end;
+            continue;
+        }
+        if c.is_ascii_digit() && !prev_ident {
+            let end = rest
+                .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_' || ch == '.'))
+                .unwrap_or(rest.len());
+            push(&rest[..end], Style::default().fg(theme::ORANGE));
+            i += end;
+            continue;
+        }
+        if c.is_alphabetic() || c == '_' {
+            let end = rest
+                .find(|ch: char| {
+                    !(ch.is_alphanumeric() || ch == '_' || ch == '-' && lang.key_sep.is_some())
+                })
+                .unwrap_or(rest.len());
+            let word = &rest[..end];
+            let style = if is_key(&rest[end..]) {
+                key
+            } else if lang.keywords.contains(&word) {
+                Style::default().fg(theme::LAVENDER)
+            } else if rest[end..].starts_with('(') || rest[end..].starts_with('!') {
+                Style::default().fg(theme::ACCENT)
+            } else if word.starts_with(char::is_uppercase) {
+                Style::default().fg(theme::YELLOW)
+            } else {
+                text
+            };
+            push(word, style);
+            i += end;
+            continue;
+        }
+        push(&rest[..c.len_utf8()], text);
+        i += c.len_utf8();
+    }
+    spans
+}
+
+// ── Blocks ──────────────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy, PartialEq)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+enum Marker {
+    Bullet,
+    Number(String),
+}
+
+struct Item<'a> {
+    depth: usize,
+    marker: Marker,
+    check: Option<bool>,
+    text: &'a str,
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.len() - line.trim_start_matches('#').len();
+    let rest = &line[level..];
+    ((1..=6).contains(&level) && (rest.is_empty() || rest.starts_with(' ')))
+        .then(|| (level, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|&m| compact.chars().all(|c| c == m))
+}
+
+fn fence(line: &str) -> Option<&'static str> {
+    ["```", "~~~"].into_iter().find(|f| line.starts_with(f))
+}
+
+fn cells(row: &str) -> Vec<&str> {
+    let row = row.trim();
+    let row = row.strip_prefix('|').unwrap_or(row);
+    let row = row.strip_suffix('|').unwrap_or(row);
+    row.split('|').map(str::trim).collect()
+}
+
+/// Column alignments of a `|---|:--:|` separator row.
+fn table_aligns(line: &str) -> Option<Vec<Align>> {
+    let trimmed = line.trim();
+    if !trimmed.starts_with('|') && !trimmed.contains('|') {
+        return None;
+    }
+    cells(trimmed)
+        .into_iter()
+        .map(|c| {
+            let dashes = c.trim_matches(':');
+            (!dashes.is_empty() && dashes.chars().all(|ch| ch == '-')).then(|| {
+                match (c.starts_with(':'), c.ends_with(':')) {
+                    (true, true) => Align::Center,
+                    (false, true) => Align::Right,
+                    _ => Align::Left,
+                }
+            })
+        })
+        .collect()
+}
+
+fn list_item(line: &str) -> Option<Item<'_>> {
+    let body = line.trim_start();
+    let indent: usize = line[..line.len() - body.len()]
+        .chars()
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum();
+    let (marker, rest) =
+        if let Some(rest) = ["- ", "* ", "+ "].iter().find_map(|m| body.strip_prefix(m)) {
+            (Marker::Bullet, rest)
+        } else {
+            let digits = body.len() - body.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            let after = &body[digits..];
+            let rest = after
+                .strip_prefix(". ")
+                .or_else(|| after.strip_prefix(") "))
+                .filter(|_| digits > 0)?;
+            (Marker::Number(body[..digits].to_string()), rest)
+        };
+    let (check, text) = match rest.get(..4) {
+        Some("[ ] ") => (Some(false), &rest[4..]),
+        Some("[x] ") | Some("[X] ") => (Some(true), &rest[4..]),
+        _ => (None, rest),
+    };
+    Some(Item {
+        depth: indent / 2,
+        marker,
+        check,
+        text: text.trim(),
+    })
+}
+
+struct Renderer {
+    width: usize,
+    lines: Vec<Line<'static>>,
+    headings: Vec<Heading>,
+}
+
+impl Renderer {
+    fn blank(&mut self) {
+        if self.lines.last().is_some_and(|l| l.width() > 0) {
+            self.lines.push(Line::raw(""));
+        }
+    }
+
+    fn wrapped(
+        &mut self,
+        spans: Vec<Span<'static>>,
+        first: Vec<Span<'static>>,
+        rest: Vec<Span<'static>>,
+    ) {
+        let lines = wrap(spans, self.width, first, rest);
+        self.lines.extend(lines);
+    }
+
+    fn margin() -> Vec<Span<'static>> {
+        vec![Span::raw(MARGIN)]
+    }
+
+    fn paragraph(&mut self, para: &mut Vec<&str>) {
+        if para.is_empty() {
+            return;
+        }
+        let text = para.join(" ");
+        para.clear();
+        self.wrapped(
+            inline(&text, theme::value_style()),
+            Self::margin(),
+            Self::margin(),
+        );
+    }
+
+    fn heading(&mut self, level: usize, title: &str) {
+        self.blank();
+        let style = match level {
+            1 => Style::default()
+                .fg(theme::LAVENDER)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            2 => theme::section_style(),
+            3 => Style::default()
+                .fg(theme::ACCENT)
+                .add_modifier(Modifier::BOLD),
+            _ => Style::default()
+                .fg(theme::ACCENT_DIM)
+                .add_modifier(Modifier::BOLD),
+        };
+        let spans = inline(title, style);
+        self.headings.push(Heading {
+            level,
+            title: plain_text(&spans),
+            row: self.lines.len(),
+        });
+        self.wrapped(spans, Self::margin(), Self::margin());
+    }
+
+    fn rule(&mut self) {
+        self.lines.push(Line::from(vec![
+            Span::raw(MARGIN),
+            Span::styled(
+                "─".repeat(self.width.saturating_sub(MARGIN.len())),
+                Style::default().fg(theme::OVERLAY),
+            ),
+        ]));
+    }
+
+    fn quote(&mut self, text: &str) {
+        let bar = || {
+            vec![
+                Span::raw(MARGIN),
+                Span::styled("▎ ", Style::default().fg(theme::OVERLAY)),
+            ]
+        };
+        let style = Style::default()
+            .fg(theme::SUBTEXT)
+            .add_modifier(Modifier::ITALIC);
+        self.wrapped(inline(text, style), bar(), bar());
+    }
+
+    fn item(&mut self, item: Item) {
+        let pad = format!("{MARGIN}{}", "  ".repeat(item.depth));
+        let accent = Style::default().fg(theme::ACCENT);
+        let mut marker = vec![Span::raw(pad.clone())];
+        match (&item.marker, item.check) {
+            (Marker::Bullet, Some(_)) => {}
+            (Marker::Bullet, None) => {
+                let bullet = if item.depth == 0 { "• " } else { "◦ " };
+                marker.push(Span::styled(bullet, accent));
+            }
+            (Marker::Number(n), _) => marker.push(Span::styled(format!("{n}. "), accent)),
+        }
+        let text_style = match item.check {
+            Some(true) => {
+                marker.push(Span::styled("☑ ", Style::default().fg(theme::GREEN)));
+                theme::muted_style()
+            }
+            Some(false) => {
+                marker.push(Span::styled("☐ ", Style::default().fg(theme::SUBTEXT)));
+                theme::value_style()
+            }
+            None => theme::value_style(),
+        };
+        let hang = vec![Span::raw(" ".repeat(spans_width(&marker)))];
+        self.wrapped(inline(item.text, text_style), marker, hang);
+    }
+
+    fn code(&mut self, lang_name: &str, body: &[&str]) {
+        let border = Style::default().fg(theme::OVERLAY);
+        let syntax = lang(lang_name);
+        let mut top = vec![Span::raw(MARGIN), Span::styled("╭─", border)];
+        if !lang_name.is_empty() {
+            top.push(Span::styled(format!(" {lang_name}"), theme::muted_style()));
+        }
+        self.lines.push(Line::from(top));
+        for l in body {
+            let bar = vec![Span::raw(MARGIN), Span::styled("│ ", border)];
+            let lines = hard_wrap(highlight(l, syntax.as_ref()), self.width, bar);
+            self.lines.extend(lines);
+        }
+        self.lines.push(Line::from(vec![
+            Span::raw(MARGIN),
+            Span::styled("╰─", border),
+        ]));
+    }
+
+    fn table(&mut self, header: &str, aligns: &[Align], rows: &[&str]) {
+        let head = Style::default()
+            .fg(theme::TEXT)
+            .add_modifier(Modifier::BOLD);
+        let parse = |row: &str, style: Style| -> Vec<Vec<Span<'static>>> {
+            cells(row).into_iter().map(|c| inline(c, style)).collect()
+        };
+        let mut grid = vec![parse(header, head)];
+        grid.extend(rows.iter().map(|r| parse(r, theme::value_style())));
+
+        let cols = grid.iter().map(Vec::len).max().unwrap_or(0);
+        let widths: Vec<usize> = (0..cols)
+            .map(|c| {
+                grid.iter()
+                    .filter_map(|r| r.get(c))
+                    .map(|cell| spans_width(cell))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let border = Style::default().fg(theme::OVERLAY);
+
+        for (r, row) in grid.into_iter().enumerate() {
+            let mut spans = vec![Span::raw(MARGIN)];
+            for (c, &w) in widths.iter().enumerate() {
+                if c > 0 {
+                    spans.push(Span::styled(" │ ", border));
+                }
+                let cell = row.get(c).cloned().unwrap_or_default();
+                let gap = w - spans_width(&cell);
+                let (left, right) = match aligns.get(c).copied().unwrap_or(Align::Left) {
+                    Align::Left => (0, gap),
+                    Align::Right => (gap, 0),
+                    Align::Center => (gap / 2, gap - gap / 2),
+                };
+                spans.push(Span::raw(" ".repeat(left)));
+                spans.extend(cell);
+                spans.push(Span::raw(" ".repeat(right)));
+            }
+            self.lines.push(truncate(spans, self.width));
+            if r == 0 {
+                let rule: Vec<String> = widths.iter().map(|&w| "─".repeat(w)).collect();
+                let rule = vec![Span::raw(MARGIN), Span::styled(rule.join("─┼─"), border)];
+                self.lines.push(truncate(rule, self.width));
+            }
+        }
+    }
+}
+
+/// Render markdown into styled lines wrapped to `width` columns, with the
+/// headings (and bold `**Phase N: ...**` markers) for the outline.
+pub fn render(markdown: &str, width: usize) -> Rendered {
+    let mut r = Renderer {
+        width: width.max(MIN_WIDTH),
+        lines: Vec::new(),
+        headings: Vec::new(),
+    };
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut para: Vec<&str> = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+        i += 1;
+
+        if let Some(f) = fence(trimmed) {
+            r.paragraph(&mut para);
+            let end = lines[i..]
+                .iter()
+                .position(|l| l.trim().starts_with(f))
+                .map_or(lines.len(), |n| i + n);
+            r.code(trimmed[f.len()..].trim(), &lines[i..end]);
+            i = end + 1;
+            continue;
+        }
+        if trimmed.is_empty() {
+            r.paragraph(&mut para);
+            r.blank();
+            continue;
+        }
+        if let Some((level, title)) = heading(trimmed) {
+            r.paragraph(&mut para);
+            r.heading(level, title);
+            continue;
+        }
+        if is_rule(trimmed) {
+            r.paragraph(&mut para);
+            r.rule();
+            continue;
+        }
+        if trimmed.contains('|')
+            && let Some(aligns) = lines.get(i).and_then(|l| table_aligns(l))
+        {
+            r.paragraph(&mut para);
+            let end = lines[i + 1..]
+                .iter()
+                .position(|l| !l.contains('|') || l.trim().is_empty())
+                .map_or(lines.len(), |n| i + 1 + n);
+            r.table(trimmed, &aligns, &lines[i + 1..end]);
+            i = end;
+            continue;
+        }
+        if let Some(q) = trimmed.strip_prefix('>') {
+            r.paragraph(&mut para);
+            r.quote(q.trim());
+            continue;
+        }
+        if let Some(item) = list_item(line) {
+            r.paragraph(&mut para);
+            r.item(item);
+            continue;
+        }
+        if phases::phase_header(trimmed).is_some() {
+            r.paragraph(&mut para);
+            r.heading(3, trimmed);
+            continue;
+        }
+        para.push(trimmed);
+    }
+    r.paragraph(&mut para);
+
+    Rendered {
+        lines: r.lines,
+        headings: r.headings,
+    }
+}
+
+/// Sidebar listing the headings, with the section at the top of the pane
+/// marked.
+pub fn render_outline(f: &mut Frame, area: Rect, view: &MarkdownView, scroll: u16) {
+    let block = Block::default()
+        .borders(Borders::LEFT)
+        .border_style(Style::default().fg(theme::OVERLAY))
+        .title(Span::styled(" outline ", theme::section_style()));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let top = view.headings.iter().map(|h| h.level).min().unwrap_or(1);
+    let current = view.current(scroll);
+    let width = inner.width as usize;
+    let lines: Vec<Line> = view
+        .headings
+        .iter()
+        .enumerate()
+        .map(|(i, h)| {
+            let selected = current == Some(i);
+            let indent = "  ".repeat(h.level - top);
+            let style = if selected {
+                theme::selected_style()
+            } else if h.level == top {
+                theme::value_style()
+            } else {
+                theme::muted_style()
+            };
+            let marker = if selected { "▸ " } else { "  " };
+            let text: String = format!("{marker}{indent}{}", h.title)
+                .chars()
+                .take(width)
+                .collect();
+            Line::from(Span::styled(text, style))
+        })
+        .collect();
+
+    let height = inner.height as usize;
+    let offset = current.map_or(0, |c| (c + 1).saturating_sub(height));
+    f.render_widget(Paragraph::new(lines).scroll((offset as u16, 0)), inner);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    fn texts(r: &Rendered) -> Vec<String> {
+        r.lines.iter().map(text).collect()
+    }
+
+    #[test]
+    fn blocks_render_with_outline_rows() {
+        let md = "\
+# Plan
+
+Intro with **bold**, `code` and a [link](docs/a.md).
+
+## Phase list
+
+**Phase 1: Setup**
+- [ ] install
+- [x] configure
+  - nested
+1. first
+
+> quoted
+
+| Name | Count |
+|:-----|------:|
+| a    | 10    |
+| bb   | 2     |
+
+---
+```rust
+let x = 1; // note
+```
+";
+        let r = render(md, 80);
+        let out = texts(&r);
+        assert_eq!(out[0], "  Plan");
+        assert_eq!(out[2], "  Intro with bold, code and a link (docs/a.md).");
+        let titles: Vec<_> = r
+            .headings
+            .iter()
+            .map(|h| (h.level, h.title.as_str(), out[h.row].trim().to_string()))
+            .collect();
+        assert_eq!(
+            titles,
+            [
+                (1, "Plan", "Plan".to_string()),
+                (2, "Phase list", "Phase list".to_string()),
+                (3, "Phase 1: Setup", "Phase 1: Setup".to_string()),
+            ]
+        );
+        assert!(out.contains(&"  ☐ install".to_string()));
+        assert!(out.contains(&"  ☑ configure".to_string()));
+        assert!(out.contains(&"    ◦ nested".to_string()));
+        assert!(out.contains(&"  1. first".to_string()));
+        assert!(out.contains(&"  ▎ quoted".to_string()));
+        assert!(out.contains(&"  Name │ Count".to_string()));
+        assert!(out.contains(&"  ─────┼──────".to_string()));
+        assert!(out.contains(&"  bb   │     2".to_string()));
+        assert!(out.contains(&"  ╭─ rust".to_string()));
+        assert!(out.contains(&"  │ let x = 1; // note".to_string()));
+    }
+
+    #[test]
+    fn inline_styles_and_wrapping() {
+        let spans = inline("a **b _c_** ~~d~~ snake_case_name", Style::default());
+        let styled: Vec<_> = spans
+            .iter()
+            .map(|s| (s.content.as_ref(), s.style.add_modifier))
+            .collect();
+        assert_eq!(
+            styled,
+            [
+                ("a ", Modifier::empty()),
+                ("b ", Modifier::BOLD),
+                ("c", Modifier::BOLD | Modifier::ITALIC),
+                (" ", Modifier::empty()),
+                ("d", Modifier::CROSSED_OUT),
+                (" snake_case_name", Modifier::empty()),
+            ]
+        );
+
+        let r = render(
+            "- a list item whose text is long enough to wrap onto a second line",
+            30,
+        );
+        assert_eq!(
+            texts(&r),
+            [
+                "  • a list item whose text is",
+                "    long enough to wrap onto a",
+                "    second line",
+            ]
+        );
+    }
+
+    #[test]
+    fn code_and_tables_fit_the_width() {
+        let md = "```\nlet total = first_value + second_value;\n```\n\n| Name | Description |\n|---|---|\n| a | something rather long |\n";
+        let r = render(md, 24);
+        assert_eq!(
+            texts(&r),
+            [
+                "  ╭─",
+                "  │ let total = first_va",
+                "  │ lue + second_value;",
+                "  ╰─",
+                "",
+                "  Name │ Description   …",
+                "  ─────┼───────────────…",
+                "  a    │ something rath…",
+            ]
+        );
+        assert!(r.lines.iter().all(|l| l.width() <= 24));
+
+        let lines = hang(
+            vec![Span::raw("  #1     ")],
+            vec![Span::raw("a subject long enough to wrap")],
+            24,
+        );
+        let out: Vec<String> = lines.iter().map(text).collect();
+        assert_eq!(out, ["  #1     a subject long", "         enough to wrap"]);
+    }
+
+    #[test]
+    fn code_is_highlighted_by_language() {
+        let rust = lang("rust");
+        let spans = highlight("fn main() { let s = \"hi\"; } // done", rust.as_ref());
+        let color = |t: &str| {
+            spans
+                .iter()
+                .find(|s| s.content.trim() == t)
+                .and_then(|s| s.style.fg)
+        };
+        assert_eq!(color("fn"), Some(theme::LAVENDER));
+        assert_eq!(color("main"), Some(theme::ACCENT));
+        assert_eq!(color("\"hi\""), Some(theme::GREEN));
+        assert_eq!(color("// done"), Some(theme::MUTED));
+
+        let yaml = lang("yaml");
+        let spans = highlight("retries: 3", yaml.as_ref());
+        assert_eq!(spans[0].style.fg, Some(theme::ACCENT));
+        assert!(highlight("plain", None).len() == 1);
+    }
+
+    #[test]
+    fn outline_navigation() {
+        let view = MarkdownView {
+            headings: [2, 10, 25]
+                .into_iter()
+                .map(|row| Heading {
+                    level: 1,
+                    title: String::new(),
+                    row,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        assert_eq!(view.current(0), None);
+        assert_eq!(view.current(12), Some(1));
+        assert_eq!(view.next(10), Some(25));
+        assert_eq!(view.prev(10), Some(2));
+        assert_eq!(view.next(25), None);
+    }
+}
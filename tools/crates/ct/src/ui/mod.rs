@@ -1,10 +1,15 @@
+pub mod board;
 pub mod confirm;
 pub mod create;
 pub mod detail;
+pub mod graph;
 pub mod help;
 pub mod list;
+pub mod markdown;
+pub mod move_picker;
 pub mod plan_detail;
 pub mod plans;
+pub mod search;
 pub mod spec_detail;
 pub mod specs;
 pub mod status;
@@ -0,0 +1,70 @@
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+
+use crate::ui::theme;
+
+pub struct MovePickerState {
+    pub task_id: String,
+    pub options: Vec<String>,
+    pub cursor: usize,
+    pub copy: bool,
+}
+
+impl MovePickerState {
+    pub fn new(task_id: String, options: Vec<String>) -> Self {
+        Self {
+            task_id,
+            options,
+            cursor: 0,
+            copy: false,
+        }
+    }
+
+    pub fn next(&mut self) {
+        if self.cursor + 1 < self.options.len() {
+            self.cursor += 1;
+        }
+    }
+
+    pub fn prev(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn selected(&self) -> Option<&str> {
+        self.options.get(self.cursor).map(String::as_str)
+    }
+}
+
+pub fn render_move_picker(f: &mut Frame, area: Rect, state: &MovePickerState) {
+    let verb = if state.copy { "Copy" } else { "Move" };
+    let mut lines = vec![
+        Line::raw(""),
+        Line::from(vec![
+            Span::raw("  "),
+            Span::styled(
+                format!("{verb} #{} and its subtasks to", state.task_id),
+                theme::section_style(),
+            ),
+        ]),
+        Line::raw(""),
+    ];
+
+    for (i, list_id) in state.options.iter().enumerate() {
+        let style = if i == state.cursor {
+            theme::selected_style()
+        } else {
+            theme::value_style()
+        };
+        let indicator = if i == state.cursor { "▸ " } else { "  " };
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled(format!("{indicator}{list_id}"), style),
+        ]));
+    }
+
+    lines.push(Line::raw(""));
+
+    f.render_widget(Paragraph::new(lines), area);
+}
@@ -6,7 +6,9 @@ use ratatui::widgets::{Paragraph, Wrap};
 
 use crate::plan::{self, Plan};
 use crate::planfile;
+use crate::revisions::{self, DiffLine, Hunk, Revision};
 use crate::store::Task;
+use crate::ui::markdown::{self, MarkdownView};
 use crate::ui::theme;
 
 pub struct PlanDetailState {
@@ -14,6 +16,12 @@ pub struct PlanDetailState {
     pub content: String,
     pub linked_tasks: Vec<Task>,
     pub scroll: u16,
+    pub revisions: Vec<Revision>,
+    /// Index into `revisions` being diffed against the current content;
+    /// `None` shows the plan itself.
+    pub diff_rev: Option<usize>,
+    pub diff: Vec<Hunk>,
+    pub view: MarkdownView,
 }
 
 impl PlanDetailState {
@@ -25,14 +33,51 @@ impl PlanDetailState {
             .filter(|t| !t.plan_file.is_empty() && t.plan_file == plan_path)
             .cloned()
             .collect();
+        let revisions = revisions::list(&plan.path);
         Self {
             plan,
             content,
             linked_tasks,
             scroll: 0,
+            revisions,
+            diff_rev: None,
+            diff: Vec::new(),
+            view: MarkdownView::default(),
         }
     }
 
+    /// Toggle between the plan and a diff from its newest revision.
+    pub fn toggle_diff(&mut self) {
+        if self.diff_rev.is_some() || self.revisions.is_empty() {
+            self.diff_rev = None;
+            self.diff.clear();
+            self.scroll = 0;
+        } else {
+            self.show_rev(self.revisions.len() - 1);
+        }
+    }
+
+    /// Step the diff base to an older (`-1`) or newer (`1`) revision.
+    pub fn step_rev(&mut self, delta: isize) {
+        if let Some(idx) = self
+            .diff_rev
+            .and_then(|i| i.checked_add_signed(delta))
+            .filter(|i| *i < self.revisions.len())
+        {
+            self.show_rev(idx);
+        }
+    }
+
+    fn show_rev(&mut self, idx: usize) {
+        let old = self.revisions[idx].load().unwrap_or_default();
+        self.diff = revisions::hunks(
+            &revisions::diff_lines(&old, &self.content),
+            revisions::CONTEXT,
+        );
+        self.diff_rev = Some(idx);
+        self.scroll = 0;
+    }
+
     pub fn scroll_down(&mut self) {
         self.scroll = self.scroll.saturating_add(1);
     }
@@ -48,37 +93,105 @@ impl PlanDetailState {
     pub fn page_up(&mut self, page: u16) {
         self.scroll = self.scroll.saturating_sub(page);
     }
+
+    pub fn next_heading(&mut self) {
+        if let Some(row) = self.view.next(self.scroll) {
+            self.scroll = row;
+        }
+    }
+
+    pub fn prev_heading(&mut self) {
+        if let Some(row) = self.view.prev(self.scroll) {
+            self.scroll = row;
+        }
+    }
+}
+
+fn diff_lines(lines: &mut Vec<Line>, state: &PlanDetailState, rev: &Revision) {
+    lines.push(Line::from(vec![
+        Span::raw("  "),
+        Span::styled(
+            format!("Revision {} ({}) → current", rev.number, rev.ts),
+            theme::section_style(),
+        ),
+        Span::styled("   [ / ] older / newer", theme::muted_style()),
+    ]));
+    lines.push(Line::raw(""));
+    if state.diff.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No changes.",
+            theme::muted_style(),
+        )));
+    }
+    for hunk in &state.diff {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", hunk.header),
+            Style::default().fg(theme::LAVENDER),
+        )));
+        for l in &hunk.lines {
+            let (prefix, text, style) = match l {
+                DiffLine::Context(t) => (' ', t, theme::value_style()),
+                DiffLine::Added(t) => ('+', t, Style::default().fg(theme::GREEN)),
+                DiffLine::Removed(t) => ('-', t, Style::default().fg(theme::RED)),
+            };
+            lines.push(Line::from(Span::styled(format!("  {prefix}{text}"), style)));
+        }
+    }
 }
 
-pub fn render_plan_detail(f: &mut Frame, area: Rect, state: &PlanDetailState) {
+pub fn render_plan_detail(f: &mut Frame, area: Rect, state: &mut PlanDetailState) {
+    let (area, outline) = if state.diff_rev.is_some() {
+        (area, None)
+    } else {
+        state.view.layout(area)
+    };
     let mut lines: Vec<Line> = Vec::new();
     let p = &state.plan;
 
     lines.push(Line::raw(""));
 
-    let field = |name: &str, value: &str, style: Style| -> Line {
-        Line::from(vec![
-            Span::raw("  "),
-            Span::styled(format!("{name:>12} "), theme::label_style()),
-            Span::styled(value.to_string(), style),
-        ])
+    let width = area.width;
+    let field = |name: &str, value: &str, style: Style| {
+        markdown::hang(
+            vec![
+                Span::raw("  "),
+                Span::styled(format!("{name:>12} "), theme::label_style()),
+            ],
+            vec![Span::styled(value.to_string(), style)],
+            width,
+        )
     };
 
     if !p.project.is_empty() {
-        lines.push(field(
+        lines.extend(field(
             "Project",
             &planfile::project_name(&p.project),
             theme::value_style(),
         ));
     }
     if !p.title.is_empty() {
-        lines.push(field("Title", &p.title, theme::value_style()));
+        lines.extend(field("Title", &p.title, theme::value_style()));
+    }
+    lines.extend(field(
+        "Status",
+        p.status.as_str(),
+        theme::lifecycle_style(p.status),
+    ));
+    if !p.spec.is_empty() {
+        lines.extend(field("Spec", &p.spec, theme::value_style()));
     }
-    lines.push(field(
+    lines.extend(field(
         "Date",
         &plan::format_date(p.mod_time),
         theme::muted_style(),
     ));
+    if !state.revisions.is_empty() {
+        lines.extend(field(
+            "Revisions",
+            &format!("{}  (d to diff)", state.revisions.len()),
+            theme::muted_style(),
+        ));
+    }
 
     lines.push(Line::raw(""));
     lines.push(Line::from(vec![
@@ -87,8 +200,15 @@ pub fn render_plan_detail(f: &mut Frame, area: Rect, state: &PlanDetailState) {
     ]));
     lines.push(Line::raw(""));
 
-    for l in state.content.lines() {
-        lines.push(Line::from(vec![Span::raw(format!("  {l}"))]));
+    match state.diff_rev.map(|i| &state.revisions[i]) {
+        Some(rev) => {
+            diff_lines(&mut lines, state, rev);
+            state.view.headings.clear();
+        }
+        None => {
+            let body = state.view.lines(&state.content, area.width, lines.len());
+            lines.extend(body);
+        }
     }
 
     if !state.linked_tasks.is_empty() {
@@ -104,26 +224,34 @@ pub fn render_plan_detail(f: &mut Frame, area: Rect, state: &PlanDetailState) {
         lines.push(Line::raw(""));
 
         for t in &state.linked_tasks {
-            lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled(
-                    format!("#{:<6}", t.id),
-                    Style::default().fg(theme::LAVENDER),
-                ),
-                Span::styled(
-                    format!("{:<12}", t.status.as_str()),
-                    theme::status_style(&t.status),
-                ),
-                Span::styled(&t.subject, theme::value_style()),
-            ]));
+            lines.extend(markdown::hang(
+                vec![
+                    Span::raw("  "),
+                    Span::styled(
+                        format!("#{:<6}", t.id),
+                        Style::default().fg(theme::LAVENDER),
+                    ),
+                    Span::styled(
+                        format!("{:<12}", t.status.as_str()),
+                        theme::status_style(&t.status),
+                    ),
+                ],
+                vec![Span::styled(&t.subject, theme::value_style())],
+                width,
+            ));
         }
     }
 
-    let paragraph = Paragraph::new(lines)
-        .wrap(Wrap { trim: false })
-        .scroll((state.scroll, 0));
-
+    // Rendered markdown and the rows around it are wrapped already; wrapping
+    // again would shift the heading rows the outline jumps to
+    let mut paragraph = Paragraph::new(lines).scroll((state.scroll, 0));
+    if state.view.raw || state.diff_rev.is_some() {
+        paragraph = paragraph.wrap(Wrap { trim: false });
+    }
     f.render_widget(paragraph, area);
+    if let Some(side) = outline {
+        markdown::render_outline(f, side, &state.view, state.scroll);
+    }
 }
 
 #[cfg(test)]
@@ -166,6 +294,8 @@ mod tests {
             path: PathBuf::from(path),
             title: "Test Plan".to_string(),
             project: "/Users/me/project".to_string(),
+            spec: String::new(),
+            status: Default::default(),
             mod_time: SystemTime::UNIX_EPOCH,
             size: 100,
         }
@@ -174,7 +304,7 @@ mod tests {
     #[test]
     fn linked_tasks_filters_by_plan_path() {
         let plan = make_plan("/home/user/.claude/plans/my-plan.md");
-        let tasks = vec![
+        let tasks = [
             make_task(
                 "1",
                 "matching",
@@ -208,6 +338,10 @@ mod tests {
                     .collect()
             },
             scroll: 0,
+            revisions: Vec::new(),
+            diff_rev: None,
+            diff: Vec::new(),
+            view: MarkdownView::default(),
         };
 
         assert_eq!(state.linked_tasks.len(), 2);
@@ -218,7 +352,7 @@ mod tests {
     #[test]
     fn linked_tasks_empty_when_no_matches() {
         let plan = make_plan("/home/user/.claude/plans/my-plan.md");
-        let tasks = vec![
+        let tasks = [
             make_task(
                 "1",
                 "different",
@@ -240,6 +374,10 @@ mod tests {
                     .collect()
             },
             scroll: 0,
+            revisions: Vec::new(),
+            diff_rev: None,
+            diff: Vec::new(),
+            view: MarkdownView::default(),
         };
 
         assert!(state.linked_tasks.is_empty());
@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
 
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Rect};
@@ -6,8 +8,11 @@ use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Cell, Row, Table, TableState};
 
+use crate::artifact::Lifecycle;
 use crate::plan::{self, Plan};
 use crate::planfile;
+use crate::progress;
+use crate::store::{self, Task};
 use crate::ui::theme;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -44,7 +49,12 @@ pub struct PlansState {
     pub search_input: String,
     pub source: PlanSource,
     pub project_filter: Option<String>,
+    pub status_filter: Option<Lifecycle>,
     pub link_counts: HashMap<String, usize>,
+    /// Completed/total plan items per plan path, for plans with linked tasks.
+    pub progress: HashMap<String, (usize, usize)>,
+    /// Linked plan contents by path, reread only when the file's mtime moves.
+    contents: HashMap<String, (SystemTime, String)>,
 }
 
 impl PlansState {
@@ -62,10 +72,34 @@ impl PlansState {
             search_input: String::new(),
             source: PlanSource::Active,
             project_filter: None,
+            status_filter: None,
             link_counts,
+            progress: HashMap::new(),
+            contents: HashMap::new(),
         }
     }
 
+    /// Refresh per-plan task counts and progress from the current task list.
+    pub fn set_tasks(&mut self, tasks: &[Task]) {
+        self.link_counts = store::plan_link_counts(tasks);
+        let mut cache = std::mem::take(&mut self.contents);
+        let mut contents = Vec::new();
+        for path in self.link_counts.keys() {
+            let path_buf = PathBuf::from(path);
+            // Git-notes plans have no mtime, so they are always reread
+            let mtime = std::fs::metadata(&path_buf).and_then(|m| m.modified()).ok();
+            let content = match (mtime, cache.remove(path)) {
+                (Some(mtime), Some((cached, content))) if mtime == cached => content,
+                _ => plan::load_content(&path_buf),
+            };
+            if let Some(mtime) = mtime {
+                self.contents.insert(path.clone(), (mtime, content.clone()));
+            }
+            contents.push((path.as_str(), content));
+        }
+        self.progress = progress::plan_counts(contents, tasks);
+    }
+
     pub fn reload_plans(&mut self) {
         let raw = match self.source {
             PlanSource::Active => plan::list_plans(),
@@ -89,14 +123,23 @@ impl PlansState {
         self.reload_plans();
     }
 
+    /// Step the status filter: all, then each lifecycle state in order.
+    pub fn cycle_status(&mut self) {
+        self.status_filter = Lifecycle::next_filter(self.status_filter);
+        self.filter();
+    }
+
     pub fn filter(&mut self) {
+        let status = self.status_filter;
+        let by_status = self
+            .plans
+            .iter()
+            .filter(|p| status.is_none_or(|st| p.status == st));
         if self.query.is_empty() {
-            self.filtered = self.plans.clone();
+            self.filtered = by_status.cloned().collect();
         } else {
             let q = self.query.to_lowercase();
-            self.filtered = self
-                .plans
-                .iter()
+            self.filtered = by_status
                 .filter(|p| {
                     p.title.to_lowercase().contains(&q)
                         || p.name.to_lowercase().contains(&q)
@@ -159,14 +202,31 @@ impl PlansState {
     }
 }
 
+fn progress_cell(counts: Option<&(usize, usize)>) -> Cell<'static> {
+    let Some(&(done, total)) = counts else {
+        return Cell::from(Span::styled("\u{2014}", theme::muted_style()));
+    };
+    let color = if done == total {
+        theme::GREEN
+    } else {
+        theme::ACCENT
+    };
+    Cell::from(Line::from(vec![
+        Span::styled(progress::bar(done, total, 6), Style::default().fg(color)),
+        Span::styled(format!(" {done}/{total}"), theme::muted_style()),
+    ]))
+}
+
 pub fn render_plans(f: &mut Frame, area: Rect, state: &mut PlansState) {
-    let header = Row::new(vec!["Project", "Date", "Size", "Tasks", "Title"])
-        .style(
-            Style::default()
-                .fg(theme::SUBTEXT)
-                .add_modifier(Modifier::BOLD),
-        )
-        .bottom_margin(0);
+    let header = Row::new(vec![
+        "Project", "Date", "Size", "Tasks", "Progress", "Status", "Title",
+    ])
+    .style(
+        Style::default()
+            .fg(theme::SUBTEXT)
+            .add_modifier(Modifier::BOLD),
+    )
+    .bottom_margin(0);
 
     let rows: Vec<Row> = state
         .filtered
@@ -200,6 +260,11 @@ pub fn render_plans(f: &mut Frame, area: Rect, state: &mut PlansState) {
                     theme::muted_style(),
                 )),
                 Cell::from(Span::styled(task_label, theme::muted_style())),
+                progress_cell(state.progress.get(path_key.as_ref())),
+                Cell::from(Span::styled(
+                    p.status.as_str(),
+                    theme::lifecycle_style(p.status),
+                )),
                 Cell::from(Span::raw(title)),
             ])
         })
@@ -210,6 +275,8 @@ pub fn render_plans(f: &mut Frame, area: Rect, state: &mut PlansState) {
         Constraint::Length(12),
         Constraint::Length(6),
         Constraint::Length(6),
+        Constraint::Length(12),
+        Constraint::Length(11),
         Constraint::Fill(1),
     ];
 
@@ -253,6 +320,14 @@ pub fn render_plans_filter_bar(f: &mut Frame, area: Rect, state: &PlansState) {
         spans.push(Span::raw(" "));
     }
 
+    if let Some(status) = state.status_filter {
+        spans.push(Span::styled(
+            format!(" status:{} ", status.as_str()),
+            theme::filter_tag_style(),
+        ));
+        spans.push(Span::raw(" "));
+    }
+
     spans.push(Span::styled(
         format!("{} plans", state.filtered.len()),
         theme::muted_style(),
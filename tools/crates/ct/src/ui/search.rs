@@ -0,0 +1,177 @@
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+
+use crate::planfile;
+use crate::search::{self, Doc, Hit, Kind};
+use crate::ui::theme;
+
+/// Results shown at most; refine the query to see past them.
+const MAX_HITS: usize = 200;
+
+/// Rows each hit takes: title line and snippet line.
+const HIT_ROWS: usize = 2;
+
+pub struct SearchState {
+    pub docs: Vec<Doc>,
+    pub input: String,
+    pub hits: Vec<Hit>,
+    pub cursor: usize,
+}
+
+impl SearchState {
+    pub fn new(docs: Vec<Doc>) -> Self {
+        Self {
+            docs,
+            input: String::new(),
+            hits: Vec::new(),
+            cursor: 0,
+        }
+    }
+
+    pub fn update(&mut self) {
+        self.hits = search::search(&self.docs, &self.input);
+        self.hits.truncate(MAX_HITS);
+        self.cursor = self.cursor.min(self.hits.len().saturating_sub(1));
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.input.push(c);
+        self.cursor = 0;
+        self.update();
+    }
+
+    pub fn pop(&mut self) {
+        self.input.pop();
+        self.cursor = 0;
+        self.update();
+    }
+
+    pub fn next(&mut self) {
+        if self.cursor + 1 < self.hits.len() {
+            self.cursor += 1;
+        }
+    }
+
+    pub fn prev(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn selected(&self) -> Option<&Hit> {
+        self.hits.get(self.cursor)
+    }
+}
+
+fn kind_color(kind: Kind) -> ratatui::style::Color {
+    match kind {
+        Kind::Task => theme::ACCENT,
+        Kind::Plan => theme::LAVENDER,
+        Kind::Spec => theme::GREEN,
+    }
+}
+
+/// `text` with the query terms emphasised.
+fn highlighted(text: &str, terms: &[String], base: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    for (s, e) in search::match_ranges(text, terms) {
+        if s > pos {
+            spans.push(Span::styled(text[pos..s].to_string(), base));
+        }
+        spans.push(Span::styled(
+            text[s..e].to_string(),
+            base.fg(theme::YELLOW).add_modifier(Modifier::BOLD),
+        ));
+        pos = e;
+    }
+    if pos < text.len() {
+        spans.push(Span::styled(text[pos..].to_string(), base));
+    }
+    spans
+}
+
+pub fn render_search(f: &mut Frame, area: Rect, state: &SearchState) {
+    let mut lines = vec![
+        Line::raw(""),
+        Line::from(vec![
+            Span::raw("  "),
+            Span::styled("/", Style::default().fg(theme::ACCENT)),
+            Span::raw(state.input.clone()),
+            Span::styled("█", Style::default().fg(theme::ACCENT)),
+            Span::raw("  "),
+            Span::styled(
+                format!("{} results", state.hits.len()),
+                theme::muted_style(),
+            ),
+        ]),
+        Line::raw(""),
+    ];
+
+    if state.hits.is_empty() {
+        let hint = if state.input.trim().is_empty() {
+            "Type to search tasks, plans and specs"
+        } else {
+            "No matches"
+        };
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled(hint, theme::muted_style()),
+        ]));
+        f.render_widget(Paragraph::new(lines), area);
+        return;
+    }
+
+    // Scroll so the cursor stays in view
+    let visible = (area.height as usize).saturating_sub(lines.len()) / HIT_ROWS;
+    let start = (state.cursor + 1).saturating_sub(visible.max(1));
+    let terms = search::terms(&state.input);
+
+    for (i, hit) in state
+        .hits
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(visible.max(1))
+    {
+        let d = &hit.doc;
+        let selected = i == state.cursor;
+        let indicator = if selected { "▸ " } else { "  " };
+        let title_style = if selected {
+            theme::selected_style()
+        } else {
+            theme::value_style()
+        };
+        let scope = match d.kind {
+            Kind::Task => format!("#{} · {}", d.key, d.scope),
+            _ => planfile::project_name(&d.scope),
+        };
+
+        let mut title = vec![
+            Span::raw(indicator),
+            Span::styled(
+                format!("{:<5}", d.kind.as_str()),
+                Style::default().fg(kind_color(d.kind)),
+            ),
+        ];
+        title.extend(highlighted(&d.title, &terms, title_style));
+        title.push(Span::styled(
+            format!("  {scope} · {}", d.source.label()),
+            theme::muted_style(),
+        ));
+        lines.push(Line::from(title));
+
+        let mut snippet = vec![Span::raw("       ")];
+        if hit.line > 0 {
+            snippet.push(Span::styled(
+                format!("{}: ", hit.line),
+                theme::muted_style(),
+            ));
+        }
+        snippet.extend(highlighted(&hit.snippet, &terms, theme::muted_style()));
+        lines.push(Line::from(snippet));
+    }
+
+    f.render_widget(Paragraph::new(lines), area);
+}
@@ -8,29 +8,29 @@ use crate::plan;
 use crate::planfile;
 use crate::spec::{self, Spec};
 use crate::store::Task;
+use crate::trace::{self, Row, SpecTrace};
+use crate::ui::markdown::{self, MarkdownView};
 use crate::ui::theme;
 
 pub struct SpecDetailState {
     pub spec: Spec,
     pub content: String,
-    pub linked_tasks: Vec<Task>,
+    /// Plans derived from the spec and the tasks under them.
+    pub trace: SpecTrace,
     pub scroll: u16,
+    pub view: MarkdownView,
 }
 
 impl SpecDetailState {
     pub fn new(spec: Spec, tasks: &[Task]) -> Self {
         let content = spec::load_content(&spec.path);
-        let spec_path = spec.path.to_string_lossy();
-        let linked_tasks: Vec<Task> = tasks
-            .iter()
-            .filter(|t| !t.spec_file.is_empty() && t.spec_file == spec_path)
-            .cloned()
-            .collect();
+        let trace = trace::trace(&spec, &spec::list_specs(), &plan::list_plans(), tasks);
         Self {
             spec,
             content,
-            linked_tasks,
+            trace,
             scroll: 0,
+            view: MarkdownView::default(),
         }
     }
 
@@ -49,33 +49,109 @@ impl SpecDetailState {
     pub fn page_up(&mut self, page: u16) {
         self.scroll = self.scroll.saturating_sub(page);
     }
+
+    pub fn next_heading(&mut self) {
+        if let Some(row) = self.view.next(self.scroll) {
+            self.scroll = row;
+        }
+    }
+
+    pub fn prev_heading(&mut self) {
+        if let Some(row) = self.view.prev(self.scroll) {
+            self.scroll = row;
+        }
+    }
 }
 
-pub fn render_spec_detail(f: &mut Frame, area: Rect, state: &SpecDetailState) {
+/// The spec → plans → tasks tree, matching `ct spec trace`, wrapped to
+/// `width`.
+fn trace_lines(lines: &mut Vec<Line>, t: &SpecTrace, width: u16) {
+    let mut head = vec![
+        Span::styled(t.spec.title.as_str(), theme::value_style()),
+        Span::raw(" "),
+        Span::styled(
+            t.spec.status.as_str(),
+            theme::lifecycle_style(t.spec.status),
+        ),
+    ];
+    if t.plans.is_empty() {
+        head.push(Span::styled("  no plan", Style::default().fg(theme::RED)));
+    }
+    lines.extend(markdown::hang(vec![Span::raw("  ")], head, width));
+
+    let joint = |prefix: String| Span::styled(format!("  {prefix}"), theme::muted_style());
+    for row in t.rows() {
+        let (prefix, text) = match row {
+            Row::Plan(prefix, node) => {
+                let p = &node.plan;
+                let mut spans = vec![
+                    Span::styled(p.title.as_str(), theme::label_style()),
+                    Span::raw(" "),
+                    Span::styled(p.status.as_str(), theme::lifecycle_style(p.status)),
+                ];
+                if node.inferred {
+                    spans.push(Span::styled("  (via tasks)", theme::muted_style()));
+                }
+                (vec![joint(prefix)], spans)
+            }
+            Row::Task(prefix, task) => (
+                vec![
+                    joint(prefix),
+                    Span::styled(
+                        format!("#{:<6}", task.id),
+                        Style::default().fg(theme::LAVENDER),
+                    ),
+                    Span::styled(
+                        format!("{:<12}", task.status.as_str()),
+                        theme::status_style(&task.status),
+                    ),
+                ],
+                vec![Span::styled(task.subject.as_str(), theme::value_style())],
+            ),
+            Row::Unplanned(prefix) => (
+                vec![joint(prefix)],
+                vec![Span::styled("tasks without a plan", theme::muted_style())],
+            ),
+        };
+        lines.extend(markdown::hang(prefix, text, width));
+    }
+}
+
+pub fn render_spec_detail(f: &mut Frame, area: Rect, state: &mut SpecDetailState) {
+    let (area, outline) = state.view.layout(area);
     let mut lines: Vec<Line> = Vec::new();
     let s = &state.spec;
 
     lines.push(Line::raw(""));
 
-    let field = |name: &str, value: &str, style: Style| -> Line {
-        Line::from(vec![
-            Span::raw("  "),
-            Span::styled(format!("{name:>12} "), theme::label_style()),
-            Span::styled(value.to_string(), style),
-        ])
+    let width = area.width;
+    let field = |name: &str, value: &str, style: Style| {
+        markdown::hang(
+            vec![
+                Span::raw("  "),
+                Span::styled(format!("{name:>12} "), theme::label_style()),
+            ],
+            vec![Span::styled(value.to_string(), style)],
+            width,
+        )
     };
 
     if !s.project.is_empty() {
-        lines.push(field(
+        lines.extend(field(
             "Project",
             &planfile::project_name(&s.project),
             theme::value_style(),
         ));
     }
     if !s.title.is_empty() {
-        lines.push(field("Topic", &s.title, theme::value_style()));
+        lines.extend(field("Topic", &s.title, theme::value_style()));
     }
-    lines.push(field(
+    lines.extend(field(
+        "Status",
+        s.status.as_str(),
+        theme::lifecycle_style(s.status),
+    ));
+    lines.extend(field(
         "Date",
         &plan::format_date(s.mod_time),
         theme::muted_style(),
@@ -88,43 +164,30 @@ pub fn render_spec_detail(f: &mut Frame, area: Rect, state: &SpecDetailState) {
     ]));
     lines.push(Line::raw(""));
 
-    for l in state.content.lines() {
-        lines.push(Line::from(vec![Span::raw(format!("  {l}"))]));
-    }
+    let body = state.view.lines(&state.content, area.width, lines.len());
+    lines.extend(body);
 
-    if !state.linked_tasks.is_empty() {
-        lines.push(Line::raw(""));
-        lines.push(Line::from(vec![
-            Span::raw("  "),
-            Span::styled("Linked Tasks", theme::section_style()),
-        ]));
-        lines.push(Line::from(vec![
-            Span::raw("  "),
-            Span::styled("─".repeat(60), Style::default().fg(theme::OVERLAY)),
-        ]));
-        lines.push(Line::raw(""));
+    lines.push(Line::raw(""));
+    lines.push(Line::from(vec![
+        Span::raw("  "),
+        Span::styled("Trace", theme::section_style()),
+    ]));
+    lines.push(Line::from(vec![
+        Span::raw("  "),
+        Span::styled("─".repeat(60), Style::default().fg(theme::OVERLAY)),
+    ]));
+    lines.push(Line::raw(""));
+    trace_lines(&mut lines, &state.trace, width);
 
-        for t in &state.linked_tasks {
-            lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled(
-                    format!("#{:<6}", t.id),
-                    Style::default().fg(theme::LAVENDER),
-                ),
-                Span::styled(
-                    format!("{:<12}", t.status.as_str()),
-                    theme::status_style(&t.status),
-                ),
-                Span::styled(&t.subject, theme::value_style()),
-            ]));
-        }
+    // Rendered markdown is wrapped already; see `render_plan_detail`
+    let mut paragraph = Paragraph::new(lines).scroll((state.scroll, 0));
+    if state.view.raw {
+        paragraph = paragraph.wrap(Wrap { trim: false });
     }
-
-    let paragraph = Paragraph::new(lines)
-        .wrap(Wrap { trim: false })
-        .scroll((state.scroll, 0));
-
     f.render_widget(paragraph, area);
+    if let Some(side) = outline {
+        markdown::render_outline(f, side, &state.view, state.scroll);
+    }
 }
 
 #[cfg(test)]
@@ -167,6 +230,7 @@ mod tests {
             path: PathBuf::from(path),
             title: "Test Spec".to_string(),
             project: "/Users/me/project".to_string(),
+            status: Default::default(),
             mod_time: SystemTime::UNIX_EPOCH,
             size: 100,
         }
@@ -175,7 +239,7 @@ mod tests {
     #[test]
     fn linked_tasks_filters_by_spec_path() {
         let spec = make_spec("/home/user/.claude/specs/my-spec.md");
-        let tasks = vec![
+        let tasks = [
             make_task(
                 "1",
                 "matching",
@@ -197,29 +261,17 @@ mod tests {
             ),
         ];
 
-        let state = SpecDetailState {
-            spec,
-            content: String::new(),
-            linked_tasks: {
-                let spec_path = "/home/user/.claude/specs/my-spec.md";
-                tasks
-                    .iter()
-                    .filter(|t| !t.spec_file.is_empty() && t.spec_file == spec_path)
-                    .cloned()
-                    .collect()
-            },
-            scroll: 0,
-        };
+        let t = trace::trace(&spec, &[], &[], &tasks);
 
-        assert_eq!(state.linked_tasks.len(), 2);
-        assert_eq!(state.linked_tasks[0].id, "1");
-        assert_eq!(state.linked_tasks[1].id, "4");
+        assert_eq!(t.tasks.len(), 2);
+        assert_eq!(t.tasks[0].id, "1");
+        assert_eq!(t.tasks[1].id, "4");
     }
 
     #[test]
     fn linked_tasks_empty_when_no_matches() {
         let spec = make_spec("/home/user/.claude/specs/my-spec.md");
-        let tasks = vec![
+        let tasks = [
             make_task(
                 "1",
                 "different",
@@ -229,20 +281,9 @@ mod tests {
             make_task("2", "no spec", "", Status::Completed),
         ];
 
-        let state = SpecDetailState {
-            spec,
-            content: String::new(),
-            linked_tasks: {
-                let spec_path = "/home/user/.claude/specs/my-spec.md";
-                tasks
-                    .iter()
-                    .filter(|t| !t.spec_file.is_empty() && t.spec_file == spec_path)
-                    .cloned()
-                    .collect()
-            },
-            scroll: 0,
-        };
+        let t = trace::trace(&spec, &[], &[], &tasks);
 
-        assert!(state.linked_tasks.is_empty());
+        assert!(t.tasks.is_empty());
+        assert!(t.rows().is_empty());
     }
 }
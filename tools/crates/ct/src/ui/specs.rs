@@ -6,6 +6,7 @@ use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Cell, Row, Table, TableState};
 
+use crate::artifact::Lifecycle;
 use crate::plan;
 use crate::planfile;
 use crate::spec::{self, Spec};
@@ -15,13 +16,15 @@ use crate::ui::theme;
 pub enum SpecSource {
     Active,
     Archived,
+    GitNotes,
 }
 
 impl SpecSource {
     pub fn next(self) -> Self {
         match self {
             Self::Active => Self::Archived,
-            Self::Archived => Self::Active,
+            Self::Archived => Self::GitNotes,
+            Self::GitNotes => Self::Active,
         }
     }
 
@@ -29,6 +32,7 @@ impl SpecSource {
         match self {
             Self::Active => "active",
             Self::Archived => "archived",
+            Self::GitNotes => "git notes",
         }
     }
 }
@@ -41,6 +45,7 @@ pub struct SpecsState {
     pub query: String,
     pub search_input: String,
     pub source: SpecSource,
+    pub status_filter: Option<Lifecycle>,
     pub link_counts: HashMap<String, usize>,
 }
 
@@ -58,6 +63,7 @@ impl SpecsState {
             query: String::new(),
             search_input: String::new(),
             source: SpecSource::Active,
+            status_filter: None,
             link_counts,
         }
     }
@@ -66,6 +72,7 @@ impl SpecsState {
         self.specs = match self.source {
             SpecSource::Active => spec::list_specs(),
             SpecSource::Archived => spec::list_archived_specs(),
+            SpecSource::GitNotes => spec::list_git_notes_specs_all(),
         };
         self.filter();
     }
@@ -75,14 +82,23 @@ impl SpecsState {
         self.reload_specs();
     }
 
+    /// Step the status filter: all, then each lifecycle state in order.
+    pub fn cycle_status(&mut self) {
+        self.status_filter = Lifecycle::next_filter(self.status_filter);
+        self.filter();
+    }
+
     pub fn filter(&mut self) {
+        let status = self.status_filter;
+        let by_status = self
+            .specs
+            .iter()
+            .filter(|s| status.is_none_or(|st| s.status == st));
         if self.query.is_empty() {
-            self.filtered = self.specs.clone();
+            self.filtered = by_status.cloned().collect();
         } else {
             let q = self.query.to_lowercase();
-            self.filtered = self
-                .specs
-                .iter()
+            self.filtered = by_status
                 .filter(|s| {
                     s.title.to_lowercase().contains(&q)
                         || s.name.to_lowercase().contains(&q)
@@ -146,7 +162,7 @@ impl SpecsState {
 }
 
 pub fn render_specs(f: &mut Frame, area: Rect, state: &mut SpecsState) {
-    let header = Row::new(vec!["Project", "Date", "Size", "Tasks", "Title"])
+    let header = Row::new(vec!["Project", "Date", "Size", "Tasks", "Status", "Title"])
         .style(
             Style::default()
                 .fg(theme::SUBTEXT)
@@ -186,6 +202,10 @@ pub fn render_specs(f: &mut Frame, area: Rect, state: &mut SpecsState) {
                     theme::muted_style(),
                 )),
                 Cell::from(Span::styled(task_label, theme::muted_style())),
+                Cell::from(Span::styled(
+                    s.status.as_str(),
+                    theme::lifecycle_style(s.status),
+                )),
                 Cell::from(Span::raw(title)),
             ])
         })
@@ -196,6 +216,7 @@ pub fn render_specs(f: &mut Frame, area: Rect, state: &mut SpecsState) {
         Constraint::Length(12),
         Constraint::Length(6),
         Constraint::Length(6),
+        Constraint::Length(11),
         Constraint::Fill(1),
     ];
 
@@ -231,6 +252,14 @@ pub fn render_specs_filter_bar(f: &mut Frame, area: Rect, state: &SpecsState) {
         spans.push(Span::raw(" "));
     }
 
+    if let Some(status) = state.status_filter {
+        spans.push(Span::styled(
+            format!(" status:{} ", status.as_str()),
+            theme::filter_tag_style(),
+        ));
+        spans.push(Span::raw(" "));
+    }
+
     spans.push(Span::styled(
         format!("{} specs", state.filtered.len()),
         theme::muted_style(),
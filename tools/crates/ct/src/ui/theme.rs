@@ -1,3 +1,4 @@
+use crate::artifact::Lifecycle;
 use crate::store::{Priority, Status};
 use ratatui::style::{Color, Modifier, Style};
 
@@ -27,6 +28,16 @@ pub fn status_style(status: &Status) -> Style {
     }
 }
 
+pub fn lifecycle_style(status: Lifecycle) -> Style {
+    match status {
+        Lifecycle::Draft => Style::default().fg(MUTED),
+        Lifecycle::Approved => Style::default().fg(LAVENDER),
+        Lifecycle::InProgress => Style::default().fg(ACCENT),
+        Lifecycle::Done => Style::default().fg(GREEN),
+        Lifecycle::Superseded => Style::default().fg(OVERLAY),
+    }
+}
+
 pub fn priority_style(priority: &Priority) -> Style {
     match priority {
         Priority::P1 => Style::default().fg(RED),
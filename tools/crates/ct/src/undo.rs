@@ -0,0 +1,303 @@
+use crate::store::{Store, Task};
+
+/// Stack depth; older actions are dropped.
+const MAX_DEPTH: usize = 100;
+
+/// A reversible TUI mutation. Each variant keeps the task snapshots it needs
+/// to detect external edits; a deleted task's JSON waits in the list's
+/// `.trash/` and is restored from there.
+#[derive(Debug, Clone)]
+pub enum Action {
+    Update {
+        list_id: String,
+        before: Box<Task>,
+        after: Box<Task>,
+        label: String,
+    },
+    Create {
+        list_id: String,
+        task: Task,
+    },
+    Delete {
+        list_id: String,
+        task: Task,
+    },
+    Archive {
+        list_id: String,
+        task: Task,
+    },
+    Unarchive {
+        list_id: String,
+        task: Task,
+    },
+}
+
+impl Action {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Update { label, .. } => label.clone(),
+            Self::Create { task, .. } => format!("create #{}", task.id),
+            Self::Delete { task, .. } => format!("delete #{}", task.id),
+            Self::Archive { task, .. } => format!("archive #{}", task.id),
+            Self::Unarchive { task, .. } => format!("restore #{}", task.id),
+        }
+    }
+
+    fn undo(&self, store: &Store) -> Result<(), String> {
+        match self {
+            Self::Update {
+                list_id,
+                before,
+                after,
+                ..
+            } => {
+                ensure_unchanged(store, list_id, after)?;
+                store.save_task(list_id, before)
+            }
+            Self::Create { list_id, task } => {
+                ensure_unchanged(store, list_id, task)?;
+                store.delete_task(list_id, &task.id)
+            }
+            Self::Delete { list_id, task } => {
+                ensure_absent(store, list_id, &task.id)?;
+                store.untrash_task(list_id, &task.id)
+            }
+            Self::Archive { list_id, task } => {
+                // Restore under the same ID so redo archives the right file
+                ensure_absent(store, list_id, &task.id)?;
+                store.unarchive_task(list_id, &task.id).map(|_| ())
+            }
+            Self::Unarchive { list_id, task } => {
+                ensure_unchanged(store, list_id, task)?;
+                store.archive_task(list_id, &task.id)
+            }
+        }
+    }
+
+    fn redo(&self, store: &Store) -> Result<(), String> {
+        match self {
+            Self::Update {
+                list_id,
+                before,
+                after,
+                ..
+            } => {
+                ensure_unchanged(store, list_id, before)?;
+                store.save_task(list_id, after)
+            }
+            Self::Create { list_id, task } => {
+                ensure_absent(store, list_id, &task.id)?;
+                store.save_task(list_id, task)
+            }
+            Self::Delete { list_id, task } => {
+                ensure_unchanged(store, list_id, task)?;
+                store.trash_task(list_id, &task.id)
+            }
+            Self::Archive { list_id, task } => {
+                ensure_unchanged(store, list_id, task)?;
+                store.archive_task(list_id, &task.id)
+            }
+            Self::Unarchive { list_id, task } => {
+                ensure_absent(store, list_id, &task.id)?;
+                store.unarchive_task(list_id, &task.id).map(|_| ())
+            }
+        }
+    }
+}
+
+/// Refuse to overwrite a task that someone else (usually an agent) has
+/// modified since the action was recorded.
+fn ensure_unchanged(store: &Store, list_id: &str, expected: &Task) -> Result<(), String> {
+    match store.load_task(list_id, &expected.id) {
+        Some(current) if current.to_json() == expected.to_json() => Ok(()),
+        Some(_) => Err(format!("#{} was modified since", expected.id)),
+        None => Err(format!("#{} no longer exists", expected.id)),
+    }
+}
+
+fn ensure_absent(store: &Store, list_id: &str, task_id: &str) -> Result<(), String> {
+    match store.load_task(list_id, task_id) {
+        Some(_) => Err(format!("#{task_id} already exists")),
+        None => Ok(()),
+    }
+}
+
+#[derive(Default)]
+pub struct UndoStack {
+    undo: Vec<Action>,
+    redo: Vec<Action>,
+}
+
+impl UndoStack {
+    pub fn push(&mut self, action: Action) {
+        self.undo.push(action);
+        if self.undo.len() > MAX_DEPTH {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Revert the most recent action. Returns `None` when there is nothing to
+    /// undo. A failed action is dropped rather than retried.
+    pub fn undo(&mut self, store: &Store) -> Option<Result<String, String>> {
+        let action = self.undo.pop()?;
+        Some(match action.undo(store) {
+            Ok(()) => {
+                let msg = format!("Undid {}", action.describe());
+                self.redo.push(action);
+                Ok(msg)
+            }
+            Err(e) => Err(format!("Can't undo {}: {e}", action.describe())),
+        })
+    }
+
+    pub fn redo(&mut self, store: &Store) -> Option<Result<String, String>> {
+        let action = self.redo.pop()?;
+        Some(match action.redo(store) {
+            Ok(()) => {
+                let msg = format!("Redid {}", action.describe());
+                self.undo.push(action);
+                Ok(msg)
+            }
+            Err(e) => Err(format!("Can't redo {}: {e}", action.describe())),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Status;
+
+    fn setup() -> (tempfile::TempDir, Store, Task) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("proj")).unwrap();
+        let store = Store::with_base(dir.path().to_path_buf());
+        let task = store
+            .create_task(
+                "proj",
+                &Task::from_raw(serde_json::json!({"subject": "a", "description": "keep me"})),
+            )
+            .unwrap();
+        let task = store.load_task("proj", &task.id).unwrap();
+        (dir, store, task)
+    }
+
+    #[test]
+    fn undo_and_redo_status_change() {
+        let (_dir, store, before) = setup();
+        let mut after = before.clone();
+        after.status = Status::Completed;
+        store.save_task("proj", &after).unwrap();
+        let after = store.load_task("proj", &after.id).unwrap();
+
+        let mut stack = UndoStack::default();
+        stack.push(Action::Update {
+            list_id: "proj".into(),
+            before: Box::new(before.clone()),
+            after: Box::new(after),
+            label: "status → completed on #1".into(),
+        });
+
+        let msg = stack.undo(&store).unwrap().unwrap();
+        assert_eq!(msg, "Undid status → completed on #1");
+        assert_eq!(
+            store.load_task("proj", &before.id).unwrap().status,
+            Status::Pending
+        );
+
+        stack.redo(&store).unwrap().unwrap();
+        assert_eq!(
+            store.load_task("proj", &before.id).unwrap().status,
+            Status::Completed
+        );
+        assert!(stack.redo(&store).is_none());
+    }
+
+    #[test]
+    fn undo_delete_restores_task() {
+        let (dir, store, task) = setup();
+        store.trash_task("proj", &task.id).unwrap();
+        let trashed = dir
+            .path()
+            .join("proj")
+            .join(crate::store::TRASH_DIR)
+            .join(format!("{}.json", task.id));
+        assert!(trashed.exists());
+
+        let mut stack = UndoStack::default();
+        stack.push(Action::Delete {
+            list_id: "proj".into(),
+            task: task.clone(),
+        });
+        stack.undo(&store).unwrap().unwrap();
+
+        let restored = store.load_task("proj", &task.id).unwrap();
+        assert_eq!(restored.description, "keep me");
+        assert!(!trashed.exists());
+
+        stack.redo(&store).unwrap().unwrap();
+        assert!(store.load_task("proj", &task.id).is_none());
+        assert!(trashed.exists());
+    }
+
+    #[test]
+    fn undo_archive_moves_task_back() {
+        let (_dir, store, task) = setup();
+        store.archive_task("proj", &task.id).unwrap();
+
+        let mut stack = UndoStack::default();
+        stack.push(Action::Archive {
+            list_id: "proj".into(),
+            task: task.clone(),
+        });
+        stack.undo(&store).unwrap().unwrap();
+        assert!(store.load_task("proj", &task.id).is_some());
+
+        stack.redo(&store).unwrap().unwrap();
+        assert!(store.load_task("proj", &task.id).is_none());
+    }
+
+    #[test]
+    fn undo_refuses_to_clobber_external_change() {
+        let (_dir, store, before) = setup();
+        let mut after = before.clone();
+        after.subject = "b".into();
+        store.save_task("proj", &after).unwrap();
+
+        let mut stack = UndoStack::default();
+        stack.push(Action::Update {
+            list_id: "proj".into(),
+            before: Box::new(before.clone()),
+            after: Box::new(after.clone()),
+            label: "edit #1".into(),
+        });
+
+        let mut external = after;
+        external.subject = "agent wrote this".into();
+        store.save_task("proj", &external).unwrap();
+
+        assert!(stack.undo(&store).unwrap().is_err());
+        assert_eq!(
+            store.load_task("proj", &before.id).unwrap().subject,
+            "agent wrote this"
+        );
+    }
+
+    #[test]
+    fn new_action_clears_redo() {
+        let (_dir, store, task) = setup();
+        store.archive_task("proj", &task.id).unwrap();
+        let mut stack = UndoStack::default();
+        stack.push(Action::Archive {
+            list_id: "proj".into(),
+            task: task.clone(),
+        });
+        stack.undo(&store).unwrap().unwrap();
+        stack.push(Action::Create {
+            list_id: "proj".into(),
+            task,
+        });
+        assert!(stack.redo(&store).is_none());
+    }
+}
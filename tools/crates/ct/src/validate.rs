@@ -0,0 +1,391 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::store::Task;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueKind {
+    BlockCycle,
+    ParentCycle,
+    SelfBlock,
+    SelfParent,
+    DanglingBlockedBy,
+    DanglingBlocks,
+    DanglingParent,
+    Asymmetric,
+}
+
+impl IssueKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::BlockCycle => "block_cycle",
+            Self::ParentCycle => "parent_cycle",
+            Self::SelfBlock => "self_block",
+            Self::SelfParent => "self_parent",
+            Self::DanglingBlockedBy => "dangling_blocked_by",
+            Self::DanglingBlocks => "dangling_blocks",
+            Self::DanglingParent => "dangling_parent",
+            Self::Asymmetric => "asymmetric_block",
+        }
+    }
+
+    pub fn severity(self) -> Severity {
+        match self {
+            Self::Asymmetric => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub kind: IssueKind,
+    pub task_id: String,
+    pub message: String,
+}
+
+impl Issue {
+    fn new(kind: IssueKind, task_id: &str, message: String) -> Self {
+        Self {
+            kind,
+            task_id: task_id.to_string(),
+            message,
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "severity": self.severity().as_str(),
+            "kind": self.kind.as_str(),
+            "task_id": self.task_id,
+            "message": self.message,
+        })
+    }
+}
+
+/// Check a task list for broken `blocks` / `blocked_by` / `parent_id` links.
+/// Issues are returned grouped by task in list order.
+pub fn validate_tasks(tasks: &[Task]) -> Vec<Issue> {
+    let by_id: HashMap<&str, &Task> = tasks.iter().map(|t| (t.id.as_str(), t)).collect();
+    let mut issues = Vec::new();
+
+    for t in tasks {
+        let id = t.id.as_str();
+
+        if t.parent_id == t.id {
+            issues.push(Issue::new(
+                IssueKind::SelfParent,
+                id,
+                format!("#{id} is its own parent"),
+            ));
+        } else if !t.parent_id.is_empty() && !by_id.contains_key(t.parent_id.as_str()) {
+            issues.push(Issue::new(
+                IssueKind::DanglingParent,
+                id,
+                format!("#{id} has missing parent #{}", t.parent_id),
+            ));
+        }
+
+        for dep in &t.blocked_by {
+            if dep == &t.id {
+                issues.push(Issue::new(
+                    IssueKind::SelfBlock,
+                    id,
+                    format!("#{id} is blocked by itself"),
+                ));
+            } else if let Some(other) = by_id.get(dep.as_str()) {
+                if !other.blocks.contains(&t.id) {
+                    issues.push(Issue::new(
+                        IssueKind::Asymmetric,
+                        id,
+                        format!(
+                            "#{id} is blocked by #{dep}, but #{dep} does not list it in blocks"
+                        ),
+                    ));
+                }
+            } else {
+                issues.push(Issue::new(
+                    IssueKind::DanglingBlockedBy,
+                    id,
+                    format!("#{id} is blocked by missing task #{dep}"),
+                ));
+            }
+        }
+
+        for dep in &t.blocks {
+            if dep == &t.id {
+                issues.push(Issue::new(
+                    IssueKind::SelfBlock,
+                    id,
+                    format!("#{id} blocks itself"),
+                ));
+            } else if let Some(other) = by_id.get(dep.as_str()) {
+                if !other.blocked_by.contains(&t.id) {
+                    issues.push(Issue::new(
+                        IssueKind::Asymmetric,
+                        id,
+                        format!("#{id} blocks #{dep}, but #{dep} does not list it in blocked_by"),
+                    ));
+                }
+            } else {
+                issues.push(Issue::new(
+                    IssueKind::DanglingBlocks,
+                    id,
+                    format!("#{id} blocks missing task #{dep}"),
+                ));
+            }
+        }
+    }
+
+    for cycle in block_cycles(tasks, &by_id) {
+        issues.push(Issue::new(
+            IssueKind::BlockCycle,
+            &cycle[0],
+            format!("dependency cycle: {}", format_cycle(&cycle)),
+        ));
+    }
+    for cycle in parent_cycles(tasks, &by_id) {
+        issues.push(Issue::new(
+            IssueKind::ParentCycle,
+            &cycle[0],
+            format!("parent cycle: {}", format_cycle(&cycle)),
+        ));
+    }
+
+    issues
+}
+
+pub fn has_errors(issues: &[Issue]) -> bool {
+    issues.iter().any(|i| i.severity() == Severity::Error)
+}
+
+fn format_cycle(cycle: &[String]) -> String {
+    let mut parts: Vec<String> = cycle.iter().map(|id| format!("#{id}")).collect();
+    parts.push(format!("#{}", cycle[0]));
+    parts.join(" → ")
+}
+
+/// Rotate a cycle so it starts at its smallest ID, giving one canonical form
+/// per cycle regardless of where the walk entered it.
+fn canonical_cycle(mut cycle: Vec<String>) -> Vec<String> {
+    if let Some(min_idx) = cycle
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, id)| (id.parse::<u64>().unwrap_or(u64::MAX), id.to_string()))
+        .map(|(i, _)| i)
+    {
+        cycle.rotate_left(min_idx);
+    }
+    cycle
+}
+
+/// Cycles in the union of both edge directions: `A.blocked_by ∋ B` and
+/// `B.blocks ∋ A` both mean "A waits on B". Self-loops are reported
+/// separately and skipped here.
+fn block_cycles(tasks: &[Task], by_id: &HashMap<&str, &Task>) -> Vec<Vec<String>> {
+    let mut waits_on: HashMap<&str, Vec<&str>> = HashMap::new();
+    for t in tasks {
+        for dep in &t.blocked_by {
+            if dep != &t.id && by_id.contains_key(dep.as_str()) {
+                waits_on
+                    .entry(t.id.as_str())
+                    .or_default()
+                    .push(dep.as_str());
+            }
+        }
+        for dep in &t.blocks {
+            if dep != &t.id && by_id.contains_key(dep.as_str()) {
+                waits_on
+                    .entry(dep.as_str())
+                    .or_default()
+                    .push(t.id.as_str());
+            }
+        }
+    }
+    for deps in waits_on.values_mut() {
+        deps.sort_unstable();
+        deps.dedup();
+    }
+
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        Visiting,
+        Done,
+    }
+
+    fn visit<'a>(
+        node: &'a str,
+        waits_on: &HashMap<&'a str, Vec<&'a str>>,
+        marks: &mut HashMap<&'a str, Mark>,
+        stack: &mut Vec<&'a str>,
+        found: &mut Vec<Vec<String>>,
+    ) {
+        marks.insert(node, Mark::Visiting);
+        stack.push(node);
+        for &next in waits_on.get(node).map(|v| v.as_slice()).unwrap_or(&[]) {
+            match marks.get(next) {
+                Some(Mark::Visiting) => {
+                    if let Some(start) = stack.iter().position(|&n| n == next) {
+                        found.push(stack[start..].iter().map(|s| s.to_string()).collect());
+                    }
+                }
+                Some(Mark::Done) => {}
+                None => visit(next, waits_on, marks, stack, found),
+            }
+        }
+        stack.pop();
+        marks.insert(node, Mark::Done);
+    }
+
+    let mut marks = HashMap::new();
+    let mut found = Vec::new();
+    for t in tasks {
+        if !marks.contains_key(t.id.as_str()) {
+            visit(
+                t.id.as_str(),
+                &waits_on,
+                &mut marks,
+                &mut Vec::new(),
+                &mut found,
+            );
+        }
+    }
+
+    let mut seen = HashSet::new();
+    found
+        .into_iter()
+        .map(canonical_cycle)
+        .filter(|c| seen.insert(c.clone()))
+        .collect()
+}
+
+fn parent_cycles(tasks: &[Task], by_id: &HashMap<&str, &Task>) -> Vec<Vec<String>> {
+    let mut seen = HashSet::new();
+    let mut cycles = Vec::new();
+    for t in tasks {
+        let mut chain: Vec<&str> = vec![t.id.as_str()];
+        let mut current: &Task = t;
+        while let Some(&parent) = by_id.get(current.parent_id.as_str()) {
+            if parent.id == current.id {
+                break; // self-parent, reported on its own
+            }
+            if let Some(start) = chain.iter().position(|&id| id == parent.id) {
+                let cycle = canonical_cycle(chain[start..].iter().map(|s| s.to_string()).collect());
+                if seen.insert(cycle.clone()) {
+                    cycles.push(cycle);
+                }
+                break;
+            }
+            chain.push(parent.id.as_str());
+            current = parent;
+        }
+    }
+    cycles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(issues: &[Issue]) -> Vec<IssueKind> {
+        issues.iter().map(|i| i.kind).collect()
+    }
+
+    #[test]
+    fn clean_list_has_no_issues() {
+        let tasks = vec![
+            Task::from_raw(serde_json::json!({"id": "1", "blocks": ["2"]})),
+            Task::from_raw(
+                serde_json::json!({"id": "2", "blockedBy": ["1"], "metadata": {"parent_id": "1"}}),
+            ),
+        ];
+        assert!(validate_tasks(&tasks).is_empty());
+    }
+
+    #[test]
+    fn reports_dangling_references() {
+        let tasks = vec![Task::from_raw(serde_json::json!({
+            "id": "1",
+            "blocks": ["7"],
+            "blockedBy": ["8"],
+            "metadata": {"parent_id": "9"}
+        }))];
+        let issues = validate_tasks(&tasks);
+        assert_eq!(
+            kinds(&issues),
+            vec![
+                IssueKind::DanglingParent,
+                IssueKind::DanglingBlockedBy,
+                IssueKind::DanglingBlocks
+            ]
+        );
+        assert!(has_errors(&issues));
+    }
+
+    #[test]
+    fn asymmetric_pair_is_a_warning() {
+        let tasks = vec![
+            Task::from_raw(serde_json::json!({"id": "1"})),
+            Task::from_raw(serde_json::json!({"id": "2", "blockedBy": ["1"]})),
+        ];
+        let issues = validate_tasks(&tasks);
+        assert_eq!(kinds(&issues), vec![IssueKind::Asymmetric]);
+        assert!(!has_errors(&issues));
+    }
+
+    #[test]
+    fn self_parent_and_self_block_reported() {
+        let tasks = vec![Task::from_raw(serde_json::json!({
+            "id": "3",
+            "blockedBy": ["3"],
+            "metadata": {"parent_id": "3"}
+        }))];
+        assert_eq!(
+            kinds(&validate_tasks(&tasks)),
+            vec![IssueKind::SelfParent, IssueKind::SelfBlock]
+        );
+    }
+
+    #[test]
+    fn block_cycle_reported_once() {
+        let tasks = vec![
+            Task::from_raw(serde_json::json!({"id": "1", "blockedBy": ["3"], "blocks": ["2"]})),
+            Task::from_raw(serde_json::json!({"id": "2", "blockedBy": ["1"], "blocks": ["3"]})),
+            Task::from_raw(serde_json::json!({"id": "3", "blockedBy": ["2"], "blocks": ["1"]})),
+        ];
+        let issues = validate_tasks(&tasks);
+        assert_eq!(kinds(&issues), vec![IssueKind::BlockCycle]);
+        assert_eq!(issues[0].task_id, "1");
+        assert!(issues[0].message.contains("#1 → #3 → #2 → #1"));
+    }
+
+    #[test]
+    fn parent_cycle_reported_once() {
+        let tasks = vec![
+            Task::from_raw(serde_json::json!({"id": "4", "metadata": {"parent_id": "5"}})),
+            Task::from_raw(serde_json::json!({"id": "5", "metadata": {"parent_id": "4"}})),
+            Task::from_raw(serde_json::json!({"id": "6", "metadata": {"parent_id": "5"}})),
+        ];
+        let issues = validate_tasks(&tasks);
+        assert_eq!(kinds(&issues), vec![IssueKind::ParentCycle]);
+        assert_eq!(issues[0].task_id, "4");
+    }
+}
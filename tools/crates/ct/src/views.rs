@@ -0,0 +1,161 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_json::Value;
+
+use crate::artifact;
+use crate::store::{SortOrder, StatusFilter};
+
+/// A named task-list filter: the TUI's `f`/`o`/`T`/`A`/`/` state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct View {
+    pub name: String,
+    pub status: StatusFilter,
+    pub sort: SortOrder,
+    pub tree: bool,
+    pub show_closed: bool,
+    pub query: String,
+}
+
+impl View {
+    /// True when the view selects the same tasks in the same layout,
+    /// ignoring its name.
+    pub fn same_filter(&self, other: &View) -> bool {
+        self.status == other.status
+            && self.sort == other.sort
+            && self.tree == other.tree
+            && self.show_closed == other.show_closed
+            && self.query == other.query
+    }
+
+    fn to_json(&self) -> Value {
+        serde_json::json!({
+            "name": self.name,
+            "status": self.status.label(),
+            "sort": self.sort.label(),
+            "tree": self.tree,
+            "show_closed": self.show_closed,
+            "query": self.query,
+        })
+    }
+
+    fn from_json(val: &Value) -> Option<Self> {
+        let s = |key: &str| val.get(key).and_then(|v| v.as_str()).unwrap_or_default();
+        let b = |key: &str| val.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+        let name = s("name");
+        if name.is_empty() {
+            return None;
+        }
+        Some(Self {
+            name: name.to_string(),
+            status: StatusFilter::from_label(s("status")).unwrap_or(StatusFilter::All),
+            sort: SortOrder::from_label(s("sort")).unwrap_or(SortOrder::Id),
+            tree: b("tree"),
+            show_closed: b("show_closed"),
+            query: s("query").to_string(),
+        })
+    }
+}
+
+/// Views file for the project containing `cwd`:
+/// `~/.claude/views/<project>.json`, keyed by git root like plans and specs.
+pub fn project_path(cwd: &str) -> PathBuf {
+    let project = Command::new("git")
+        .args(["-C", cwd, "rev-parse", "--show-toplevel"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_else(|| cwd.to_string());
+    let dir = artifact::artifact_dir(&project, "views");
+    let name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    dir.with_file_name(format!("{name}.json"))
+}
+
+/// Saved views in file order. A missing or malformed file yields none.
+pub fn load(path: &Path) -> Vec<View> {
+    let Ok(data) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let Ok(val) = serde_json::from_str::<Value>(&data) else {
+        return Vec::new();
+    };
+    val.get("views")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(View::from_json).collect())
+        .unwrap_or_default()
+}
+
+/// Insert `view`, replacing any existing view with the same name in place.
+pub fn save(path: &Path, view: &View) -> Result<(), String> {
+    let mut views = load(path);
+    match views.iter_mut().find(|v| v.name == view.name) {
+        Some(existing) => *existing = view.clone(),
+        None => views.push(view.clone()),
+    }
+    write(path, &views)
+}
+
+fn write(path: &Path, views: &[View]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("create views dir: {e}"))?;
+    }
+    let val = serde_json::json!({
+        "views": views.iter().map(View::to_json).collect::<Vec<_>>(),
+    });
+    let data = serde_json::to_string_pretty(&val).map_err(|e| format!("serialize: {e}"))?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, data + "\n").map_err(|e| format!("write views: {e}"))?;
+    fs::rename(&tmp, path).map_err(|e| format!("rename views: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(name: &str, status: StatusFilter) -> View {
+        View {
+            name: name.to_string(),
+            status,
+            sort: SortOrder::Priority,
+            tree: true,
+            show_closed: false,
+            query: "type:bug".to_string(),
+        }
+    }
+
+    #[test]
+    fn save_upserts_by_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("views").join("proj.json");
+        assert!(load(&path).is_empty());
+
+        save(&path, &view("bugs", StatusFilter::Active)).unwrap();
+        save(&path, &view("ready", StatusFilter::Ready)).unwrap();
+        save(&path, &view("bugs", StatusFilter::Pending)).unwrap();
+
+        let views = load(&path);
+        assert_eq!(views.len(), 2);
+        assert_eq!(views[0], view("bugs", StatusFilter::Pending));
+        assert_eq!(views[1].status, StatusFilter::Ready);
+    }
+
+    #[test]
+    fn load_skips_unnamed_and_defaults_unknown_labels() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("proj.json");
+        fs::write(
+            &path,
+            r#"{"views":[{"status":"pending"},{"name":"x","status":"bogus","sort":"nope"}]}"#,
+        )
+        .unwrap();
+        let views = load(&path);
+        assert_eq!(views.len(), 1);
+        assert_eq!(views[0].status, StatusFilter::All);
+        assert_eq!(views[0].sort, SortOrder::Id);
+    }
+}